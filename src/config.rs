@@ -1,17 +1,32 @@
 use crate::dave_hash::*;
+use crate::dave_rate_provider::{ProviderConfig, ProviderKind};
+use crate::dave_statement::{Categorizer, ImportConfig};
+use crate::dave_store::write_atomic;
+use crate::dave_vault::EncryptionConfig;
 use lazy_static::lazy_static;
-use serde_json::json;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::env;
 use std::error::Error;
-use std::fs::{File, OpenOptions};
-use std::path::PathBuf;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::sync::RwLock;
 
 // Default Filename for Configuration File
 pub const CONF_FILE: &str = "dave.conf";
 
-// Default Installation Directory
+// Default Installation Directory When Running From a Checkout
 pub const ROOT_PATH: &str = "./dave_conf";
 
+// Per-Project Config File, Found by Walking Up From the Working Directory
+pub const PROJECT_CONF_FILE: &str = ".dave.conf";
+
+// Environment Variables Starting With This Override Settings
+pub const ENV_PREFIX: &str = "DAVE_";
+
 // Default Database Name
 pub const DB_NAME: &str = "dave_db";
 
@@ -21,12 +36,75 @@ pub const OUTPUT_FILE: &str = "dave.out";
 // Default Location of Budget File
 pub const BUDGET_FILE: &str = "dave.budget";
 
+// Default Location of Exchange Rate Table
+pub const RATES_FILE: &str = "daves_rates.json";
+
+// Default Location of Dated Exchange Rate History
+pub const RATE_HISTORY_FILE: &str = "daves_rate_history.json";
+
+// Default Location of Rates Fetched From a Provider
+pub const RATE_CACHE_FILE: &str = "daves_rates.cache";
+
+// Default Location of the Locked Key Protecting Encrypted Data
+pub const VAULT_KEY_FILE: &str = "daves_vault.key";
+
+// Default Location of Notes Kept by Older Versions
+pub const NOTES_DB: &str = "daves_notes";
+
+// Default Location of Quiz Questions
+pub const QUIZ_DIR: &str = "daves_quiz";
+
+// Default Location of Clustering Results
+pub const MACHINES_DIR: &str = "daves_machines";
+
+// Default Location of CHIP-8 ROMs
+pub const ROMS_DIR: &str = "daves_roms";
+
+// Default Location of Textures, Models, Audio and Fonts
+pub const ASSETS_DIR: &str = "daves_assets";
+
+// Default Location of the Tic Tac Toe AI Log
+pub const AI_LOG_FILE: &str = "dave_ai.log";
+
 lazy_static! {
 	pub static ref CONFIG: RwLock<DaveConfig> = {
-		RwLock::new(DaveConfig::load())
+		RwLock::new(DaveConfig::resolve(&[]).config)
 	};
 }
 
+// Settings That Live Under root_path Unless Something Says Otherwise
+const ROOTED_PATHS: [(&str, &str, &str); 13] = [
+	("database_path", "var", DB_NAME),
+	("output_file", "var", OUTPUT_FILE),
+	("budget_file", "var", BUDGET_FILE),
+	("rates_file", "etc", RATES_FILE),
+	("rate_history_file", "etc", RATE_HISTORY_FILE),
+	("rate_cache_file", "var", RATE_CACHE_FILE),
+	("vault_key_file", "var", VAULT_KEY_FILE),
+	("notes_path", "var", NOTES_DB),
+	("quiz_path", "etc", QUIZ_DIR),
+	("machines_path", "var", MACHINES_DIR),
+	("roms_path", "etc", ROMS_DIR),
+	("assets_path", "etc", ASSETS_DIR),
+	("ai_log_file", "var", AI_LOG_FILE),
+];
+
+// Changing These Without Resealing the Data Would Lock It Away, so Only
+// 'dave config --encrypt' and 'dave config --rekey' Touch Them
+const MANAGED_SETTINGS: [&str; 4] = ["encryption", "encryption.enabled", "encryption.identity", "vault_key_file"];
+
+// All a Project File May Set. Anyone Can Drop a .dave.conf in a Directory,
+// so It Doesn't Get to Move Where Dave Keeps Its Data, Keys or Config
+const PROJECT_SETTINGS: [&str; 7] = [
+	"hash_type",
+	"import",
+	"rate_provider",
+	"rates_file",
+	"quiz_path",
+	"roms_path",
+	"assets_path",
+];
+
 // Default Configuration
 lazy_static! {
 	static ref DEFAULT_CONFIG: serde_json::Value = json!({
@@ -35,34 +113,76 @@ lazy_static! {
 		"database_path": find_database_path(),
 		"output_file": find_output_file(),
 		"budget_file": find_budget_file(),
+		"rates_file": find_rates_file(),
+		"rate_history_file": find_rate_history_file(),
+		"rate_cache_file": find_rate_cache_file(),
+		"vault_key_file": find_vault_key_file(),
+		"notes_path": find_notes_path(),
+		"quiz_path": find_quiz_path(),
+		"machines_path": find_machines_path(),
+		"roms_path": find_roms_path(),
+		"assets_path": find_assets_path(),
+		"ai_log_file": find_ai_log_file(),
 		"hash_type": HashType::Sha256,
 	});
 }
 
-// Determines Appropriate Path to Config File
+fn home_dir() -> Option<PathBuf> {
+	env::var_os("HOME").filter(|home| !home.is_empty()).map(PathBuf::from)
+}
+
+// An XDG Base Directory, or Its Usual Place Under the Home Directory
+fn xdg_dir(variable: &str, fallback: &str) -> Option<PathBuf> {
+	env::var_os(variable)
+		.map(PathBuf::from)
+		.filter(|path| path.is_absolute())
+		.or_else(|| home_dir().map(|home| home.join(fallback)))
+}
+
+// Determines Path to the Per-User Config File
+pub fn user_config_path() -> Option<PathBuf> {
+	xdg_dir("XDG_CONFIG_HOME", ".config").map(|config_home| config_home.join("dave").join(CONF_FILE))
+}
+
+// Determines Appropriate Path to Config File. The Per-User File Is
+// Preferred, Falling Back to One Installed Under the Root
 pub fn find_config_path() -> PathBuf {
-	let mut config_path = PathBuf::new();
-	let dave_home = ROOT_PATH.to_string();
+	let mut installed_path = find_root_path();
+	installed_path.push("etc/");
+	installed_path.push(CONF_FILE);
 
-	config_path.push(dave_home);
-	config_path.push("etc/");
-	config_path.push(CONF_FILE);
-	config_path
+	match user_config_path() {
+		Some(user_path) if user_path.exists() || !installed_path.exists() => user_path,
+		_ => installed_path,
+	}
 }
 
-// Determines Root Path
+// Determines Root Path. A dave_conf Directory Where Dave Is Run Wins so
+// a Checkout Works as Is, Otherwise Data Lives in the XDG Data Directory
 pub fn find_root_path() -> PathBuf {
-	let mut root_path = PathBuf::new();
-	let dave_home = ROOT_PATH.to_string();
+	let local_root = PathBuf::from(ROOT_PATH);
+	if local_root.is_dir() {
+		return local_root
+	}
+	find_install_root()
+}
 
-	root_path.push(dave_home);
-	root_path
+// Determines Where 'dave setup' Installs to by Default
+pub fn find_install_root() -> PathBuf {
+	xdg_dir("XDG_DATA_HOME", ".local/share")
+		.map(|data_home| data_home.join("dave"))
+		.unwrap_or_else(|| PathBuf::from(ROOT_PATH))
+}
+
+// The Nearest Project Config File in the Directory or Any Above It
+pub fn find_project_config(start: &Path) -> Option<PathBuf> {
+	start.ancestors().map(|directory| directory.join(PROJECT_CONF_FILE)).find(|path| path.is_file())
 }
 
 // Determines Path to Database
 pub fn find_database_path() -> PathBuf {
 	let mut db_path = PathBuf::new();
-	let dave_home = ROOT_PATH.to_string();
+	let dave_home = find_root_path();
 
 	db_path.push(dave_home);
 	db_path.push("var/");
@@ -73,7 +193,7 @@ pub fn find_database_path() -> PathBuf {
 // Determines Appropriate Path to Output File
 pub fn find_output_file() -> PathBuf {
 	let mut output_file_path = PathBuf::new();
-	let dave_home = ROOT_PATH.to_string();
+	let dave_home = find_root_path();
 
 	output_file_path.push(dave_home);
 	output_file_path.push("var/");
@@ -84,7 +204,7 @@ pub fn find_output_file() -> PathBuf {
 // Determines Appropriate Path to Budget File
 pub fn find_budget_file() -> PathBuf {
 	let mut output_file_path = PathBuf::new();
-	let dave_home = ROOT_PATH.to_string();
+	let dave_home = find_root_path();
 
 	output_file_path.push(dave_home);
 	output_file_path.push("var/");
@@ -92,6 +212,393 @@ pub fn find_budget_file() -> PathBuf {
 	output_file_path
 }
 
+// Determines Appropriate Path to Exchange Rate Table
+pub fn find_rates_file() -> PathBuf {
+	let mut rates_file_path = PathBuf::new();
+	let dave_home = find_root_path();
+
+	rates_file_path.push(dave_home);
+	rates_file_path.push("etc/");
+	rates_file_path.push(RATES_FILE);
+	rates_file_path
+}
+
+// Determines Appropriate Path to Exchange Rate History
+pub fn find_rate_history_file() -> PathBuf {
+	let mut rate_history_path = PathBuf::new();
+	let dave_home = find_root_path();
+
+	rate_history_path.push(dave_home);
+	rate_history_path.push("etc/");
+	rate_history_path.push(RATE_HISTORY_FILE);
+	rate_history_path
+}
+
+// Determines Appropriate Path to Cached Provider Rates
+pub fn find_rate_cache_file() -> PathBuf {
+	let mut rate_cache_path = PathBuf::new();
+	let dave_home = find_root_path();
+
+	rate_cache_path.push(dave_home);
+	rate_cache_path.push("var/");
+	rate_cache_path.push(RATE_CACHE_FILE);
+	rate_cache_path
+}
+
+// Determines Appropriate Path to the Vault Key
+pub fn find_vault_key_file() -> PathBuf {
+	let mut vault_key_path = PathBuf::new();
+	let dave_home = find_root_path();
+
+	vault_key_path.push(dave_home);
+	vault_key_path.push("var/");
+	vault_key_path.push(VAULT_KEY_FILE);
+	vault_key_path
+}
+
+// Determines Appropriate Path to Notes From Older Versions
+pub fn find_notes_path() -> PathBuf {
+	let mut notes_path = PathBuf::new();
+	let dave_home = find_root_path();
+
+	notes_path.push(dave_home);
+	notes_path.push("var/");
+	notes_path.push(NOTES_DB);
+	notes_path
+}
+
+// Determines Appropriate Path to Quiz Questions
+pub fn find_quiz_path() -> PathBuf {
+	let mut quiz_path = PathBuf::new();
+	let dave_home = find_root_path();
+
+	quiz_path.push(dave_home);
+	quiz_path.push("etc/");
+	quiz_path.push(QUIZ_DIR);
+	quiz_path
+}
+
+// Determines Appropriate Path to Clustering Results
+pub fn find_machines_path() -> PathBuf {
+	let mut machines_path = PathBuf::new();
+	let dave_home = find_root_path();
+
+	machines_path.push(dave_home);
+	machines_path.push("var/");
+	machines_path.push(MACHINES_DIR);
+	machines_path
+}
+
+// Determines Appropriate Path to CHIP-8 ROMs
+pub fn find_roms_path() -> PathBuf {
+	let mut roms_path = PathBuf::new();
+	let dave_home = find_root_path();
+
+	roms_path.push(dave_home);
+	roms_path.push("etc/");
+	roms_path.push(ROMS_DIR);
+	roms_path
+}
+
+// Determines Appropriate Path to Assets
+pub fn find_assets_path() -> PathBuf {
+	let mut assets_path = PathBuf::new();
+	let dave_home = find_root_path();
+
+	assets_path.push(dave_home);
+	assets_path.push("etc/");
+	assets_path.push(ASSETS_DIR);
+	assets_path
+}
+
+// Determines Appropriate Path to the Tic Tac Toe AI Log
+pub fn find_ai_log_file() -> PathBuf {
+	let mut ai_log_path = PathBuf::new();
+	let dave_home = find_root_path();
+
+	ai_log_path.push(dave_home);
+	ai_log_path.push("var/");
+	ai_log_path.push(AI_LOG_FILE);
+	ai_log_path
+}
+
+// Everything That Can Go Wrong Reading or Changing a Setting
+#[derive(Debug)]
+pub enum ConfigError {
+	UnknownKey(String),
+	InvalidValue(String, String),
+	Managed(String),
+	Io(PathBuf, io::Error),
+	Parse(PathBuf, serde_json::Error),
+}
+
+impl fmt::Display for ConfigError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ConfigError::UnknownKey(key) => write!(f, "Unknown Setting '{}'. Run 'dave config list' to See Them All", key),
+			ConfigError::InvalidValue(key, reason) => write!(f, "Invalid Value for '{}': {}", key, reason),
+			ConfigError::Managed(key) => write!(f, "'{}' Can't be Set by Hand. Use 'dave config --encrypt' or 'dave config --rekey'", key),
+			ConfigError::Io(path, error) => write!(f, "{}: {}", path.display(), error),
+			ConfigError::Parse(path, error) => write!(f, "{}: Parse Error: {}", path.display(), error),
+		}
+	}
+}
+
+impl Error for ConfigError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			ConfigError::Io(_, error) => Some(error),
+			ConfigError::Parse(_, error) => Some(error),
+			_ => None,
+		}
+	}
+}
+
+// Follow a Dotted Key Like 'import.csv.delimiter' Into a JSON Value
+fn lookup<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+	key.split('.').try_fold(value, |value, part| value.get(part))
+}
+
+fn lookup_mut<'a>(value: &'a mut Value, key: &str) -> Option<&'a mut Value> {
+	key.split('.').try_fold(value, |value, part| value.get_mut(part))
+}
+
+// Every Leaf Setting Under a Value as Dotted Keys
+fn flatten(prefix: &str, value: &Value, entries: &mut Vec<(String, Value)>) {
+	match value {
+		Value::Object(map) => {
+			for (name, value) in map {
+				let key = if prefix.is_empty() { name.clone() } else { format!("{}.{}", prefix, name) };
+				flatten(&key, value, entries);
+			}
+		},
+		_ => entries.push((prefix.to_string(), value.clone())),
+	}
+}
+
+// Turn Text From the Command Line Into the Same Kind of Value a Setting
+// Already Holds, so 'true' Stays a Bool and '12' Stays a Number
+fn parse_setting(key: &str, current: &Value, raw: &str) -> Result<Value, ConfigError> {
+	let invalid = |reason: &str| ConfigError::InvalidValue(key.to_string(), reason.to_string());
+	match current {
+		Value::String(_) if key == "hash_type" => {
+			let hash_type = HashType::from_str(raw).map_err(|error| invalid(&error))?;
+			serde_json::to_value(hash_type).map_err(|error| invalid(&error.to_string()))
+		},
+		Value::String(_) => Ok(Value::String(raw.to_string())),
+		Value::Bool(_) => raw.parse::<bool>().map(Value::Bool).map_err(|_| invalid("Expected true or false")),
+		Value::Number(number) if number.is_f64() => {
+			raw.parse::<f64>().map(|number| json!(number)).map_err(|_| invalid("Expected a Number"))
+		},
+		Value::Number(_) => raw.parse::<i64>().map(|number| json!(number)).map_err(|_| invalid("Expected a Whole Number")),
+		// Optional Settings Take JSON, or Plain Text as a String
+		Value::Null => Ok(serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))),
+		Value::Array(_) | Value::Object(_) => {
+			serde_json::from_str(raw).map_err(|error| invalid(&format!("Expected JSON: {}", error)))
+		},
+	}
+}
+
+// Where a Setting's Value Came From. Later Layers Win
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConfigSource {
+	Default,
+	UserFile(PathBuf),
+	ProjectFile(PathBuf),
+	Environment(String),
+	Flag,
+}
+
+impl fmt::Display for ConfigSource {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ConfigSource::Default => write!(f, "default"),
+			ConfigSource::UserFile(path) => write!(f, "config file {}", path.display()),
+			ConfigSource::ProjectFile(path) => write!(f, "project file {}", path.display()),
+			ConfigSource::Environment(variable) => write!(f, "environment {}", variable),
+			ConfigSource::Flag => write!(f, "command line --override"),
+		}
+	}
+}
+
+// The Config Built From Every Layer, Which Layer Each Setting Came
+// From, and Anything That Had to be Skipped Along the Way
+pub struct ResolvedConfig {
+	pub config: DaveConfig,
+	pub sources: BTreeMap<String, ConfigSource>,
+	pub warnings: Vec<String>,
+}
+
+impl ResolvedConfig {
+	pub fn source(&self, key: &str) -> &ConfigSource {
+		// A Whole Array or Section Set at Once Is Recorded Under Its Own Key
+		let mut key = key;
+		loop {
+			if let Some(source) = self.sources.get(key) {
+				return source
+			}
+			match key.rsplit_once('.') {
+				Some((parent, _)) => key = parent,
+				None => return &ConfigSource::Default,
+			}
+		}
+	}
+}
+
+fn set_dotted(value: &mut Value, key: &str, new: Value) {
+	let mut current = value;
+	for part in key.split('.') {
+		if !current.is_object() {
+			*current = Value::Object(Map::new());
+		}
+		current = current.as_object_mut().unwrap().entry(part).or_insert(Value::Null);
+	}
+	*current = new;
+}
+
+fn remove_dotted(value: &mut Value, key: &str) {
+	match key.rsplit_once('.') {
+		Some((parent, name)) => {
+			if let Some(Value::Object(map)) = lookup_mut(value, parent) {
+				map.remove(name);
+			}
+		},
+		None => {
+			if let Value::Object(map) = value {
+				map.remove(key);
+			}
+		},
+	}
+}
+
+// Lay One Layer Over Another, Noting Which Settings It Changed
+fn merge(base: &mut Value, layer: &Value, source: &ConfigSource, prefix: &str, sources: &mut BTreeMap<String, ConfigSource>) {
+	match layer {
+		Value::Object(layer) => {
+			if !base.is_object() {
+				*base = Value::Object(Map::new());
+			}
+			let base = base.as_object_mut().unwrap();
+			for (name, value) in layer {
+				let key = if prefix.is_empty() { name.clone() } else { format!("{}.{}", prefix, name) };
+				merge(base.entry(name.clone()).or_insert(Value::Null), value, source, &key, sources);
+			}
+		},
+		_ => {
+			*base = layer.clone();
+			sources.insert(prefix.to_string(), source.clone());
+		},
+	}
+}
+
+fn check_managed(key: &str) -> Result<(), ConfigError> {
+	match MANAGED_SETTINGS.contains(&key) {
+		true => Err(ConfigError::Managed(key.to_string())),
+		false => Ok(()),
+	}
+}
+
+// Drop Anything a Project File Isn't Allowed to Set, Saying What Went
+fn restrict_project(layer: &mut Value, path: &Path, warnings: &mut Vec<String>) {
+	if let Value::Object(map) = layer {
+		map.retain(|key, _| {
+			let allowed = PROJECT_SETTINGS.contains(&key.as_str());
+			if !allowed {
+				warnings.push(format!("Ignoring {} in Project File {}: Only Your Own Config Can Set It", key, path.display()));
+			}
+			allowed
+		});
+	}
+}
+
+// Paths in a Project File Are Relative to the File, Not Wherever Dave Runs
+fn rebase_paths(layer: &mut Value, directory: &Path) {
+	let keys = ROOTED_PATHS.iter().map(|(key, _, _)| *key).chain(["root_path", "config_path", "encryption.identity"]);
+	for key in keys {
+		if let Some(Value::String(path)) = lookup_mut(layer, key) {
+			if Path::new(path.as_str()).is_relative() {
+				*path = directory.join(path.as_str()).display().to_string();
+			}
+		}
+	}
+}
+
+// A Config File as It Is on Disk, Empty if There Isn't One Yet
+fn read_file(path: &Path) -> Result<Value, ConfigError> {
+	match fs::read_to_string(path) {
+		Ok(text) => serde_json::from_str(&text).map_err(|error| ConfigError::Parse(path.to_path_buf(), error)),
+		Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(json!({})),
+		Err(error) => Err(ConfigError::Io(path.to_path_buf(), error)),
+	}
+}
+
+fn read_layer(path: &Path) -> Result<Value, ConfigError> {
+	let text = fs::read_to_string(path).map_err(|error| ConfigError::Io(path.to_path_buf(), error))?;
+	let layer: Value = serde_json::from_str(&text).map_err(|error| ConfigError::Parse(path.to_path_buf(), error))?;
+	if !layer.is_object() {
+		return Err(ConfigError::InvalidValue(path.display().to_string(), "Expected a JSON Object".to_string()))
+	}
+	Ok(layer)
+}
+
+// Defaults With Every Rooted Path Placed Under the Given Root
+fn defaults_for_root(root: &Path) -> Value {
+	let mut defaults = DEFAULT_CONFIG.clone();
+	defaults["root_path"] = json!(root);
+	for (key, directory, name) in ROOTED_PATHS {
+		defaults[key] = json!(root.join(directory).join(name));
+	}
+	defaults
+}
+
+// Build the Full Config From the Layers Set So Far. Rooted Paths Nobody
+// Set Follow root_path Wherever It Ends Up
+fn build_config(layers: &Value) -> Result<DaveConfig, serde_json::Error> {
+	let root = layers.get("root_path").and_then(Value::as_str).map(PathBuf::from).unwrap_or_else(find_root_path);
+	let mut value = defaults_for_root(&root);
+	merge(&mut value, layers, &ConfigSource::Default, "", &mut BTreeMap::new());
+	serde_json::from_value(value)
+}
+
+// Settings Given as Text, Like Environment Variables, Typed Against the Defaults
+fn text_layer(settings: &[(String, String, ConfigSource)], warnings: &mut Vec<String>) -> Vec<(ConfigSource, Value)> {
+	let defaults = DaveConfig::default().to_value();
+	let mut layers = Vec::new();
+	for (key, raw, source) in settings {
+		let Some(current) = lookup(&defaults, key) else {
+			warnings.push(format!("{}: {}", source, ConfigError::UnknownKey(key.clone())));
+			continue
+		};
+		match parse_setting(key, current, raw) {
+			Ok(value) => {
+				let mut layer = json!({});
+				set_dotted(&mut layer, key, value);
+				layers.push((source.clone(), layer));
+			},
+			Err(error) => warnings.push(format!("{}: {}", source, error)),
+		}
+	}
+	layers
+}
+
+// DAVE_HASH_TYPE Sets hash_type, and a Double Underscore Reaches Into a
+// Section, so DAVE_ENCRYPTION__ENABLED Sets encryption.enabled
+fn environment_settings() -> Vec<(String, String, ConfigSource)> {
+	let defaults = DaveConfig::default().to_value();
+	// vars() Panics on Anything That Isn't UTF-8, and Those Can't be Ours
+	let mut settings: Vec<_> = env::vars_os()
+		.filter_map(|(variable, raw)| {
+			let (variable, raw) = (variable.into_string().ok()?, raw.into_string().ok()?);
+			let key = variable.strip_prefix(ENV_PREFIX)?.to_lowercase().replace("__", ".");
+			// Other DAVE_ Variables Aren't Ours to Complain About
+			lookup(&defaults, &key)?;
+			Some((key, raw, ConfigSource::Environment(variable)))
+		})
+		.collect();
+	settings.sort_by(|a, b| a.0.cmp(&b.0));
+	settings
+}
+
 // Runtime Configuration for Dave
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct DaveConfig {
@@ -105,8 +612,47 @@ pub struct DaveConfig {
 	output_file: PathBuf,
 	// Budget File Path
 	budget_file: PathBuf,
+	// Exchange Rate Table Path
+	#[serde(default = "find_rates_file")]
+	rates_file: PathBuf,
+	// Exchange Rate History Path
+	#[serde(default = "find_rate_history_file")]
+	rate_history_file: PathBuf,
+	// Cached Provider Rates Path
+	#[serde(default = "find_rate_cache_file")]
+	rate_cache_file: PathBuf,
+	// Locked Key for Encrypted Data Path
+	#[serde(default = "find_vault_key_file")]
+	vault_key_file: PathBuf,
+	// Notes Database Kept by Older Versions Path
+	#[serde(default = "find_notes_path")]
+	notes_path: PathBuf,
+	// Quiz Questions Path
+	#[serde(default = "find_quiz_path")]
+	quiz_path: PathBuf,
+	// Clustering Results Path
+	#[serde(default = "find_machines_path")]
+	machines_path: PathBuf,
+	// CHIP-8 ROMs Path
+	#[serde(default = "find_roms_path")]
+	roms_path: PathBuf,
+	// Textures, Models, Audio and Fonts Path
+	#[serde(default = "find_assets_path")]
+	assets_path: PathBuf,
+	// Tic Tac Toe AI Log Path
+	#[serde(default = "find_ai_log_file")]
+	ai_log_file: PathBuf,
 	// What Hashing Algorithm Dave Will Use
 	pub hash_type: HashType,
+	// How Bank Statements Are Read Into the Budget
+	#[serde(default)]
+	pub import: ImportConfig,
+	// Where Fresh Exchange Rates Are Fetched From
+	#[serde(default)]
+	pub rate_provider: ProviderConfig,
+	// Whether the Budget and Notes Are Encrypted at Rest
+	#[serde(default)]
+	pub encryption: EncryptionConfig,
 }
 
 impl Default for DaveConfig {
@@ -118,38 +664,221 @@ impl Default for DaveConfig {
 
 impl DaveConfig {
 	pub fn load() -> Self {
+		Self::load_with(&[])
+	}
+
+	// Load Every Layer, Warning About Any That Couldn't be Used
+	pub fn load_with(overrides: &[(String, String)]) -> Self {
+		let resolved = Self::resolve(overrides);
+		for warning in &resolved.warnings {
+			eprintln!("##==>> Warning! {}", warning);
+		}
+		if !resolved.warnings.is_empty() {
+			eprintln!();
+		}
+		resolved.config
+	}
+
+	// Resolve the Config From Built In Defaults, Then the User's Config
+	// File, Then a .dave.conf Above the Working Directory, Then DAVE_*
+	// Environment Variables, Then key=value Overrides From the Command Line
+	pub fn resolve(overrides: &[(String, String)]) -> ResolvedConfig {
+		let mut warnings = Vec::new();
+		let mut layers = Vec::new();
+
 		let config_path = find_config_path();
+		if config_path.exists() {
+			match read_layer(&config_path) {
+				Ok(layer) => layers.push((ConfigSource::UserFile(config_path), layer)),
+				Err(error) => warnings.push(error.to_string()),
+			}
+		}
+		if let Some(project_path) = env::current_dir().ok().and_then(|cwd| find_project_config(&cwd)) {
+			match read_layer(&project_path) {
+				Ok(mut layer) => {
+					restrict_project(&mut layer, &project_path, &mut warnings);
+					rebase_paths(&mut layer, project_path.parent().unwrap_or(Path::new(".")));
+					layers.push((ConfigSource::ProjectFile(project_path), layer));
+				},
+				Err(error) => warnings.push(error.to_string()),
+			}
+		}
+		layers.extend(text_layer(&environment_settings(), &mut warnings));
+		let overrides: Vec<_> = overrides
+			.iter()
+			.map(|(key, raw)| (key.clone(), raw.clone(), ConfigSource::Flag))
+			.collect();
+		layers.extend(text_layer(&overrides, &mut warnings));
 
-		let file = match File::open(&config_path) {
-			Ok(file) => file,
-			Err(error) => {
-				println!("##==>> Warning! Config File Not Found, Using Defaults");
-				println!("##==>> Warning! {}: {}\n", config_path.display(), error);
-				return Self::default()
+		// A Layer That Would Leave the Config Unreadable Is Skipped Whole
+		let mut merged = json!({});
+		let mut sources = BTreeMap::new();
+		for (source, layer) in layers {
+			let mut candidate = merged.clone();
+			let mut candidate_sources = sources.clone();
+			merge(&mut candidate, &layer, &source, "", &mut candidate_sources);
+			match build_config(&candidate) {
+				Ok(_) => {
+					merged = candidate;
+					sources = candidate_sources;
+				},
+				Err(error) => warnings.push(format!("Ignoring {}: {}", source, error)),
 			}
-		};
+		}
+		let config = build_config(&merged).unwrap_or_default();
+		ResolvedConfig { config, sources, warnings }
+	}
+
+	// Change One Setting in a Config File, Leaving the Rest of It Alone.
+	// None Removes the Setting so Lower Layers Show Through Again
+	pub fn write_setting(path: &Path, key: &str, value: Option<Value>) -> Result<(), ConfigError> {
+		Self::write_settings(path, &[(key, value)])
+	}
 
-		match serde_json::from_reader(file) {
-			Ok(deserialized) => deserialized,
-			Err(error) => {
-				println!("##==>> Warning! Failed to Parse Config File, Using Defaults");
-				println!("##==>> Warning! {}: Parse Error: {}\n", &config_path.display(), error);
-				Self::default()
+	// Change Several Settings in a Config File in One Write. The File Is
+	// Created if Need Be, Even When There Is Nothing to Change
+	pub fn write_settings(path: &Path, settings: &[(&str, Option<Value>)]) -> Result<(), ConfigError> {
+		let mut file = read_file(path)?;
+		for (key, value) in settings {
+			match value {
+				Some(value) => set_dotted(&mut file, key, value.clone()),
+				None => remove_dotted(&mut file, key),
 			}
 		}
+		let bytes = serde_json::to_vec_pretty(&file).map_err(|error| ConfigError::Parse(path.to_path_buf(), error))?;
+		write_atomic(path, &bytes).map_err(|error| ConfigError::Io(path.to_path_buf(), error))
+	}
+
+	// A Setting as Written in One Config File, if It's There at All
+	pub fn read_setting(path: &Path, key: &str) -> Result<Option<Value>, ConfigError> {
+		Ok(lookup(&read_file(path)?, key).cloned())
+	}
+
+	// Write This Config's Values for Just the Given Keys to Its Own File.
+	// Whatever Else Was Layered In Stays Out of It
+	pub fn save_settings(&self, keys: &[&str]) -> Result<(), ConfigError> {
+		let mut settings = Vec::new();
+		for key in keys {
+			settings.push((*key, Some(self.get(key)?)));
+		}
+		Self::write_settings(&self.config_path, &settings)
+	}
+
+	// Paths a Config File Sets Outright, Which Stay Put When root_path Moves
+	pub fn pinned_paths(path: &Path) -> Result<Vec<String>, ConfigError> {
+		let file = read_file(path)?;
+		let pinned = ROOTED_PATHS.iter().map(|(key, _, _)| *key).filter(|key| lookup(&file, key).is_some());
+		Ok(pinned.map(String::from).collect())
+	}
+
+	// Empty a Config File so Every Setting Falls Back to Its Default
+	pub fn reset_file(path: &Path) -> Result<(), ConfigError> {
+		write_atomic(path, b"{}").map_err(|error| ConfigError::Io(path.to_path_buf(), error))
+	}
+
+	// Defaults for an Installation Rooted Somewhere Else
+	pub fn for_root(root: &Path) -> Self {
+		serde_json::from_value(defaults_for_root(root)).unwrap()
 	}
 
+	// Write the Configuration to Its Own config_path
 	pub fn save(&self) -> Result<(), Box<dyn Error>> {
-		let file = OpenOptions::new()
-			.write(true)
-			.truncate(true)
-			.create(true)
-			.open(find_config_path())?;
+		let bytes = serde_json::to_vec_pretty(self)?;
+		write_atomic(&self.config_path, &bytes)?;
+		Ok(())
+	}
+
+	fn to_value(&self) -> Value {
+		serde_json::to_value(self).unwrap_or(Value::Null)
+	}
+
+	// Every Setting as a Dotted Key and Its Value
+	pub fn entries(&self) -> Vec<(String, Value)> {
+		let mut entries = Vec::new();
+		flatten("", &self.to_value(), &mut entries);
+		entries
+	}
+
+	// Value of a Setting, or a Whole Section Like 'import'
+	pub fn get(&self, key: &str) -> Result<Value, ConfigError> {
+		lookup(&self.to_value(), key).cloned().ok_or_else(|| ConfigError::UnknownKey(key.to_string()))
+	}
 
-		serde_json::to_writer_pretty(file, self)?;
+	fn replace(&mut self, key: &str, new: Value) -> Result<(), ConfigError> {
+		let mut value = self.to_value();
+		*lookup_mut(&mut value, key).ok_or_else(|| ConfigError::UnknownKey(key.to_string()))? = new;
+		*self = serde_json::from_value(value).map_err(|error| ConfigError::InvalidValue(key.to_string(), error.to_string()))?;
 		Ok(())
 	}
 
+	// Change a Setting From Text, Checked Against the Type It Already Has
+	pub fn set(&mut self, key: &str, raw: &str) -> Result<(), ConfigError> {
+		check_managed(key)?;
+		let new = parse_setting(key, &self.get(key)?, raw)?;
+		self.replace(key, new)
+	}
+
+	// Put a Setting Back to Its Default
+	pub fn unset(&mut self, key: &str) -> Result<(), ConfigError> {
+		check_managed(key)?;
+		let default = Self::default().get(key)?;
+		self.replace(key, default)
+	}
+
+	// Problems That Would Stop Some Part of Dave Working, Keyed by Setting
+	pub fn validate(&self) -> Vec<(String, String)> {
+		let mut problems = Vec::new();
+		let directories = [
+			("root_path", self.root_path()),
+			("quiz_path", self.quiz_path()),
+			("roms_path", self.roms_path()),
+			("assets_path", self.assets_path()),
+		];
+		for (key, path) in directories {
+			if !path.is_dir() {
+				problems.push((key.to_string(), format!("Directory {} Does Not Exist", path.display())));
+			}
+		}
+		if !self.rates_path().is_file() {
+			problems.push(("rates_file".to_string(), format!("File {} Does Not Exist", self.rates_path().display())));
+		}
+		if let Err(error) = Categorizer::new(&self.import.rules) {
+			problems.push(("import.rules".to_string(), error.to_string()));
+		}
+		if self.rate_provider.kind != ProviderKind::Offline && self.rate_provider.url.trim().is_empty() {
+			problems.push(("rate_provider.url".to_string(), "A URL Is Needed to Fetch Rates".to_string()));
+		}
+		if self.encryption.enabled {
+			if !self.vault_key_path().is_file() {
+				problems.push(("vault_key_file".to_string(), format!("Key {} Does Not Exist", self.vault_key_path().display())));
+			}
+			if let Some(identity) = self.encryption.identity.as_ref().filter(|identity| !identity.is_file()) {
+				problems.push(("encryption.identity".to_string(), format!("File {} Does Not Exist", identity.display())));
+			}
+		}
+		problems
+	}
+
+	// Check a Config File Strictly: It Must Parse, Every Key Must be One
+	// Dave Knows About, and Every Value Must Have the Right Type
+	pub fn validate_file(path: &Path) -> Result<(Self, Vec<(String, String)>), ConfigError> {
+		let text = fs::read_to_string(path).map_err(|error| ConfigError::Io(path.to_path_buf(), error))?;
+		let value: Value = serde_json::from_str(&text).map_err(|error| ConfigError::Parse(path.to_path_buf(), error))?;
+		let mut problems = Vec::new();
+		let defaults = Self::default().to_value();
+		let mut found = Vec::new();
+		flatten("", &value, &mut found);
+		for (key, _) in found {
+			if lookup(&defaults, &key).is_none() {
+				problems.push((key, "Unknown Setting".to_string()));
+			}
+		}
+		// Settings Left Out Take Their Defaults, With Paths Under root_path
+		let config = build_config(&value).map_err(|error| ConfigError::InvalidValue("config".to_string(), error.to_string()))?;
+		problems.extend(config.validate());
+		Ok((config, problems))
+	}
+
 	pub fn set_config_path(&mut self, path: PathBuf) {
 		self.config_path = path;
 	}
@@ -190,6 +919,86 @@ impl DaveConfig {
 		self.budget_file.clone()
 	}
 
+	pub fn set_rates_path(&mut self, path: PathBuf) {
+		self.rates_file = path;
+	}
+
+	pub fn rates_path(&self) -> PathBuf {
+		self.rates_file.clone()
+	}
+
+	pub fn set_rate_history_path(&mut self, path: PathBuf) {
+		self.rate_history_file = path;
+	}
+
+	pub fn rate_history_path(&self) -> PathBuf {
+		self.rate_history_file.clone()
+	}
+
+	pub fn set_rate_cache_path(&mut self, path: PathBuf) {
+		self.rate_cache_file = path;
+	}
+
+	pub fn rate_cache_path(&self) -> PathBuf {
+		self.rate_cache_file.clone()
+	}
+
+	pub fn set_vault_key_path(&mut self, path: PathBuf) {
+		self.vault_key_file = path;
+	}
+
+	pub fn vault_key_path(&self) -> PathBuf {
+		self.vault_key_file.clone()
+	}
+
+	pub fn set_notes_path(&mut self, path: PathBuf) {
+		self.notes_path = path;
+	}
+
+	pub fn notes_path(&self) -> PathBuf {
+		self.notes_path.clone()
+	}
+
+	pub fn set_quiz_path(&mut self, path: PathBuf) {
+		self.quiz_path = path;
+	}
+
+	pub fn quiz_path(&self) -> PathBuf {
+		self.quiz_path.clone()
+	}
+
+	pub fn set_machines_path(&mut self, path: PathBuf) {
+		self.machines_path = path;
+	}
+
+	pub fn machines_path(&self) -> PathBuf {
+		self.machines_path.clone()
+	}
+
+	pub fn set_roms_path(&mut self, path: PathBuf) {
+		self.roms_path = path;
+	}
+
+	pub fn roms_path(&self) -> PathBuf {
+		self.roms_path.clone()
+	}
+
+	pub fn set_assets_path(&mut self, path: PathBuf) {
+		self.assets_path = path;
+	}
+
+	pub fn assets_path(&self) -> PathBuf {
+		self.assets_path.clone()
+	}
+
+	pub fn set_ai_log_path(&mut self, path: PathBuf) {
+		self.ai_log_file = path;
+	}
+
+	pub fn ai_log_path(&self) -> PathBuf {
+		self.ai_log_file.clone()
+	}
+
 	pub fn set_hash_type(&mut self, hash_type: HashType) {
 		self.hash_type = hash_type;
 	}
@@ -198,3 +1007,116 @@ impl DaveConfig {
 		self.hash_type
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn can_get_set_and_unset_settings() {
+		let mut config = DaveConfig::default();
+		config.set("hash_type", "blake3").unwrap();
+		assert_eq!(HashType::Blake3, config.hash_type());
+		config.set("encryption.unlock_minutes", "30").unwrap();
+		assert_eq!(json!(30), config.get("encryption.unlock_minutes").unwrap());
+		assert!(matches!(config.set("encryption.enabled", "true"), Err(ConfigError::Managed(_))));
+		assert!(matches!(config.set("vault_key_file", "/tmp/key"), Err(ConfigError::Managed(_))));
+		assert!(matches!(config.unset("encryption.identity"), Err(ConfigError::Managed(_))));
+		assert!(!config.encryption.enabled);
+
+		assert!(matches!(config.set("encryption.unlock_minutes", "soon"), Err(ConfigError::InvalidValue(..))));
+		assert!(matches!(config.set("hash_type", "md4"), Err(ConfigError::InvalidValue(..))));
+		assert!(matches!(config.get("no_such_setting"), Err(ConfigError::UnknownKey(_))));
+
+		config.unset("encryption.unlock_minutes").unwrap();
+		assert_eq!(15, config.encryption.unlock_minutes);
+		assert!(config.entries().iter().any(|(key, _)| key == "quiz_path"));
+	}
+
+	#[test]
+	fn can_layer_settings() {
+		let mut project = json!({
+			"root_path": "data",
+			"budget_file": "/tmp/project.budget",
+			"encryption": { "enabled": false },
+			"quiz_path": "quiz",
+			"rates_file": "/tmp/project_rates.json",
+		});
+		let mut warnings = Vec::new();
+		restrict_project(&mut project, Path::new("/work/site/.dave.conf"), &mut warnings);
+		rebase_paths(&mut project, Path::new("/work/site"));
+		assert_eq!(3, warnings.len());
+		warnings.clear();
+		let overrides = [
+			("encryption.enabled".to_string(), "true".to_string(), ConfigSource::Flag),
+			("encryption.unlock_minutes".to_string(), "later".to_string(), ConfigSource::Flag),
+			("no_such_setting".to_string(), "1".to_string(), ConfigSource::Flag),
+		];
+		let flags = text_layer(&overrides, &mut warnings);
+		assert_eq!(1, flags.len());
+		assert_eq!(2, warnings.len());
+
+		let mut merged = json!({});
+		let mut sources = BTreeMap::new();
+		let project_source = ConfigSource::ProjectFile(PathBuf::from("/work/site/.dave.conf"));
+		merge(&mut merged, &project, &project_source, "", &mut sources);
+		for (source, layer) in &flags {
+			merge(&mut merged, layer, source, "", &mut sources);
+		}
+		let config = build_config(&merged).unwrap();
+
+		// Where Data and Keys Live Can't be Moved by a Project File
+		assert_eq!(DaveConfig::default().root_path(), config.root_path());
+		assert_eq!(DaveConfig::default().budget_path(), config.budget_path());
+		assert_eq!(PathBuf::from("/work/site/quiz"), config.quiz_path());
+		assert_eq!(PathBuf::from("/tmp/project_rates.json"), config.rates_path());
+		assert!(config.encryption.enabled);
+
+		let resolved = ResolvedConfig { config, sources, warnings };
+		assert_eq!(&project_source, resolved.source("quiz_path"));
+		assert_eq!(&ConfigSource::Default, resolved.source("root_path"));
+		assert_eq!(&ConfigSource::Flag, resolved.source("encryption.enabled"));
+		assert_eq!(&ConfigSource::Default, resolved.source("database_path"));
+	}
+
+	#[test]
+	fn can_write_one_setting() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join(CONF_FILE);
+		fs::write(&path, r#"{ "hash_type": "Md5", "encryption": { "unlock_minutes": 5 } }"#).unwrap();
+		DaveConfig::write_setting(&path, "encryption.enabled", Some(json!(true))).unwrap();
+		DaveConfig::write_setting(&path, "encryption.unlock_minutes", None).unwrap();
+		let written: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+		assert_eq!(json!({ "hash_type": "Md5", "encryption": { "enabled": true } }), written);
+		assert_eq!(Some(json!(true)), DaveConfig::read_setting(&path, "encryption.enabled").unwrap());
+		assert_eq!(None, DaveConfig::read_setting(&path, "import.rules").unwrap());
+	}
+
+	#[test]
+	fn can_save_settings_without_other_layers() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join(CONF_FILE);
+		fs::write(&path, r#"{ "encryption": { "unlock_minutes": 5 } }"#).unwrap();
+
+		let mut warnings = Vec::new();
+		let environment = [("hash_type".to_string(), "md5".to_string(), ConfigSource::Environment("DAVE_HASH_TYPE".to_string()))];
+		let mut merged = json!({});
+		for (source, layer) in text_layer(&environment, &mut warnings) {
+			merge(&mut merged, &layer, &source, "", &mut BTreeMap::new());
+		}
+		let mut config = build_config(&merged).unwrap();
+		assert_eq!(HashType::Md5, config.hash_type());
+		config.set_config_path(path.clone());
+		config.import.rules.push(crate::dave_statement::CategoryRule { pattern: "coffee".to_string(), category: "treats".to_string() });
+		config.save_settings(&["import.rules"]).unwrap();
+
+		// Only the Saved Setting Lands in the File, Not the Environment's
+		let written: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+		assert_eq!(None, lookup(&written, "hash_type"));
+		assert_eq!(json!(5), written["encryption"]["unlock_minutes"]);
+		assert_eq!(json!("treats"), written["import"]["rules"][0]["category"]);
+		let (saved, _) = DaveConfig::validate_file(&path).unwrap();
+		assert_eq!(DaveConfig::default().hash_type(), saved.hash_type());
+		assert_eq!(1, saved.import.rules.len());
+	}
+}
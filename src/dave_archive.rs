@@ -0,0 +1,321 @@
+use std::fs::{self, File, OpenOptions};
+use bincode::Options;
+use std::io::{
+	self,
+	Error,
+	ErrorKind,
+	Read,
+	Write,
+};
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+// First Bytes of Every Archive. Extraction Checks Them Before Reading Any
+// Header, so Something That Isn't an Archive Is Refused Up Front
+pub const ARCHIVE_MAGIC: &[u8; 8] = b"DAVEARC1";
+
+// Archives Can Come From Anyone With a Public Key, so Nothing Read From
+// One Is Trusted to be a Sensible Size: Entry Headers Are Capped Before
+// Anything Is Allocated for Them, as Are Paths and the Number of Entries
+const MAX_HEADER_BYTES: u64 = 8 * 1024;
+const MAX_PATH_LENGTH: usize = 4096;
+const MAX_ENTRIES: usize = 1_000_000;
+
+// Permission Bits Kept. Setuid, Setgid and Sticky Bits Never Are
+const MODE_MASK: u32 = 0o777;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+enum EntryKind {
+	Directory,
+	// The File's Contents Follow Its Entry
+	File { size: u64 },
+}
+
+// One Directory or File. Paths Are Relative to the Archived Root and
+// Always Use '/' Between Components
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct ArchiveEntry {
+	path: String,
+	kind: EntryKind,
+	mode: u32,
+	modified: Option<SystemTime>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ArchiveSummary {
+	pub directories: usize,
+	pub files: usize,
+	pub bytes: u64,
+	// Symlinks and Other Special Files Aren't Archived
+	pub skipped: Vec<PathBuf>,
+}
+
+fn archive_error<E: std::fmt::Display>(error: E) -> Error {
+	Error::new(ErrorKind::InvalidData, format!("Corrupt Archive: {}", error))
+}
+
+// The Same Encoding bincode::serialize_into Uses, With a Size Limit
+fn header_options() -> impl bincode::Options {
+	bincode::DefaultOptions::new()
+		.with_fixint_encoding()
+		.allow_trailing_bytes()
+		.with_limit(MAX_HEADER_BYTES)
+}
+
+#[cfg(unix)]
+fn file_mode(metadata: &fs::Metadata) -> u32 {
+	use std::os::unix::fs::PermissionsExt;
+	metadata.permissions().mode() & MODE_MASK
+}
+
+#[cfg(not(unix))]
+fn file_mode(metadata: &fs::Metadata) -> u32 {
+	if metadata.permissions().readonly() { 0o444 } else { 0o644 }
+}
+
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: u32) -> io::Result<()> {
+	use std::os::unix::fs::PermissionsExt;
+	fs::set_permissions(path, fs::Permissions::from_mode(mode))
+}
+
+#[cfg(not(unix))]
+fn set_mode(path: &Path, mode: u32) -> io::Result<()> {
+	let mut permissions = fs::metadata(path)?.permissions();
+	permissions.set_readonly(mode & 0o222 == 0);
+	fs::set_permissions(path, permissions)
+}
+
+fn set_modified(path: &Path, modified: Option<SystemTime>) -> io::Result<()> {
+	match modified {
+		Some(modified) => File::open(path)?.set_modified(modified),
+		None => Ok(()),
+	}
+}
+
+// Write Every Directory and File Under the Root to the Writer
+pub fn write_archive<W: Write>(root: &Path, mut writer: W) -> io::Result<ArchiveSummary> {
+	let mut summary = ArchiveSummary::default();
+	writer.write_all(ARCHIVE_MAGIC)?;
+	for entry in WalkDir::new(root).min_depth(1).sort_by_file_name() {
+		let entry = entry?;
+		let metadata = entry.metadata()?;
+		let relative = entry.path().strip_prefix(root).map_err(Error::other)?;
+		let path = relative
+			.components()
+			.map(|component| component.as_os_str().to_string_lossy())
+			.collect::<Vec<_>>()
+			.join("/");
+		let kind = if metadata.is_dir() {
+			summary.directories += 1;
+			EntryKind::Directory
+		} else if metadata.is_file() {
+			summary.files += 1;
+			summary.bytes += metadata.len();
+			EntryKind::File { size: metadata.len() }
+		} else {
+			summary.skipped.push(entry.into_path());
+			continue
+		};
+		let header = ArchiveEntry { path, kind, mode: file_mode(&metadata), modified: metadata.modified().ok() };
+		header_options().serialize_into(&mut writer, &Some(&header)).map_err(Error::other)?;
+
+		if let EntryKind::File { size } = header.kind {
+			// The Header Promised Exactly This Many Bytes
+			let copied = io::copy(&mut File::open(entry.path())?.take(size), &mut writer)?;
+			if copied != size {
+				return Err(Error::other(format!("{} Changed While Being Archived", entry.path().display())))
+			}
+		}
+	}
+	header_options().serialize_into(&mut writer, &None::<ArchiveEntry>).map_err(Error::other)?;
+	Ok(summary)
+}
+
+// Resolve an Archived Path Under the Target. Anything That Could Land
+// Outside It, Like '..', Absolute Paths or Drive Prefixes, Is Refused
+fn safe_join(target: &Path, archived: &str) -> io::Result<PathBuf> {
+	let relative = Path::new(archived);
+	let mut joined = target.to_path_buf();
+	for component in relative.components() {
+		match component {
+			Component::Normal(part) => joined.push(part),
+			_ => {
+				return Err(Error::new(ErrorKind::InvalidData, format!("Refusing to Extract Unsafe Path '{}'", archived)))
+			},
+		}
+	}
+	if joined == target {
+		return Err(Error::new(ErrorKind::InvalidData, "Refusing to Extract an Empty Path"))
+	}
+	Ok(joined)
+}
+
+fn extract_into<R: Read>(reader: &mut R, target: &Path) -> io::Result<ArchiveSummary> {
+	let mut summary = ArchiveSummary::default();
+	// Directory Modes and Times Are Applied Last, Since Adding Files
+	// Would Change Them and a Read Only Directory Can't be Filled
+	let mut directories = Vec::new();
+	let mut entries = 0;
+	while let Some(entry) = header_options().deserialize_from::<_, Option<ArchiveEntry>>(&mut *reader).map_err(archive_error)? {
+		entries += 1;
+		if entries > MAX_ENTRIES {
+			return Err(archive_error(format!("More Than {} Entries", MAX_ENTRIES)))
+		}
+		if entry.path.len() > MAX_PATH_LENGTH {
+			return Err(archive_error(format!("Path Longer Than {} Bytes", MAX_PATH_LENGTH)))
+		}
+		let path = safe_join(target, &entry.path)?;
+		match entry.kind {
+			EntryKind::Directory => {
+				fs::create_dir_all(&path)?;
+				summary.directories += 1;
+				directories.push((path, entry.mode & MODE_MASK, entry.modified));
+			},
+			EntryKind::File { size } => {
+				if let Some(parent) = path.parent() {
+					fs::create_dir_all(parent)?;
+				}
+				let mut file = OpenOptions::new().write(true).create_new(true).open(&path)?;
+				let copied = io::copy(&mut reader.take(size), &mut file)?;
+				if copied != size {
+					return Err(archive_error(format!("{} Is Truncated", entry.path)))
+				}
+				drop(file);
+				set_modified(&path, entry.modified)?;
+				set_mode(&path, entry.mode & MODE_MASK)?;
+				summary.files += 1;
+				summary.bytes += size;
+			},
+		}
+	}
+	for (path, mode, modified) in directories.into_iter().rev() {
+		set_modified(&path, modified)?;
+		set_mode(&path, mode)?;
+	}
+	Ok(summary)
+}
+
+// A New, Empty Directory Beside the Target to Build the Tree In. It's
+// Only Ever Created, so a Directory Already There Is Never Reused
+fn create_staging(target: &Path) -> io::Result<PathBuf> {
+	let name = target.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+	for _ in 0..16 {
+		let staging = target.with_file_name(format!(".{}.{:016x}.extracting", name, rand::random::<u64>()));
+		match fs::create_dir(&staging) {
+			Ok(()) => return Ok(staging),
+			Err(error) if error.kind() == ErrorKind::AlreadyExists => continue,
+			Err(error) => return Err(error),
+		}
+	}
+	Err(Error::new(ErrorKind::AlreadyExists, format!("Could Not Find a Free Directory Name Next to {}", target.display())))
+}
+
+// Restore an Archive to a Target Directory That Doesn't Exist Yet, or
+// Is Empty. The Tree Is Built Beside It and Only Moved Into Place Once
+// Every Entry Has Been Read, so a Damaged Archive Leaves Nothing Behind
+pub fn extract_archive<R: Read>(mut reader: R, target: &Path) -> io::Result<ArchiveSummary> {
+	let mut magic = [0u8; 8];
+	reader.read_exact(&mut magic).map_err(archive_error)?;
+	if &magic != ARCHIVE_MAGIC {
+		return Err(Error::new(ErrorKind::InvalidData, "Not a Dave Archive"))
+	}
+	if target.exists() && fs::read_dir(target)?.next().is_some() {
+		return Err(Error::new(ErrorKind::AlreadyExists, format!("{} Already Exists and Isn't Empty", target.display())))
+	}
+
+	let staging = create_staging(target)?;
+	let result = extract_into(&mut reader, &staging).and_then(|summary| {
+		if target.exists() {
+			fs::remove_dir(target)?;
+		}
+		fs::rename(&staging, target)?;
+		Ok(summary)
+	});
+	if result.is_err() {
+		let _ = fs::remove_dir_all(&staging);
+	}
+	result
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn can_round_trip_a_tree() {
+		let dir = tempfile::tempdir().unwrap();
+		let root = dir.path().join("secrets");
+		fs::create_dir_all(root.join("keys/empty")).unwrap();
+		fs::write(root.join("keys/id_ed25519"), [0u8, 1, 2, 255]).unwrap();
+		fs::write(root.join(".env"), "TOKEN=abc").unwrap();
+		set_mode(&root.join("keys/id_ed25519"), 0o600).unwrap();
+		let then = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+		set_modified(&root.join(".env"), Some(then)).unwrap();
+
+		let mut archive = Vec::new();
+		let written = write_archive(&root, &mut archive).unwrap();
+		assert_eq!((2, 2, 13), (written.directories, written.files, written.bytes));
+
+		let restored = dir.path().join("restored");
+		assert_eq!(written, extract_archive(&archive[..], &restored).unwrap());
+		assert_eq!(vec![0u8, 1, 2, 255], fs::read(restored.join("keys/id_ed25519")).unwrap());
+		assert!(restored.join("keys/empty").is_dir());
+		assert_eq!(then, fs::metadata(restored.join(".env")).unwrap().modified().unwrap());
+		if cfg!(unix) {
+			assert_eq!(0o600, file_mode(&fs::metadata(restored.join("keys/id_ed25519")).unwrap()));
+		}
+
+		// Never Restores Over Existing Files
+		assert_eq!(ErrorKind::AlreadyExists, extract_archive(&archive[..], &restored).unwrap_err().kind());
+	}
+
+	#[test]
+	fn can_refuse_path_traversal() {
+		let dir = tempfile::tempdir().unwrap();
+		for path in ["../escaped", "/etc/escaped", "a/../../escaped", ""] {
+			let mut archive = ARCHIVE_MAGIC.to_vec();
+			let entry = ArchiveEntry { path: path.to_string(), kind: EntryKind::File { size: 2 }, mode: 0o644, modified: None };
+			header_options().serialize_into(&mut archive, &Some(&entry)).unwrap();
+			archive.extend_from_slice(b"hi");
+			header_options().serialize_into(&mut archive, &None::<ArchiveEntry>).unwrap();
+
+			let target = dir.path().join("target");
+			assert_eq!(ErrorKind::InvalidData, extract_archive(&archive[..], &target).unwrap_err().kind());
+			assert!(!target.exists());
+		}
+		assert!(!dir.path().join("escaped").exists());
+	}
+
+	#[test]
+	fn can_refuse_hostile_archives() {
+		let dir = tempfile::tempdir().unwrap();
+		let target = dir.path().join("target");
+
+		// A Length Prefix Claiming Terabytes Fails Before Anything Is Allocated
+		let mut huge = ARCHIVE_MAGIC.to_vec();
+		huge.push(1);
+		huge.extend_from_slice(&u64::MAX.to_le_bytes());
+		assert_eq!(ErrorKind::InvalidData, extract_archive(&huge[..], &target).unwrap_err().kind());
+
+		// Leftovers Beside the Target Are Neither Reused Nor Removed
+		let leftover = dir.path().join("target.extracting");
+		fs::create_dir(&leftover).unwrap();
+		fs::write(leftover.join("mine.txt"), "mine").unwrap();
+		assert!(extract_archive(&huge[..], &target).is_err());
+		assert_eq!("mine", fs::read_to_string(leftover.join("mine.txt")).unwrap());
+
+		let mut archive = ARCHIVE_MAGIC.to_vec();
+		let entry = ArchiveEntry { path: "run".to_string(), kind: EntryKind::File { size: 0 }, mode: 0o4755, modified: None };
+		header_options().serialize_into(&mut archive, &Some(&entry)).unwrap();
+		header_options().serialize_into(&mut archive, &None::<ArchiveEntry>).unwrap();
+		extract_archive(&archive[..], &target).unwrap();
+		#[cfg(unix)]
+		{
+			use std::os::unix::fs::PermissionsExt;
+			assert_eq!(0o755, fs::metadata(target.join("run")).unwrap().permissions().mode() & 0o7777);
+		}
+		assert_eq!(2, fs::read_dir(dir.path()).unwrap().count());
+	}
+}
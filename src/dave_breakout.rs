@@ -10,7 +10,7 @@ use bevy::{
 	sprite::MaterialMesh2dBundle,
 };
 use crate::dave_graphics::{
-	ASSETS_DIR,
+	assets_dir,
 	SteppingPlugin,
 };
 
@@ -158,7 +158,7 @@ fn setup(
 
 	// Sound
 	let ball_collision_sound = asset_server.load(
-		ASSETS_DIR.to_owned() + "/audio/collision.ogg",
+		assets_dir() + "/audio/collision.ogg",
 	);
 	commands.insert_resource(CollisionSound(ball_collision_sound));
 
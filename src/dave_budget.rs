@@ -1,29 +1,600 @@
+use chrono::{
+	Datelike,
+	Days,
+	Months,
+	NaiveDate,
+};
+use colored::*;
+use crate::dave_statement::{Categorizer, StatementEntry};
+use crate::dave_store::{JsonStore, Versioned};
 use serde::{Serialize, Deserialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+use tabled::{
+	builder::Builder,
+	settings::Style,
+};
 
+// Format Used When Parsing and Displaying Transaction Dates
+pub const BUDGET_DATE_FORMAT: &str = "%Y-%m-%d";
+
+// Account Used When None Is Given
+pub const DEFAULT_ACCOUNT: &str = "default";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TransactionKind {
+	Income,
+	Expense,
+}
+
+impl fmt::Display for TransactionKind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			TransactionKind::Income => write!(f, "Income"),
+			TransactionKind::Expense => write!(f, "Expense"),
+		}
+	}
+}
+
+impl FromStr for TransactionKind {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_lowercase().as_str() {
+			"income" => Ok(TransactionKind::Income),
+			"expense" => Ok(TransactionKind::Expense),
+			_ => Err(format!("'{}' is not a transaction kind. Use income or expense", s)),
+		}
+	}
+}
+
+// A Single Movement of Money
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Transaction {
+	pub id: u64,
+	pub date: NaiveDate,
+	pub kind: TransactionKind,
+	pub amount: f64,
+	pub category: String,
+	pub memo: String,
+	pub account: String,
+	// Who Was Paid or Paid Us, as Given on a Bank Statement
+	#[serde(default)]
+	pub payee: String,
+}
+
+// Identity Used to Spot a Statement Line Already in the Ledger
+fn import_key(date: NaiveDate, kind: TransactionKind, amount: f64, payee: &str) -> (NaiveDate, TransactionKind, i64, String) {
+	(date, kind, (amount.abs() * 100.0).round() as i64, payee.trim().to_lowercase())
+}
+
+impl Transaction {
+	fn import_key(&self) -> (NaiveDate, TransactionKind, i64, String) {
+		import_key(self.date, self.kind, self.amount, &self.payee)
+	}
+
+	// Amount as It Affects the Balance
+	pub fn signed_amount(&self) -> f64 {
+		match self.kind {
+			TransactionKind::Income => self.amount,
+			TransactionKind::Expense => -self.amount,
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Frequency {
+	Weekly,
+	Monthly,
+	Yearly,
+}
+
+impl FromStr for Frequency {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_lowercase().as_str() {
+			"weekly" => Ok(Frequency::Weekly),
+			"monthly" => Ok(Frequency::Monthly),
+			"yearly" => Ok(Frequency::Yearly),
+			_ => Err(format!("'{}' is not a frequency. Use weekly, monthly or yearly", s)),
+		}
+	}
+}
+
+// Income or Expense That Repeats on a Schedule
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecurringRule {
+	pub kind: TransactionKind,
+	pub amount: f64,
+	pub category: String,
+	pub memo: String,
+	pub account: String,
+	pub frequency: Frequency,
+	pub start: NaiveDate,
+	pub end: Option<NaiveDate>,
+	// How Many Occurrences Have Been Posted to the Ledger
+	pub posted: u32,
+}
+
+impl RecurringRule {
+	// Date of the Nth Occurrence, Counted From the Start so
+	// Month Ends Don't Drift
+	pub fn occurrence(&self, n: u32) -> Option<NaiveDate> {
+		match self.frequency {
+			Frequency::Weekly => self.start.checked_add_days(Days::new(7 * n as u64)),
+			Frequency::Monthly => self.start.checked_add_months(Months::new(n)),
+			Frequency::Yearly => self.start.checked_add_months(Months::new(12 * n)),
+		}
+	}
+}
+
+// Budget as Stored Before It Became a Ledger
 #[derive(Debug, Serialize, Deserialize)]
-pub struct DaveBudget {
+pub struct LegacyDaveBudget {
 	pub income: f64,
 	pub expenses: HashMap<String, f64>,
 }
 
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DaveBudget {
+	pub transactions: Vec<Transaction>,
+	pub recurring: Vec<RecurringRule>,
+	// Monthly Spending Limit per Category
+	pub limits: BTreeMap<String, f64>,
+	pub next_id: u64,
+}
+
+// Either Shape a Budget File Might Hold
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BudgetFile {
+	Ledger(DaveBudget),
+	Legacy(LegacyDaveBudget),
+}
+
 impl DaveBudget {
 	pub fn new() -> DaveBudget {
-		DaveBudget {
-			income: 0.0,
-			expenses: HashMap::new(),
+		DaveBudget::default()
+	}
+
+	// Read a Budget, Converting the Old Income and Expenses Shape
+	// Into Ledger Entries Dated on the Day of Migration
+	pub fn from_json(json: &str, today: NaiveDate) -> serde_json::Result<DaveBudget> {
+		DaveBudget::from_value(serde_json::from_str(json)?, today)
+	}
+
+	pub fn from_value(value: serde_json::Value, today: NaiveDate) -> serde_json::Result<DaveBudget> {
+		match serde_json::from_value(value)? {
+			BudgetFile::Ledger(budget) => Ok(budget),
+			BudgetFile::Legacy(legacy) => Ok(DaveBudget::from_legacy(legacy, today)),
 		}
 	}
 
-	pub fn add_income(&mut self, amount: f64) {
-		self.income += amount
+	pub fn from_legacy(legacy: LegacyDaveBudget, today: NaiveDate) -> DaveBudget {
+		let mut budget = DaveBudget::new();
+		if legacy.income != 0.0 {
+			budget.record(today, TransactionKind::Income, legacy.income, "income", "Migrated income", DEFAULT_ACCOUNT);
+		}
+		let mut expenses: Vec<(String, f64)> = legacy.expenses.into_iter().collect();
+		expenses.sort_by(|a, b| a.0.cmp(&b.0));
+		for (name, amount) in expenses {
+			budget.record(today, TransactionKind::Expense, amount, &name, "Migrated expense", DEFAULT_ACCOUNT);
+		}
+		budget
 	}
 
-	pub fn add_expense(&mut self, expense_name: String, amount: f64) {
-		self.expenses.insert(expense_name, amount);
+	// Append a Transaction to the Ledger
+	pub fn record(
+		&mut self,
+		date: NaiveDate,
+		kind: TransactionKind,
+		amount: f64,
+		category: &str,
+		memo: &str,
+		account: &str,
+	) -> &Transaction {
+		self.next_id += 1;
+		self.transactions.push(Transaction {
+			id: self.next_id,
+			date,
+			kind,
+			amount: amount.abs(),
+			category: category.to_string(),
+			memo: memo.to_string(),
+			account: account.to_string(),
+			payee: String::new(),
+		});
+		self.transactions.last().unwrap()
+	}
+
+	// Add Statement Lines to the Ledger, Skipping Any Already Recorded.
+	// Identical Lines Within One Statement Are Kept, so Only as Many
+	// Matches as the Ledger Already Holds Count as Duplicates
+	pub fn import_statement(
+		&mut self,
+		entries: Vec<StatementEntry>,
+		categorizer: &Categorizer,
+		account: &str,
+	) -> StatementImport {
+		let mut existing: HashMap<_, usize> = HashMap::new();
+		for transaction in &self.transactions {
+			*existing.entry(transaction.import_key()).or_insert(0) += 1;
+		}
+
+		let mut result = StatementImport::default();
+		for entry in entries {
+			if let Some(count) = existing.get_mut(&import_key(entry.date, entry.kind, entry.amount, &entry.payee)) {
+				if *count > 0 {
+					*count -= 1;
+					result.duplicates += 1;
+					continue
+				}
+			}
+			let category = categorizer.category(&entry);
+			self.record(entry.date, entry.kind, entry.amount, &category, &entry.memo, account);
+			self.transactions.last_mut().unwrap().payee = entry.payee;
+			result.added += 1;
+		}
+		result
+	}
+
+	pub fn add_income(&mut self, date: NaiveDate, amount: f64, category: &str, memo: &str, account: &str) {
+		self.record(date, TransactionKind::Income, amount, category, memo, account);
+	}
+
+	pub fn add_expense(&mut self, date: NaiveDate, amount: f64, category: &str, memo: &str, account: &str) {
+		self.record(date, TransactionKind::Expense, amount, category, memo, account);
+	}
+
+	pub fn add_recurring(&mut self, rule: RecurringRule) {
+		self.recurring.push(rule);
+	}
+
+	pub fn set_limit(&mut self, category: &str, amount: f64) {
+		self.limits.insert(category.to_string(), amount.abs());
+	}
+
+	// Post Every Recurring Occurrence Due On or Before Today
+	pub fn apply_recurring(&mut self, today: NaiveDate) -> usize {
+		let mut due = Vec::new();
+		for rule in self.recurring.iter_mut() {
+			while let Some(date) = rule.occurrence(rule.posted) {
+				if date > today || rule.end.is_some_and(|end| date > end) {
+					break
+				}
+				due.push((date, rule.kind, rule.amount, rule.category.clone(), rule.memo.clone(), rule.account.clone()));
+				rule.posted += 1;
+			}
+		}
+		due.sort_by_key(|entry| entry.0);
+		let posted = due.len();
+		for (date, kind, amount, category, memo, account) in due {
+			self.record(date, kind, amount, &category, &memo, &account);
+		}
+		posted
+	}
+
+	pub fn total_income(&self) -> f64 {
+		self.transactions
+			.iter()
+			.filter(|t| t.kind == TransactionKind::Income)
+			.map(|t| t.amount)
+			.sum()
+	}
+
+	pub fn total_expenses(&self) -> f64 {
+		self.transactions
+			.iter()
+			.filter(|t| t.kind == TransactionKind::Expense)
+			.map(|t| t.amount)
+			.sum()
 	}
 
 	pub fn get_balance(&self) -> f64 {
-		self.income - self.expenses.values().sum::<f64>()
+		self.transactions.iter().map(|t| t.signed_amount()).sum()
+	}
+
+	// Net Amount per Category Over the Whole Ledger
+	pub fn category_totals(&self) -> BTreeMap<String, f64> {
+		let mut totals = BTreeMap::new();
+		for transaction in &self.transactions {
+			*totals.entry(transaction.category.clone()).or_insert(0.0) += transaction.signed_amount();
+		}
+		totals
+	}
+
+	pub fn report(&self, year: i32, month: u32) -> MonthReport {
+		let in_month = |date: &NaiveDate| date.year() == year && date.month() == month;
+		let mut ordered: Vec<&Transaction> = self.transactions.iter().collect();
+		ordered.sort_by_key(|t| (t.date, t.id));
+
+		let opening_balance: f64 = ordered
+			.iter()
+			.filter(|t| (t.date.year(), t.date.month()) < (year, month))
+			.map(|t| t.signed_amount())
+			.sum();
+
+		let mut categories: BTreeMap<String, CategoryReport> = BTreeMap::new();
+		for (category, limit) in &self.limits {
+			categories.insert(category.clone(), CategoryReport::new(category, Some(*limit)));
+		}
+
+		let mut entries = Vec::new();
+		let mut balance = opening_balance;
+		for transaction in ordered.into_iter().filter(|t| in_month(&t.date)) {
+			balance += transaction.signed_amount();
+			entries.push((transaction.clone(), balance));
+			let category = categories
+				.entry(transaction.category.clone())
+				.or_insert_with(|| CategoryReport::new(&transaction.category, None));
+			match transaction.kind {
+				TransactionKind::Income => category.income += transaction.amount,
+				TransactionKind::Expense => category.expenses += transaction.amount,
+			}
+		}
+
+		MonthReport {
+			year,
+			month,
+			opening_balance,
+			categories: categories.into_values().collect(),
+			entries,
+			closing_balance: balance,
+		}
+	}
+}
+
+impl Versioned for DaveBudget {
+	const SCHEMA_VERSION: u32 = 1;
+
+	fn migrate(data: serde_json::Value, _version: Option<u32>) -> serde_json::Result<Self> {
+		DaveBudget::from_value(data, chrono::Local::now().date_naive())
+	}
+}
+
+// Outcome of Importing a Bank Statement
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StatementImport {
+	pub added: usize,
+	pub duplicates: usize,
+}
+
+// Where the Budget Lives on Disk
+pub type BudgetStore = JsonStore<DaveBudget>;
+
+// Totals for One Category Within a Month
+#[derive(Clone, Debug, PartialEq)]
+pub struct CategoryReport {
+	pub category: String,
+	pub income: f64,
+	pub expenses: f64,
+	pub limit: Option<f64>,
+}
+
+impl CategoryReport {
+	fn new(category: &str, limit: Option<f64>) -> Self {
+		CategoryReport { category: category.to_string(), income: 0.0, expenses: 0.0, limit }
+	}
+
+	// Money Left Under the Limit, Negative When Overspent
+	pub fn variance(&self) -> Option<f64> {
+		self.limit.map(|limit| limit - self.expenses)
+	}
+}
+
+#[derive(Clone, Debug)]
+pub struct MonthReport {
+	pub year: i32,
+	pub month: u32,
+	pub opening_balance: f64,
+	pub categories: Vec<CategoryReport>,
+	pub entries: Vec<(Transaction, f64)>,
+	pub closing_balance: f64,
+}
+
+// Parse a Month Given as YYYY-MM
+pub fn parse_month(month: &str) -> Result<(i32, u32), String> {
+	match NaiveDate::parse_from_str(&format!("{}-01", month), BUDGET_DATE_FORMAT) {
+		Ok(date) => Ok((date.year(), date.month())),
+		Err(_) => Err(format!("'{}' is not a valid month (YYYY-MM)", month)),
+	}
+}
+
+pub fn parse_budget_date(date: &str) -> Result<NaiveDate, String> {
+	NaiveDate::parse_from_str(date, BUDGET_DATE_FORMAT)
+		.map_err(|_| format!("'{}' is not a valid date (YYYY-MM-DD)", date))
+}
+
+pub fn print_summary(budget: &DaveBudget) {
+	println!("##==>> Amount of Income: ${:.2}", budget.total_income());
+	println!("##==>> Amount of Expenses: ${:.2}", budget.total_expenses());
+	println!("##==>> Current Budget: ${:.2}", budget.get_balance());
+	for (category, amount) in budget.category_totals() {
+		println!("##==>> Category: {} - ${:.2}", category, amount);
+	}
+}
+
+pub fn print_report(report: &MonthReport) {
+	println!("##==>> Budget Report for {}-{:02}", report.year, report.month);
+	println!("##==>> Opening Balance: ${:.2}\n", report.opening_balance);
+
+	let mut builder = Builder::default();
+	builder.push_record(["Category", "Income", "Expenses", "Limit", "Variance"]);
+	for category in &report.categories {
+		let variance = match category.variance() {
+			Some(variance) if variance < 0.0 => format!("{:.2}", variance).red().to_string(),
+			Some(variance) => format!("{:.2}", variance).green().to_string(),
+			None => "-".to_string(),
+		};
+		builder.push_record([
+			category.category.clone(),
+			format!("{:.2}", category.income),
+			format!("{:.2}", category.expenses),
+			category.limit.map_or("-".to_string(), |limit| format!("{:.2}", limit)),
+			variance,
+		]);
+	}
+	println!("{}\n", builder.build().with(Style::modern()));
+
+	let mut builder = Builder::default();
+	builder.push_record(["Date", "Category", "Payee", "Memo", "Account", "Amount", "Balance"]);
+	for (transaction, balance) in &report.entries {
+		builder.push_record([
+			transaction.date.format(BUDGET_DATE_FORMAT).to_string(),
+			transaction.category.clone(),
+			transaction.payee.clone(),
+			transaction.memo.clone(),
+			transaction.account.clone(),
+			format!("{:.2}", transaction.signed_amount()),
+			format!("{:.2}", balance),
+		]);
+	}
+	println!("{}\n", builder.build().with(Style::modern()));
+	println!("##==>> Closing Balance: ${:.2}", report.closing_balance);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn date(date: &str) -> NaiveDate {
+		parse_budget_date(date).unwrap()
+	}
+
+	#[test]
+	fn can_migrate_legacy_budget() {
+		let json = r#"{"income":1000.0,"expenses":{"rent":600.0,"food":150.5}}"#;
+		let budget = DaveBudget::from_json(json, date("2024-03-09")).unwrap();
+		assert_eq!(3, budget.transactions.len());
+		assert_eq!(1000.0, budget.total_income());
+		assert_eq!(249.5, budget.get_balance());
+
+		let json = serde_json::to_string(&budget).unwrap();
+		let reloaded = DaveBudget::from_json(&json, date("2025-01-01")).unwrap();
+		assert_eq!(budget.transactions, reloaded.transactions);
+	}
+
+	#[test]
+	fn can_store_budget_atomically() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("dave.budget");
+		let store = BudgetStore::new(&path);
+		assert!(store.load().unwrap().transactions.is_empty());
+
+		// Files From Older Versions Load and Are Saved Versioned Straight
+		// Away, so the Migrated Transactions Keep the Date They First Got
+		std::fs::write(&path, r#"{"income":5.0,"expenses":{}}"#).unwrap();
+		let mut budget = store.load().unwrap();
+		assert!(std::fs::read_to_string(&path).unwrap().contains("\"version\": 1"));
+		assert_eq!(budget.transactions, store.load().unwrap().transactions);
+		budget.add_expense(date("2024-03-01"), 2.0, "snacks", "", DEFAULT_ACCOUNT);
+		store.save(&budget).unwrap();
+		store.save(&budget).unwrap();
+		let text = std::fs::read_to_string(&path).unwrap();
+		assert!(text.contains("\"version\": 1"));
+		assert_eq!(3.0, store.load().unwrap().get_balance());
+		assert_eq!(1, std::fs::read_dir(dir.path()).unwrap().count());
+
+		std::fs::write(&path, "{\"version\": 1, \"data\": {}}{").unwrap();
+		assert!(store.load().unwrap_err().to_string().contains("Could Not be Read"));
+		std::fs::write(&path, "{\"version\": 99, \"data\": {}}").unwrap();
+		assert!(store.load().unwrap_err().to_string().contains("Newer Version"));
+	}
+
+	#[test]
+	fn can_encrypt_budget_at_rest() {
+		use crate::dave_store::StoreError;
+		use crate::dave_vault::{is_sealed, Vault};
+
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("dave.budget");
+		let mut budget = DaveBudget::new();
+		budget.add_income(date("2024-03-01"), 100.0, "salary", "", DEFAULT_ACCOUNT);
+		BudgetStore::new(&path).save(&budget).unwrap();
+
+		// Plain Budgets Still Load Once Encryption Is Turned On
+		let key_file = dir.path().join("vault.key");
+		let store = BudgetStore::new(&path).with_vault(Some(Vault::generate(&key_file)));
+		let loaded = store.load().unwrap();
+		store.save(&loaded).unwrap();
+		assert!(is_sealed(&std::fs::read(&path).unwrap()));
+		assert_eq!(100.0, store.load().unwrap().get_balance());
+
+		assert!(matches!(BudgetStore::new(&path).load(), Err(StoreError::Encrypted(_, _))));
+	}
+
+	#[test]
+	fn can_keep_repeated_expenses() {
+		let mut budget = DaveBudget::new();
+		budget.add_expense(date("2024-03-01"), 10.0, "coffee", "", DEFAULT_ACCOUNT);
+		budget.add_expense(date("2024-03-02"), 12.0, "coffee", "", DEFAULT_ACCOUNT);
+		assert_eq!(22.0, budget.total_expenses());
+	}
+
+	#[test]
+	fn can_skip_duplicate_imports() {
+		use crate::dave_statement::CategoryRule;
+
+		let categorizer = Categorizer::new(&[CategoryRule {
+			pattern: "coffee".to_string(),
+			category: "coffee".to_string(),
+		}]).unwrap();
+		let statement = || vec![
+			StatementEntry { date: date("2024-03-01"), kind: TransactionKind::Expense, amount: 4.5, payee: "Corner Coffee".to_string(), memo: String::new() },
+			StatementEntry { date: date("2024-03-01"), kind: TransactionKind::Expense, amount: 4.5, payee: "Corner Coffee".to_string(), memo: String::new() },
+			StatementEntry { date: date("2024-03-02"), kind: TransactionKind::Income, amount: 900.0, payee: "Payroll".to_string(), memo: String::new() },
+		];
+
+		let mut budget = DaveBudget::new();
+		assert_eq!(StatementImport { added: 3, duplicates: 0 }, budget.import_statement(statement(), &categorizer, DEFAULT_ACCOUNT));
+		assert_eq!(StatementImport { added: 0, duplicates: 3 }, budget.import_statement(statement(), &categorizer, DEFAULT_ACCOUNT));
+		assert_eq!(3, budget.next_id);
+		assert_eq!(Some(&-9.0), budget.category_totals().get("coffee"));
+		assert_eq!(Some(&900.0), budget.category_totals().get("income"));
+	}
+
+	#[test]
+	fn can_apply_recurring_rules() {
+		let mut budget = DaveBudget::new();
+		budget.add_recurring(RecurringRule {
+			kind: TransactionKind::Expense,
+			amount: 50.0,
+			category: "gym".to_string(),
+			memo: String::new(),
+			account: DEFAULT_ACCOUNT.to_string(),
+			frequency: Frequency::Monthly,
+			start: date("2024-01-31"),
+			end: None,
+			posted: 0,
+		});
+		assert_eq!(3, budget.apply_recurring(date("2024-03-31")));
+		assert_eq!(0, budget.apply_recurring(date("2024-03-31")));
+		let dates: Vec<NaiveDate> = budget.transactions.iter().map(|t| t.date).collect();
+		assert_eq!(vec![date("2024-01-31"), date("2024-02-29"), date("2024-03-31")], dates);
+	}
+
+	#[test]
+	fn can_report_month() {
+		let mut budget = DaveBudget::new();
+		budget.add_income(date("2024-02-15"), 100.0, "salary", "", DEFAULT_ACCOUNT);
+		budget.add_income(date("2024-03-01"), 1000.0, "salary", "", DEFAULT_ACCOUNT);
+		budget.add_expense(date("2024-03-05"), 300.0, "food", "", DEFAULT_ACCOUNT);
+		budget.add_expense(date("2024-04-01"), 30.0, "food", "", DEFAULT_ACCOUNT);
+		budget.set_limit("food", 250.0);
+		budget.set_limit("fun", 40.0);
+
+		let report = budget.report(2024, 3);
+		assert_eq!(100.0, report.opening_balance);
+		assert_eq!(800.0, report.closing_balance);
+		assert_eq!(vec![1100.0, 800.0], report.entries.iter().map(|e| e.1).collect::<Vec<f64>>());
+
+		let food = report.categories.iter().find(|c| c.category == "food").unwrap();
+		assert_eq!(Some(-50.0), food.variance());
+		let fun = report.categories.iter().find(|c| c.category == "fun").unwrap();
+		assert_eq!(Some(40.0), fun.variance());
+		assert_eq!(Ok((2024, 3)), parse_month("2024-03"));
+		assert!(parse_month("2024-13").is_err());
 	}
 }
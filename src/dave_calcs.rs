@@ -1,5 +1,11 @@
+use crate::dave_expr::{format_number, CalcError, Calculator};
+use crate::dave_finance::*;
 use colored::*;
-use std::io;
+use serde::Serialize;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
 
 // Wheel Horsepower Calculator
 // HP = WHP * 1 / (1 – (DL - 1))
@@ -54,6 +60,60 @@ pub fn wheel_hp_calc(
 	Ok(())
 }
 
+// Show the Line With a Marker Under Where the Error Is
+pub fn print_calc_error(line: &str, error: &CalcError) {
+	eprintln!("    {}", line);
+	eprintln!("    {}{}", " ".repeat(error.column.saturating_sub(1)), "^".red());
+	eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
+}
+
+fn print_calc_help() {
+	println!("#=> Operators: + - * / % ^ and ( ), Like '2 * (3 + 4)^2'");
+	println!("#=> Functions: sqrt cbrt abs sin cos tan asin acos atan atan2 sinh cosh tanh");
+	println!("#=>            exp ln log log2 log10 floor ceil round trunc min max hypot pow deg rad");
+	println!("#=> Constants: pi e tau phi, and 'ans' for the Last Answer");
+	println!("#=> Variables: 'x = 3'. Separate Statements With ';'");
+	println!("#=> Commands:  history, vars, help, quit");
+}
+
+// Expression Calculator. Variables and History Last Until It's Closed
+pub fn dave_calc_repl() -> io::Result<()> {
+	let mut calculator = Calculator::new();
+	println!("{}", "-- Dave's Calculator --".cyan());
+	println!("#=> Enter an Expression, or 'help' for More");
+	loop {
+		print!("calc> ");
+		io::stdout().flush()?;
+		let mut line = String::new();
+		if io::stdin().read_line(&mut line)? == 0 {
+			println!();
+			break
+		}
+		let line = line.trim_end_matches(['\n', '\r']);
+		match line.trim() {
+			"" => continue,
+			"quit" | "exit" => break,
+			"help" => print_calc_help(),
+			"history" => {
+				for (number, (input, value)) in calculator.history().iter().enumerate() {
+					println!("{:>4}  {} = {}", number + 1, input, format_number(*value));
+				}
+			},
+			"vars" => {
+				for (name, value) in calculator.variables() {
+					println!("#=> {} = {}", name, format_number(*value));
+				}
+			},
+			_ => match calculator.evaluate(line) {
+				Ok(value) => println!("{}", format!("= {}", format_number(value)).yellow()),
+				Err(error) => print_calc_error(line, &error),
+			},
+		}
+	}
+	println!("##==> Exiting ...");
+	Ok(())
+}
+
 // Classic Calculator
 pub fn dave_simple_calc_loop() -> io::Result<()> {
 	loop {
@@ -176,48 +236,115 @@ pub fn dave_income_calc_loop() -> io::Result<()> {
 	Ok(())
 }
 
-pub fn dave_interest_calc_loop() -> io::Result<()> {
-	println!("{}", "-- Dave's Interest Calculator --".cyan());
-	println!("{}", "================================".cyan());
-
-	println!("##==> Enter the amount of money earning interest: ");
-	let mut principal = String::new();
-	io::stdin().read_line(&mut principal)?;
-	let principal: f64 = match principal.trim().parse() {
-		Ok(num) => num,
-		Err(_) => {
-			eprintln!("{}", "##==>>>> ERROR: Invalid Input. Please Enter a Valid Amount\n".red());
-			return Ok(())
+// Ask for a Number Until a Valid One Is Given. An Empty Answer Takes the Default
+pub fn prompt_number(question: &str, default: Option<f64>) -> io::Result<f64> {
+	loop {
+		match default {
+			Some(default) => println!("##==> {} [default: {}]: ", question, default),
+			None => println!("##==> {}: ", question),
 		}
-	};
-
-	println!("##==> Enter the interest rate (%): ");
-	let mut rate = String::new();
-	io::stdin().read_line(&mut rate)?;
-	let rate: f64 = match rate.trim().parse() {
-		Ok(num) => num,
-		Err(_) => {
-			eprintln!("{}", "##==>>>> ERROR: Invalid Input. Please Enter a Valid Number\n".red());
-			return Ok(())
+		let mut answer = String::new();
+		if io::stdin().read_line(&mut answer)? == 0 {
+			return Err(io::Error::new(io::ErrorKind::UnexpectedEof, format!("No Answer Given for '{}'", question)))
 		}
-	};
+		match (answer.trim(), default) {
+			("", Some(default)) => return Ok(default),
+			(answer, _) => match answer.trim_start_matches('$').trim_end_matches('%').replace(',', "").parse::<f64>() {
+				Ok(number) => return Ok(number),
+				Err(_) => eprintln!("{}", "##==>>>> ERROR: Invalid Input. Please Enter a Valid Number\n".red()),
+			},
+		}
+	}
+}
 
-	println!("##==> Enter the interest period (years): ");
-	let mut time = String::new();
-	io::stdin().read_line(&mut time)?;
-	let time: f64 = match time.trim().parse() {
-		Ok(num) => num,
-		Err(_) => {
-			eprintln!("{}", "##==>>>> ERROR: Invalid Input. Please Enter a Valid Number\n".red());
-			return Ok(())
+// Write Rows to a CSV File, or to Standard Output for '-'
+fn export_csv<R: Serialize>(rows: &[R], csv_path: &Path) -> Result<(), Box<dyn Error>> {
+	if csv_path == Path::new("-") {
+		return write_csv(rows, io::stdout().lock())
+	}
+	write_csv(rows, File::create(csv_path)?)?;
+	println!("##==> Successfully Wrote CSV to: {}", csv_path.display());
+	Ok(())
+}
+
+// Show How Savings Grow. With CSV on Standard Output Only the CSV Is Printed
+pub fn print_compound(savings: &Savings, csv_path: Option<&Path>) -> Result<(), Box<dyn Error>> {
+	let projection = compound_projection(savings)?;
+	if csv_path != Some(Path::new("-")) {
+		println!("{}\n", projection_table(&projection));
+		println!("{}", format!("##==>> Final Balance: ${:.2}", projection.final_balance).yellow());
+		println!("{}", format!("##==>> Contributions: ${:.2}", savings.principal + projection.total_contributions).yellow());
+		println!("{}", format!("##==>> Interest Earned: ${:.2}", projection.total_interest).yellow());
+	}
+	match csv_path {
+		Some(csv_path) => export_csv(&projection.rows, csv_path),
+		None => Ok(()),
+	}
+}
+
+// Show the Deposit Needed to Reach a Goal, and the Savings Growing to It
+pub fn print_goal(savings: &Savings, target: f64, csv_path: Option<&Path>) -> Result<(), Box<dyn Error>> {
+	let contribution = required_contribution(savings, target)?;
+	let savings = Savings { contribution, ..savings.clone() };
+	if csv_path != Some(Path::new("-")) {
+		println!(
+			"{}",
+			format!(
+				"##==>> Save ${:.2} {} to Reach ${:.2} in {} Years",
+				contribution,
+				savings.contribution_frequency,
+				target,
+				savings.years,
+			).yellow()
+		);
+	}
+	print_compound(&savings, csv_path)
+}
+
+// Show Every Loan Payment and When the Loan Is Paid Off
+pub fn print_loan(loan: &Loan, csv_path: Option<&Path>) -> Result<(), Box<dyn Error>> {
+	let schedule = amortize(loan)?;
+	if csv_path != Some(Path::new("-")) {
+		println!("{}\n", schedule_table(&schedule));
+		println!("{}", format!("##==>> Payment: ${:.2} {}", schedule.scheduled_payment, loan.frequency).yellow());
+		println!("{}", format!("##==>> Total Paid: ${:.2}", schedule.total_paid).yellow());
+		println!("{}", format!("##==>> Total Interest: ${:.2}", schedule.total_interest).yellow());
+		println!(
+			"{}",
+			format!("##==>> Paid Off: {} After {} Payments", schedule.payoff_date.format(FINANCE_DATE_FORMAT), schedule.payments.len()).yellow()
+		);
+		if loan.extra_payment > 0.0 {
+			let without_extra = amortize(&Loan { extra_payment: 0.0, ..loan.clone() })?;
+			println!(
+				"{}",
+				format!(
+					"##==>> Paying Extra Saves ${:.2} in Interest and {} Payments",
+					without_extra.total_interest - schedule.total_interest,
+					without_extra.payments.len() - schedule.payments.len(),
+				).green()
+			);
 		}
+	}
+	match csv_path {
+		Some(csv_path) => export_csv(&schedule.payments, csv_path),
+		None => Ok(()),
+	}
+}
+
+pub fn dave_interest_calc_loop() -> io::Result<()> {
+	println!("{}", "-- Dave's Interest Calculator --".cyan());
+	println!("{}", "================================".cyan());
+
+	let savings = Savings {
+		principal: prompt_number("Enter the amount of money earning interest", None)?,
+		annual_rate: prompt_number("Enter the yearly interest rate (%)", None)?,
+		years: prompt_number("Enter the interest period (years)", None)?,
+		compounding: Period::Monthly,
+		contribution: prompt_number("Enter a monthly contribution", Some(0.0))?,
+		contribution_frequency: Period::Monthly,
 	};
-	
-	let total_accrued = principal * (1.0 + ((rate/100.0) * time));
-	let interest = total_accrued - principal;
-	let accrued_string = format!("##==>> Total Accrued Amount: ${:.2}", total_accrued);
-	let interest_string = format!("##==>> Interest Paid: ${:.2}", interest);
-	println!("{}", accrued_string.yellow());
-	println!("{}", interest_string.yellow());
+	if let Err(error) = print_compound(&savings, None) {
+		eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
+	}
 	Ok(())
 }
@@ -15,7 +15,7 @@ use std::f32::consts::{
 };
 use std::io;
 use std::time::Duration;
-use crate::dave_graphics::ASSETS_DIR;
+use crate::dave_graphics::assets_dir;
 
 #[derive(Clone, Copy, Eq, PartialEq, Debug, Default, Hash, States, Reflect)]
 pub enum GameState {
@@ -123,7 +123,7 @@ fn dave_cars_setup(
 
 	// Setup Game Audio
 	commands.spawn(AudioBundle {
-		source: asset_server.load(ASSETS_DIR.to_owned() + "/audio/stalker.ogg"),
+		source: asset_server.load(assets_dir() + "/audio/stalker.ogg"),
 		settings: PlaybackSettings {
 			mode: bevy::audio::PlaybackMode::Loop,
 			..default()
@@ -131,19 +131,19 @@ fn dave_cars_setup(
 		..default()
 	});
 	let score_sound = asset_server.load(
-		ASSETS_DIR.to_owned() + "/audio/collision.ogg",
+		assets_dir() + "/audio/collision.ogg",
 	);
 	commands.insert_resource(ScoreSound(score_sound));
 	let crash_sound = asset_server.load(
-		ASSETS_DIR.to_owned() + "/audio/crash.ogg",
+		assets_dir() + "/audio/crash.ogg",
 	);
 	commands.insert_resource(CrashSound(crash_sound));
 	let engine_sound = asset_server.load(
-		ASSETS_DIR.to_owned() + "/audio/revving.ogg",
+		assets_dir() + "/audio/revving.ogg",
 	);
 	commands.insert_resource(EngineSound(engine_sound));
 	let tire_sound = asset_server.load(
-		ASSETS_DIR.to_owned() + "/audio/tires.ogg",
+		assets_dir() + "/audio/tires.ogg",
 	);
 	commands.insert_resource(TireSound(tire_sound));
 
@@ -165,7 +165,7 @@ fn dave_cars_setup(
 			.with_children(|parent| {
 				parent.spawn(SceneBundle {
 					scene: asset_server.load(
-						ASSETS_DIR.to_owned() + "/models/dave_cars/road_straight.glb#Scene0",
+						assets_dir() + "/models/dave_cars/road_straight.glb#Scene0",
 					),
 					..Default::default()
 				});
@@ -183,7 +183,7 @@ fn dave_cars_setup(
 				.with_children(|parent| {
 					parent.spawn(SceneBundle {
 						scene: asset_server.load(
-							ASSETS_DIR.to_owned() + "/models/dave_cars/lamp.glb#Scene0",
+							assets_dir() + "/models/dave_cars/lamp.glb#Scene0",
 						),
 						..Default::default()
 					});
@@ -202,7 +202,7 @@ fn dave_cars_setup(
 				.with_children(|parent| {
 					parent.spawn(SceneBundle {
 						scene: asset_server.load(
-							ASSETS_DIR.to_owned() + "/models/dave_cars/lamp.glb#Scene0",
+							assets_dir() + "/models/dave_cars/lamp.glb#Scene0",
 						),
 						..Default::default()
 					});
@@ -233,7 +233,7 @@ fn dave_cars_setup(
 			.with_children(|parent| {
 				parent.spawn(SceneBundle {
 					scene: asset_server.load(
-						ASSETS_DIR.to_owned() + "/models/dave_cars/trophy.glb#Scene0",
+						assets_dir() + "/models/dave_cars/trophy.glb#Scene0",
 					),
 					..Default::default()
 				});
@@ -262,7 +262,7 @@ fn dave_cars_setup(
 	.with_children(|parent| {
 		parent.spawn(SceneBundle {
 			scene: asset_server.load(
-				ASSETS_DIR.to_owned() + player_car,
+				assets_dir() + player_car,
 			),
 			..Default::default()
 		});
@@ -275,7 +275,7 @@ fn dave_cars_setup(
 			"Score:",
 			TextStyle {
 				font: asset_server.load(
-					ASSETS_DIR.to_owned() + "/fonts/FiraSans-Bold.ttf",
+					assets_dir() + "/fonts/FiraSans-Bold.ttf",
 				),
 				font_size: 40.0,
 				color: Color::rgb(0.5, 0.5, 1.0),
@@ -295,7 +295,7 @@ fn dave_cars_setup(
 			"Best:",
 			TextStyle {
 				font: asset_server.load(
-					ASSETS_DIR.to_owned() + "/fonts/FiraSans-Bold.ttf",
+					assets_dir() + "/fonts/FiraSans-Bold.ttf",
 				),
 				font_size: 40.0,
 				color: Color::rgb(0.5, 0.5, 1.0),
@@ -394,7 +394,7 @@ fn move_street(
 				.with_children(|parent| {
 					parent.spawn(SceneBundle {
 						scene: asset_server.load(
-							ASSETS_DIR.to_owned() + "/models/dave_cars/trophy.glb#Scene0",
+							assets_dir() + "/models/dave_cars/trophy.glb#Scene0",
 						),
 						..Default::default()
 					});
@@ -472,10 +472,10 @@ fn spawn_obstacle(
 	let die = Uniform::from(0..3);
 	let ran_street = die.sample(&mut rng);
 
-	let sports_car = ASSETS_DIR.to_owned() + "/models/dave_cars/hatchbackSports.glb#Scene0";
-	let police_car = ASSETS_DIR.to_owned() + "/models/dave_cars/police.glb#Scene0";
-	let sedan_car = ASSETS_DIR.to_owned() + "/models/dave_cars/sedan.glb#Scene0";
-	let tractor = ASSETS_DIR.to_owned() + "/models/dave_cars/tractor.glb#Scene0";
+	let sports_car = assets_dir() + "/models/dave_cars/hatchbackSports.glb#Scene0";
+	let police_car = assets_dir() + "/models/dave_cars/police.glb#Scene0";
+	let sedan_car = assets_dir() + "/models/dave_cars/sedan.glb#Scene0";
+	let tractor = assets_dir() + "/models/dave_cars/tractor.glb#Scene0";
 
 	let obstacle_models = vec![
 		sports_car,
@@ -553,7 +553,7 @@ fn collision_obstacle(
 				.with_children(|parent| {
 					parent.spawn(SceneBundle {
 						scene: asset_server.load(
-							ASSETS_DIR.to_owned() + "/models/dave_cars/fireball.glb#Scene0",
+							assets_dir() + "/models/dave_cars/fireball.glb#Scene0",
 						),
 						..Default::default()
 					});
@@ -604,7 +604,7 @@ fn show_text(
 				"Press Any Key to Restart ...",
 				TextStyle {
 					font: asset_server.load(
-						ASSETS_DIR.to_owned() + "/fonts/FiraSans-Bold.ttf",
+						assets_dir() + "/fonts/FiraSans-Bold.ttf",
 					),
 					font_size: 40.0,
 					color: Color::rgb(0.5, 0.5, 1.0),
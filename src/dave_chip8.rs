@@ -594,7 +594,7 @@ mod test {
 		let mut file = File::options()
 			.read(true)
 			.create(false)
-			.open("./dave_conf/var/daves_roms/TETRIS")
+			.open(crate::config::find_roms_path().join("TETRIS"))
 			.expect("Unable to Open File");
 		let mut file_contents: Vec<u8> = Vec::new();
 		file.read_to_end(&mut file_contents).expect("Unable to Read File");
@@ -1,46 +1,531 @@
-use sled::{Config, Db};
-use crate::dave_notes::DaveNote;
-use std::io;
-use std::path::PathBuf;
-use std::process::exit;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sled::transaction::{ConflictableTransactionError, TransactionError};
+use sled::{Config, Db, Transactional, Tree};
+use crate::config::CONFIG;
+use crate::dave_integrity::IntegrityBaseline;
+use crate::dave_vault::{open_bytes, seal_bytes, Vault};
+use crate::dave_notes::{
+	find_existing,
+	DaveNote,
+	LegacyDaveNote,
+	NoteFormat,
+	NoteImport,
+};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Legacy Notes Were Keyed by a SHA-256 of Their Title
+const LEGACY_NOTE_KEY_LEN: usize = 32;
+
+// Everything That Can Go Wrong Talking to the Database
+#[derive(Debug)]
+pub enum DaveDbError {
+	Config(String),
+	Sled(sled::Error),
+	Encoding(bincode::Error),
+	Io(std::io::Error),
+	Encrypted(std::io::Error),
+}
+
+impl fmt::Display for DaveDbError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			DaveDbError::Config(message) => write!(f, "Database Configuration Error: {}", message),
+			DaveDbError::Sled(error) => write!(f, "Database Error: {}", error),
+			DaveDbError::Encoding(error) => write!(f, "Database Record Could Not be Decoded: {}", error),
+			DaveDbError::Io(error) => write!(f, "Database I/O Error: {}", error),
+			DaveDbError::Encrypted(error) => write!(f, "Encrypted Record Could Not be Read: {}", error),
+		}
+	}
+}
+
+impl Error for DaveDbError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			DaveDbError::Config(_) => None,
+			DaveDbError::Sled(error) => Some(error),
+			DaveDbError::Encoding(error) => Some(error),
+			DaveDbError::Io(error) => Some(error),
+			DaveDbError::Encrypted(error) => Some(error),
+		}
+	}
+}
+
+impl From<sled::Error> for DaveDbError {
+	fn from(error: sled::Error) -> Self {
+		DaveDbError::Sled(error)
+	}
+}
+
+impl From<bincode::Error> for DaveDbError {
+	fn from(error: bincode::Error) -> Self {
+		DaveDbError::Encoding(error)
+	}
+}
+
+impl From<std::io::Error> for DaveDbError {
+	fn from(error: std::io::Error) -> Self {
+		DaveDbError::Io(error)
+	}
+}
+
+pub type DbResult<T> = Result<T, DaveDbError>;
+
+// Named Trees Each Feature Keeps Its Data In
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DaveTree {
+	Notes,
+	Budget,
+	QuizScores,
+	HighScores,
+	Integrity,
+}
+
+impl DaveTree {
+	pub fn name(&self) -> &'static str {
+		match self {
+			DaveTree::Notes => "notes",
+			DaveTree::Budget => "budget",
+			DaveTree::QuizScores => "quiz_scores",
+			DaveTree::HighScores => "high_scores",
+			DaveTree::Integrity => "integrity",
+		}
+	}
+
+	// Trees Holding Personal Data, Which Are Encrypted When a Vault Is Attached
+	pub fn is_private(&self) -> bool {
+		matches!(self, DaveTree::Notes | DaveTree::Budget)
+	}
+}
+
+// Best and Most Recent Result for a Quiz or Game
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ScoreRecord {
+	pub best: u64,
+	pub last: u64,
+	pub attempts: u64,
+	pub updated: DateTime<Utc>,
+}
+
+impl ScoreRecord {
+	pub fn is_new_best(&self) -> bool {
+		self.best == self.last && self.last > 0
+	}
+}
+
+// Handle Every Persistent Feature Shares
+pub struct DaveDatabase {
+	db: Db,
+	path: Option<PathBuf>,
+	vault: Option<Vault>,
+}
 
-pub struct DaveDatabase;
 impl DaveDatabase {
-	// Configure A New Database
-	pub fn config(db_location: PathBuf) -> io::Result<Db> {
-		let config = match Config::new()
-			.create_new(true)
-			.path(db_location.to_owned())
-			.open() {
-				Ok(c) => c,
-				Err(sled::Error::Io(_error)) => {
-					let config = match sled::open(db_location.to_owned()) {
-						Ok(config) => config,
-						Err(error) => {
-							eprintln!("{}", error);
-							exit(1)
+	// Open the Database Configured in CONFIG
+	pub fn open() -> DbResult<Self> {
+		let (path, vault) = match CONFIG.read() {
+			Ok(config) => (config.database_path(), Vault::from_config(&config)),
+			Err(error) => return Err(DaveDbError::Config(error.to_string())),
+		};
+		Ok(Self::open_at(path)?.with_vault(vault))
+	}
+
+	// Open or Create a Database at a Specific Location
+	pub fn open_at<P: AsRef<Path>>(db_location: P) -> DbResult<Self> {
+		let db_location = db_location.as_ref();
+		if let Some(parent) = db_location.parent() {
+			if !parent.as_os_str().is_empty() {
+				fs::create_dir_all(parent)?;
+			}
+		}
+		// Persist the ID Counter on Every Call so IDs
+		// Stay Small and Readable Across Restarts
+		let db = Config::new()
+			.path(db_location)
+			.idgen_persist_interval(1)
+			.open()?;
+		Ok(DaveDatabase { db, path: Some(db_location.to_path_buf()), vault: None })
+	}
+
+	// Database That Lives Only as Long as the Handle
+	pub fn temporary() -> DbResult<Self> {
+		let db = Config::new().temporary(true).open()?;
+		Ok(DaveDatabase { db, path: None, vault: None })
+	}
+
+	// Encrypt Private Trees With This Vault
+	pub fn with_vault(mut self, vault: Option<Vault>) -> Self {
+		self.vault = vault;
+		self
+	}
+
+	fn encode<T: Serialize>(&self, tree: DaveTree, value: &T) -> DbResult<Vec<u8>> {
+		let bytes = bincode::serialize(value)?;
+		if !tree.is_private() {
+			return Ok(bytes)
+		}
+		seal_bytes(self.vault.as_ref(), bytes).map_err(DaveDbError::Encrypted)
+	}
+
+	fn decode<T: DeserializeOwned>(&self, tree: DaveTree, bytes: &[u8]) -> DbResult<T> {
+		if !tree.is_private() {
+			return Ok(bincode::deserialize(bytes)?)
+		}
+		let bytes = open_bytes(self.vault.as_ref(), bytes.to_vec()).map_err(DaveDbError::Encrypted)?;
+		Ok(bincode::deserialize(&bytes)?)
+	}
+
+	// Re-encrypt Every Private Record for a New Vault, or Decrypt Them
+	// All When None Is Given. Everything Is Read and Resealed Before
+	// Anything Is Written, and Every Record Switches Over in One
+	// Transaction, so a Failure Part Way Leaves Them All as They Were
+	pub fn reseal(&mut self, vault: Option<Vault>) -> DbResult<usize> {
+		let notes = self.tree(DaveTree::Notes)?;
+		let budget = self.tree(DaveTree::Budget)?;
+		let mut records = Vec::new();
+		for (index, tree) in [&notes, &budget].into_iter().enumerate() {
+			for entry in tree.iter() {
+				let (key, value) = entry?;
+				let bytes = open_bytes(self.vault.as_ref(), value.to_vec()).map_err(DaveDbError::Encrypted)?;
+				let sealed = seal_bytes(vault.as_ref(), bytes).map_err(DaveDbError::Encrypted)?;
+				records.push((index, key, sealed));
+			}
+		}
+		(&notes, &budget)
+			.transaction(|(notes, budget)| {
+				for (index, key, sealed) in &records {
+					let tree = if *index == 0 { notes } else { budget };
+					tree.insert(key, sealed.clone())?;
+				}
+				Ok::<(), ConflictableTransactionError<()>>(())
+			})
+			.map_err(|error| match error {
+				TransactionError::Storage(error) => DaveDbError::Sled(error),
+				TransactionError::Abort(()) => DaveDbError::Config("Resealing Was Aborted".to_string()),
+			})?;
+		self.vault = vault;
+		self.flush()?;
+		Ok(records.len())
+	}
+
+	pub fn tree(&self, tree: DaveTree) -> DbResult<Tree> {
+		Ok(self.db.open_tree(tree.name())?)
+	}
+
+	pub fn generate_id(&self) -> DbResult<u64> {
+		Ok(self.db.generate_id()? + 1)
+	}
+
+	pub fn get<T: DeserializeOwned>(&self, tree: DaveTree, key: &[u8]) -> DbResult<Option<T>> {
+		match self.tree(tree)?.get(key)? {
+			Some(bytes) => Ok(Some(self.decode(tree, &bytes)?)),
+			None => Ok(None),
+		}
+	}
+
+	pub fn insert<T: Serialize>(&self, tree: DaveTree, key: &[u8], value: &T) -> DbResult<()> {
+		let bytes = self.encode(tree, value)?;
+		self.tree(tree)?.insert(key, bytes)?;
+		Ok(())
+	}
+
+	pub fn remove<T: DeserializeOwned>(&self, tree: DaveTree, key: &[u8]) -> DbResult<Option<T>> {
+		match self.tree(tree)?.remove(key)? {
+			Some(bytes) => Ok(Some(self.decode(tree, &bytes)?)),
+			None => Ok(None),
+		}
+	}
+
+	// Every Value in a Tree, in Key Order
+	pub fn values<T: DeserializeOwned>(&self, tree: DaveTree) -> DbResult<Vec<T>> {
+		let mut values = Vec::new();
+		for value in self.tree(tree)?.iter().values() {
+			values.push(self.decode(tree, &value?)?);
+		}
+		Ok(values)
+	}
+
+	pub fn clear(&self, tree: DaveTree) -> DbResult<()> {
+		self.tree(tree)?.clear()?;
+		Ok(())
+	}
+
+	pub fn flush(&self) -> DbResult<()> {
+		self.db.flush()?;
+		Ok(())
+	}
+
+	// Move Notes Written by Older Versions Into the Notes Tree
+	pub fn migrate_notes(&self) -> DbResult<usize> {
+		let mut migrated = Self::migrate_legacy_entries(&self.db, self)?;
+
+		// Older Versions Also Kept Notes in Their Own Database
+		let legacy_path = match CONFIG.read() {
+			Ok(config) => config.notes_path(),
+			Err(error) => return Err(DaveDbError::Config(error.to_string())),
+		};
+		migrated += self.import_notes_db(&legacy_path)?;
+
+		if migrated > 0 {
+			self.flush()?;
+		}
+		Ok(migrated)
+	}
+
+	// Pull Every Note Out of a Standalone Notes Database, Then
+	// Set It Aside so It Is Only Imported Once
+	pub fn import_notes_db(&self, legacy_path: &Path) -> DbResult<usize> {
+		if !legacy_path.is_dir() || self.path.as_deref() == Some(legacy_path) {
+			return Ok(0)
+		}
+		let mut migrated = 0;
+		{
+			let legacy = sled::open(legacy_path)?;
+			migrated += Self::migrate_legacy_entries(&legacy, self)?;
+			for value in legacy.open_tree(DaveTree::Notes.name())?.iter().values() {
+				let note: DaveNote = self.decode(DaveTree::Notes, &value?)?;
+				self.add_note(note)?;
+				migrated += 1;
+			}
+		}
+		let mut retired = legacy_path.as_os_str().to_owned();
+		retired.push(".migrated");
+		fs::rename(legacy_path, PathBuf::from(retired))?;
+		Ok(migrated)
+	}
+
+	fn migrate_legacy_entries(source: &Db, target: &DaveDatabase) -> DbResult<usize> {
+		let mut legacy_notes = Vec::new();
+		for entry in source.iter() {
+			let (key, value) = entry?;
+			if key.len() != LEGACY_NOTE_KEY_LEN {
+				continue
+			}
+			if let Ok(legacy) = bincode::deserialize::<LegacyDaveNote>(&value) {
+				legacy_notes.push((key, legacy));
+			}
+		}
+		legacy_notes.sort_by_key(|(_, legacy)| legacy.id);
+
+		let migrated = legacy_notes.len();
+		for (key, legacy) in legacy_notes {
+			target.add_note(DaveNote::from(legacy))?;
+			source.remove(key)?;
+		}
+		Ok(migrated)
+	}
+
+	// Give a Note a New Unique ID and Store It
+	pub fn add_note(&self, mut data: DaveNote) -> DbResult<DaveNote> {
+		data.id = self.generate_id()?;
+		self.update_note(&data)?;
+		Ok(data)
+	}
+
+	pub fn update_note(&self, data: &DaveNote) -> DbResult<()> {
+		self.insert(DaveTree::Notes, &data.id.to_be_bytes(), data)
+	}
+
+	pub fn get_note(&self, id: u64) -> DbResult<Option<DaveNote>> {
+		self.get(DaveTree::Notes, &id.to_be_bytes())
+	}
+
+	pub fn delete_note(&self, id: u64) -> DbResult<Option<DaveNote>> {
+		self.remove(DaveTree::Notes, &id.to_be_bytes())
+	}
+
+	// Every Stored Note in ID Order
+	pub fn all_notes(&self) -> DbResult<Vec<DaveNote>> {
+		self.values(DaveTree::Notes)
+	}
+
+	// Store Imported Notes, Skipping Ones Already Present. A Newer
+	// Copy of an Existing Note Brings Its Completion State Along.
+	// Markdown Has No Timestamps, so Its Notes Only Count as Newer
+	// When What They Say Differs, and Only Then Is updated Moved
+	pub fn import_notes(&self, imported: Vec<DaveNote>, format: NoteFormat) -> DbResult<NoteImport> {
+		let mut existing = self.all_notes()?;
+		let mut summary = NoteImport::default();
+		for note in imported {
+			match find_existing(&existing, &note, format).cloned() {
+				Some(mut current) => {
+					let changed = current.completed != note.completed
+						|| current.tags != note.tags
+						|| current.priority != note.priority
+						|| current.due != note.due;
+					let timestamped = format != NoteFormat::Markdown;
+					if changed && (!timestamped || note.updated > current.updated) {
+						current.completed = note.completed;
+						current.tags = note.tags;
+						current.priority = note.priority;
+						current.due = note.due;
+						match timestamped {
+							true => current.updated = note.updated,
+							false => current.touch(),
 						}
-					};
-					config
+						self.update_note(&current)?;
+						summary.updated += 1;
+					} else {
+						summary.unchanged += 1;
+					}
 				},
-				Err(error) => {
-					eprintln!("{}", error);
-					exit(1)
+				None => {
+					existing.push(self.add_note(note)?);
+					summary.added += 1;
 				},
-			};
-		Ok(config)
+			}
+		}
+		self.flush()?;
+		Ok(summary)
 	}
 
-	// Insert Value Into Database
-	pub fn update(db: &mut sled::Db, data: DaveNote, id: &[u8]) -> io::Result<()> {
-		let bytes = match bincode::serialize(&data) {
-			Ok(bytes) => bytes,
-			Err(error) => {
-				eprintln!("{}", error);
-				std::process::exit(1)
+	pub fn clear_notes(&self) -> DbResult<()> {
+		self.db.clear()?;
+		self.clear(DaveTree::Notes)
+	}
+
+	// Record a Result in the Quiz Score or High Score Tree
+	pub fn record_score(&self, tree: DaveTree, name: &str, score: u64) -> DbResult<ScoreRecord> {
+		let record = match self.get::<ScoreRecord>(tree, name.as_bytes())? {
+			Some(previous) => ScoreRecord {
+				best: previous.best.max(score),
+				last: score,
+				attempts: previous.attempts + 1,
+				updated: Utc::now(),
 			},
+			None => ScoreRecord { best: score, last: score, attempts: 1, updated: Utc::now() },
 		};
-		db.insert(id, bytes)?;
-		Ok(())
+		self.insert(tree, name.as_bytes(), &record)?;
+		Ok(record)
+	}
+
+	pub fn score(&self, tree: DaveTree, name: &str) -> DbResult<Option<ScoreRecord>> {
+		self.get(tree, name.as_bytes())
+	}
+
+	// Integrity Baselines Are Keyed by Their Canonical Root Directory
+	pub fn save_baseline(&self, baseline: &IntegrityBaseline) -> DbResult<()> {
+		self.insert(DaveTree::Integrity, baseline.root.to_string_lossy().as_bytes(), baseline)?;
+		self.flush()
+	}
+
+	pub fn baseline(&self, root: &Path) -> DbResult<Option<IntegrityBaseline>> {
+		self.get(DaveTree::Integrity, root.to_string_lossy().as_bytes())
+	}
+
+	pub fn all_baselines(&self) -> DbResult<Vec<IntegrityBaseline>> {
+		self.values(DaveTree::Integrity)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::dave_notes::{export_notes, parse_notes};
+	use sha2::{Digest, Sha256};
+
+	#[test]
+	fn can_migrate_legacy_notes() {
+		let db = DaveDatabase::temporary().unwrap();
+		for (id, title) in [(2, "second"), (1, "first")] {
+			let legacy = LegacyDaveNote { id, title: title.to_string(), completed: id == 2 };
+			let key = Sha256::digest(title.as_bytes()).to_vec();
+			db.db.insert(key, bincode::serialize(&legacy).unwrap()).unwrap();
+		}
+
+		assert_eq!(2, DaveDatabase::migrate_legacy_entries(&db.db, &db).unwrap());
+		assert_eq!(0, DaveDatabase::migrate_legacy_entries(&db.db, &db).unwrap());
+		assert!(db.db.is_empty());
+
+		let notes = db.all_notes().unwrap();
+		let titles: Vec<&str> = notes.iter().map(|n| n.title.as_str()).collect();
+		assert_eq!(vec!["first", "second"], titles);
+		assert!(notes[1].completed);
+		assert_ne!(notes[0].id, notes[1].id);
+	}
+
+	#[test]
+	fn can_reuse_titles_without_collisions() {
+		let db = DaveDatabase::temporary().unwrap();
+		let mut note = DaveNote::new();
+		note.title = "same".to_string();
+		let first = db.add_note(note.clone()).unwrap();
+		let second = db.add_note(note).unwrap();
+		assert!(second.id > first.id);
+
+		db.delete_note(first.id).unwrap();
+		assert_eq!(None, db.get_note(first.id).unwrap());
+		assert_eq!(1, db.all_notes().unwrap().len());
+	}
+
+	#[test]
+	fn can_import_without_duplicates() {
+		let db = DaveDatabase::temporary().unwrap();
+		let mut note = DaveNote::new();
+		note.title = "write report".to_string();
+		db.add_note(note).unwrap();
+
+		let mut buffer = Vec::new();
+		export_notes(&db.all_notes().unwrap(), NoteFormat::Json, &mut buffer).unwrap();
+		let imported = parse_notes(NoteFormat::Json, &buffer[..]).unwrap();
+		let summary = db.import_notes(imported, NoteFormat::Json).unwrap();
+		assert_eq!(NoteImport { added: 0, updated: 0, unchanged: 1 }, summary);
+
+		let checklist = "- [x] write report\n- [ ] file taxes #home\n";
+		let imported = parse_notes(NoteFormat::Markdown, checklist.as_bytes()).unwrap();
+		let summary = db.import_notes(imported, NoteFormat::Markdown).unwrap();
+		assert_eq!(NoteImport { added: 1, updated: 1, unchanged: 0 }, summary);
+
+		let notes = db.all_notes().unwrap();
+		assert_eq!(2, notes.len());
+		assert!(notes[0].completed);
+		assert_eq!(vec!["home".to_string()], notes[1].tags);
+
+		// The Same Checklist Again Leaves Every Note as It Was
+		let imported = parse_notes(NoteFormat::Markdown, checklist.as_bytes()).unwrap();
+		let summary = db.import_notes(imported, NoteFormat::Markdown).unwrap();
+		assert_eq!(NoteImport { added: 0, updated: 0, unchanged: 2 }, summary);
+		assert_eq!(notes, db.all_notes().unwrap());
+	}
+
+	#[test]
+	fn can_record_scores() {
+		let db = DaveDatabase::temporary().unwrap();
+		assert!(db.record_score(DaveTree::HighScores, "snake", 4).unwrap().is_new_best());
+		let record = db.record_score(DaveTree::HighScores, "snake", 2).unwrap();
+		assert_eq!((4, 2, 2), (record.best, record.last, record.attempts));
+		assert!(!record.is_new_best());
+		assert_eq!(None, db.score(DaveTree::QuizScores, "snake").unwrap());
+	}
+
+	#[test]
+	fn can_encrypt_notes_at_rest() {
+		let dir = tempfile::tempdir().unwrap();
+		let mut db = DaveDatabase::temporary().unwrap();
+		let mut note = DaveNote::new();
+		note.title = "call the bank".to_string();
+		let plain = db.add_note(note).unwrap();
+
+		assert_eq!(1, db.reseal(Some(Vault::generate(dir.path().join("vault.key")))).unwrap());
+		let raw = db.tree(DaveTree::Notes).unwrap().get(plain.id.to_be_bytes()).unwrap().unwrap();
+		assert!(crate::dave_vault::is_sealed(&raw));
+		assert_eq!(Some(plain.clone()), db.get_note(plain.id).unwrap());
+		let sealed = db.add_note(DaveNote::new()).unwrap();
+		assert_eq!(2, db.all_notes().unwrap().len());
+
+		// Scores Aren't Private
+		db.record_score(DaveTree::HighScores, "snake", 3).unwrap();
+		let raw = db.tree(DaveTree::HighScores).unwrap().get("snake").unwrap().unwrap();
+		assert!(bincode::deserialize::<ScoreRecord>(&raw).is_ok());
+
+		// Rekeyed Records Only Open With the New Key
+		assert_eq!(2, db.reseal(Some(Vault::generate(dir.path().join("vault.key")))).unwrap());
+		assert_eq!(Some(sealed.clone()), db.get_note(sealed.id).unwrap());
+		db.vault = Some(Vault::generate(dir.path().join("vault.key")));
+		assert!(matches!(db.get_note(sealed.id), Err(DaveDbError::Encrypted(_))));
 	}
 }
@@ -0,0 +1,283 @@
+use bytesize::ByteSize;
+use crate::dave_hash::{hash_file, HashType, Hasher};
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+// Bytes Read From the Start of Each File for the Cheap First Comparison
+pub const PARTIAL_HASH_SIZE: u64 = 4 * 1024;
+
+// What to Do With the Extra Copies in Each Set
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DupeAction {
+	Report,
+	Hardlink,
+	Delete,
+}
+
+// Files With Identical Contents. The First Is the One Kept
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DuplicateSet {
+	pub size: u64,
+	pub digest: String,
+	pub files: Vec<PathBuf>,
+}
+
+impl DuplicateSet {
+	// Space That Would be Freed by Keeping a Single Copy
+	pub fn wasted(&self) -> u64 {
+		self.size * (self.files.len() as u64 - 1)
+	}
+}
+
+#[derive(Debug, Default)]
+pub struct DupesReport {
+	pub sets: Vec<DuplicateSet>,
+	// Files That Couldn't be Read, Left Out of the Comparison
+	pub errors: Vec<(PathBuf, io::Error)>,
+}
+
+impl DupesReport {
+	pub fn wasted(&self) -> u64 {
+		self.sets.iter().map(DuplicateSet::wasted).sum()
+	}
+}
+
+// Identity of the Data Behind a Path, so Files That Are Already Hard
+// Links to Each Other Don't Count as Copies
+#[cfg(unix)]
+fn file_id(metadata: &fs::Metadata) -> Option<(u64, u64)> {
+	use std::os::unix::fs::MetadataExt;
+	Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &fs::Metadata) -> Option<(u64, u64)> {
+	None
+}
+
+fn partial_hash(hasher: &dyn Hasher, path: &Path) -> io::Result<Vec<u8>> {
+	let file = File::open(path)?;
+	hasher.hash_reader(&mut file.take(PARTIAL_HASH_SIZE))
+}
+
+// Split Each Group Further by a Key, Dropping Groups Left With One File
+fn regroup<K, F>(groups: Vec<Vec<PathBuf>>, errors: &mut Vec<(PathBuf, io::Error)>, key: F) -> Vec<(K, Vec<PathBuf>)>
+where
+	K: Ord + Send,
+	F: Fn(&Path) -> io::Result<K> + Sync,
+{
+	let keyed: Vec<(PathBuf, io::Result<K>)> = groups
+		.into_par_iter()
+		.flatten()
+		.map(|path| {
+			let key = key(&path);
+			(path, key)
+		})
+		.collect();
+
+	let mut regrouped: BTreeMap<K, Vec<PathBuf>> = BTreeMap::new();
+	for (path, key) in keyed {
+		match key {
+			Ok(key) => regrouped.entry(key).or_default().push(path),
+			Err(error) => errors.push((path, error)),
+		}
+	}
+	regrouped.into_iter().filter(|(_, paths)| paths.len() > 1).collect()
+}
+
+// Find Files With Identical Contents Under the Given Roots. Candidates
+// Are Narrowed by Size, Then by a Hash of Their First Few Kilobytes,
+// and Only Then Hashed in Full. Empty Files Are Ignored
+pub fn find_duplicates(roots: &[PathBuf], hash_type: HashType) -> DupesReport {
+	let mut report = DupesReport::default();
+	let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+	let mut seen = HashSet::new();
+	let mut seen_paths = HashSet::new();
+
+	for root in roots {
+		for entry in WalkDir::new(root).sort_by_file_name() {
+			let entry = match entry {
+				Ok(entry) => entry,
+				Err(error) => {
+					let path = error.path().map_or_else(|| root.clone(), Path::to_path_buf);
+					report.errors.push((path, io::Error::from(error)));
+					continue
+				},
+			};
+			if !entry.file_type().is_file() {
+				continue
+			}
+			let metadata = match entry.metadata() {
+				Ok(metadata) => metadata,
+				Err(error) => {
+					report.errors.push((entry.into_path(), io::Error::from(error)));
+					continue
+				},
+			};
+			if metadata.len() == 0 {
+				continue
+			}
+			// Without File Ids the Same File Reached Twice, Through
+			// Overlapping Roots, Is Caught by Its Canonical Path Instead
+			let first_sighting = match file_id(&metadata) {
+				Some(id) => seen.insert(id),
+				None => seen_paths.insert(entry.path().canonicalize().unwrap_or_else(|_| entry.path().to_path_buf())),
+			};
+			if !first_sighting {
+				continue
+			}
+			by_size.entry(metadata.len()).or_default().push(entry.into_path());
+		}
+	}
+
+	let candidates: Vec<Vec<PathBuf>> = by_size.into_values().filter(|paths| paths.len() > 1).collect();
+	let hasher: Box<dyn Hasher> = hash_type.into();
+	let partial = regroup(candidates, &mut report.errors, |path| {
+		Ok((fs::metadata(path)?.len(), partial_hash(hasher.as_ref(), path)?))
+	});
+	let full = regroup(
+		partial.into_iter().map(|(_, paths)| paths).collect(),
+		&mut report.errors,
+		|path| Ok((fs::metadata(path)?.len(), hex::encode(hash_file(hash_type, path.to_path_buf())?))),
+	);
+
+	for ((size, digest), mut files) in full {
+		files.sort();
+		report.sets.push(DuplicateSet { size, digest, files });
+	}
+	// Biggest Savings First
+	report.sets.sort_by(|a, b| b.wasted().cmp(&a.wasted()).then_with(|| a.files.cmp(&b.files)));
+	report
+}
+
+// Swap a Duplicate for a Hard Link to the Kept Copy. The Link Is Made
+// Beside the Duplicate and Renamed Over It so Neither Is Ever Missing
+fn replace_with_link(original: &Path, duplicate: &Path) -> io::Result<()> {
+	let mut link_name = duplicate.file_name().map(OsString::from).unwrap_or_default();
+	link_name.push(".dave-link");
+	let link = duplicate.with_file_name(link_name);
+	fs::hard_link(original, &link)?;
+	fs::rename(&link, duplicate).inspect_err(|_| {
+		let _ = fs::remove_file(&link);
+	})
+}
+
+// Files Can Change Between the Scan and Acting on It, so Check a File
+// Still Has the Size and Digest the Set Was Built From
+fn verify_unchanged(set: &DuplicateSet, hash_type: HashType, path: &Path) -> io::Result<()> {
+	let unchanged = fs::metadata(path)?.len() == set.size
+		&& hex::encode(hash_file(hash_type, path.to_path_buf())?) == set.digest;
+	match unchanged {
+		true => Ok(()),
+		false => Err(io::Error::other(format!("{} Changed Since It Was Scanned, Left Alone", path.display()))),
+	}
+}
+
+// Apply an Action to Every Extra Copy in a Set, Returning What
+// Happened to Each One. Nothing Is Touched Unless Both the Kept Copy
+// and the Duplicate Still Match What Was Scanned
+pub fn resolve_set(set: &DuplicateSet, action: DupeAction, hash_type: HashType) -> Vec<(PathBuf, io::Result<()>)> {
+	let Some((original, duplicates)) = set.files.split_first() else {
+		return Vec::new()
+	};
+	duplicates
+		.iter()
+		.map(|duplicate| {
+			let verified = || verify_unchanged(set, hash_type, original).and_then(|_| verify_unchanged(set, hash_type, duplicate));
+			let result = match action {
+				DupeAction::Report => Ok(()),
+				DupeAction::Hardlink => verified().and_then(|_| replace_with_link(original, duplicate)),
+				DupeAction::Delete => verified().and_then(|_| fs::remove_file(duplicate)),
+			};
+			(duplicate.clone(), result)
+		})
+		.collect()
+}
+
+pub fn print_dupes_report(report: &DupesReport) {
+	for (index, set) in report.sets.iter().enumerate() {
+		println!(
+			"##==>> Set {}: {} Copies of {} ({} Wasted)",
+			index + 1,
+			set.files.len(),
+			ByteSize::b(set.size),
+			ByteSize::b(set.wasted()),
+		);
+		for file in &set.files {
+			println!("    {}", file.display());
+		}
+	}
+	println!(
+		"##==>> {} Duplicate Set(s), {} Wasted",
+		report.sets.len(),
+		ByteSize::b(report.wasted()),
+	);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn write(dir: &Path, name: &str, contents: &[u8]) {
+		let path = dir.join(name);
+		fs::create_dir_all(path.parent().unwrap()).unwrap();
+		fs::write(path, contents).unwrap();
+	}
+
+	#[test]
+	fn can_find_and_resolve_duplicates() {
+		let dir = tempfile::tempdir().unwrap();
+		let root = dir.path();
+		// Same Size and Same First Block, Different Tails
+		let mut long_a = vec![1u8; PARTIAL_HASH_SIZE as usize + 10];
+		let mut long_b = long_a.clone();
+		long_a.push(1);
+		long_b.push(2);
+
+		write(root, "a.png", b"same bytes");
+		write(root, "nested/b.png", b"same bytes");
+		write(root, "c.png", b"same bytez");
+		write(root, "long_a.bin", &long_a);
+		write(root, "long_b.bin", &long_b);
+		write(root, "empty_1", b"");
+		write(root, "empty_2", b"");
+		write(root, "d.png", b"same bytes");
+
+		let report = find_duplicates(&[root.to_path_buf()], HashType::Sha256);
+		assert!(report.errors.is_empty());
+		assert_eq!(1, report.sets.len());
+		let set = &report.sets[0];
+		assert_eq!(vec![root.join("a.png"), root.join("d.png"), root.join("nested/b.png")], set.files);
+		assert_eq!(20, report.wasted());
+
+		// Reporting Changes Nothing
+		assert!(resolve_set(set, DupeAction::Report, HashType::Sha256).iter().all(|(_, result)| result.is_ok()));
+		assert_eq!(1, find_duplicates(&[root.to_path_buf()], HashType::Sha256).sets.len());
+
+		// A Copy Edited Since the Scan Is Left Alone
+		write(root, "d.png", b"new contents");
+		let results = resolve_set(set, DupeAction::Delete, HashType::Sha256);
+		assert!(results[0].1.is_err());
+		assert!(results[1].1.is_ok());
+		assert!(root.join("d.png").exists());
+		write(root, "d.png", b"same bytes");
+		write(root, "nested/b.png", b"same bytes");
+
+		// Linked Copies Stop Counting as Waste
+		assert!(resolve_set(set, DupeAction::Hardlink, HashType::Sha256).iter().all(|(_, result)| result.is_ok()));
+		assert_eq!(b"same bytes", &fs::read(root.join("nested/b.png")).unwrap()[..]);
+		if cfg!(unix) {
+			assert!(find_duplicates(&[root.to_path_buf()], HashType::Sha256).sets.is_empty());
+		}
+
+		assert!(resolve_set(set, DupeAction::Delete, HashType::Sha256).iter().all(|(_, result)| result.is_ok()));
+		assert!(root.join("a.png").exists());
+		assert!(!root.join("d.png").exists());
+		assert!(!root.join("nested/b.png").exists());
+	}
+}
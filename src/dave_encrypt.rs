@@ -1,72 +1,432 @@
-use age::{DecryptError, EncryptError};
-use age::secrecy::Secret;
-use std::fs::File;
+use age::armor::{ArmoredReader, ArmoredWriter, Format};
+use age::secrecy::{ExposeSecret, Secret};
+use age::stream::{StreamReader, StreamWriter};
+use age::x25519;
+use age::{DecryptError, IdentityFile, IdentityFileEntry};
+use chrono::{SecondsFormat, Utc};
+use crate::dave_archive::{extract_archive, write_archive, ArchiveSummary};
+use crate::dave_store::write_atomic_with;
+use std::ffi::OsString;
+use std::fs::{File, OpenOptions};
 use std::io::{
 	self,
+	BufRead,
+	BufReader,
+	BufWriter,
 	Error,
 	ErrorKind,
 	Read,
 	Write,
 };
-use std::path::Path;
-
-pub fn dave_encrypt(passphrase: &str, path: &Path) -> io::Result<Vec<u8>> {
-	let mut file = File::open(path)?;
-	let mut buffer = vec![];
-	file.read_to_end(&mut buffer)?;
-	let plain_text = String::from_utf8_lossy(&buffer);
-
-	// Encrypt the plaintext to a ciphertext using given passphrase
-	let encrypted = {
-		let encryptor = age::Encryptor::with_user_passphrase(Secret::new(passphrase.to_owned()));
-		let mut encrypted = vec![];
-		let mut writer = match encryptor.wrap_output(&mut encrypted) {
-			Ok(writer) => writer,
-			Err(EncryptError::Io(_)) => {
-				return Err(Error::new(ErrorKind::Other, "IO Error"))
-			},
-			_ => unreachable!(),
-		};
-		writer.write_all(plain_text.as_bytes())?;
-		writer.finish()?;
-		encrypted
-	};
+use std::path::{Path, PathBuf};
+use termion::input::TermRead;
+
+// Extension Given to Encrypted Files
+pub const AGE_EXTENSION: &str = "age";
+
+// Extra Extension Marking an Encrypted Directory Archive, as in
+// 'secrets.davearc.age'. What's Inside a File Never Decides This
+pub const ARCHIVE_EXTENSION: &str = "davearc";
+
+// What a File Is Encrypted To
+pub enum EncryptWith {
+	Passphrase(String),
+	Recipients(Vec<x25519::Recipient>),
+}
+
+// What a File Is Decrypted With
+pub enum DecryptWith {
+	Passphrase(String),
+	Identities(Vec<x25519::Identity>),
+}
+
+// What Decryption Produced
+#[derive(Debug, PartialEq)]
+pub enum Decrypted {
+	File(PathBuf),
+	Directory(PathBuf, ArchiveSummary),
+}
+
+// Turn age's Decryption Errors Into Messages Worth Showing Someone
+fn decrypt_error(error: DecryptError) -> Error {
+	match error {
+		DecryptError::Io(error) => error,
+		DecryptError::InvalidHeader | DecryptError::UnknownFormat => {
+			Error::new(ErrorKind::InvalidData, "File Not Encrypted")
+		},
+		DecryptError::DecryptionFailed | DecryptError::KeyDecryptionFailed | DecryptError::InvalidMac => {
+			Error::new(ErrorKind::PermissionDenied, "Incorrect Passphrase")
+		},
+		DecryptError::NoMatchingKeys => {
+			Error::new(ErrorKind::PermissionDenied, "None of the Identities Can Decrypt This File")
+		},
+		DecryptError::ExcessiveWork { .. } => {
+			Error::other("Passphrase Work Factor Is Too High for This Machine")
+		},
+	}
+}
+
+// Where Encrypted Output Goes When No Path Is Given: 'notes.txt' Becomes 'notes.txt.age'
+pub fn encrypted_path(path: &Path) -> PathBuf {
+	// Components Drops Any Trailing Slash, so 'secrets/' Becomes 'secrets.age'
+	let mut name = path.components().as_path().as_os_str().to_owned();
+	name.push(".");
+	name.push(AGE_EXTENSION);
+	PathBuf::from(name)
+}
+
+// Where an Encrypted Directory Goes When No Path Is Given: 'secrets/'
+// Becomes 'secrets.davearc.age'
+pub fn encrypted_archive_path(dir: &Path) -> PathBuf {
+	let mut name = dir.components().as_path().as_os_str().to_owned();
+	name.push(format!(".{}.{}", ARCHIVE_EXTENSION, AGE_EXTENSION));
+	PathBuf::from(name)
+}
+
+// Whether a File's Name Marks It as an Encrypted Directory Archive
+pub fn is_archive_path(path: &Path) -> bool {
+	let inner = path.file_stem().map(Path::new).and_then(Path::extension);
+	path.extension().is_some_and(|extension| extension == AGE_EXTENSION)
+		&& inner.is_some_and(|extension| extension == ARCHIVE_EXTENSION)
+}
 
-	let encrypted_file_name = format!("{}_encrypted", path.display());
-	let mut encrypted_file = File::create(encrypted_file_name)?;
-	encrypted_file.write_all(&encrypted)?;
-
-	Ok(encrypted)
-}
-
-pub fn dave_decrypt(passphrase: &str, path: &Path) -> io::Result<Vec<u8>> {
-	let mut file = File::open(path)?;
-	let mut buffer = vec![];
-	file.read_to_end(&mut buffer)?;
-
-	// Decrypt ciphertext to plaintext again using same passphrase
-	let decrypted = {
-		let decryptor = match age::Decryptor::new(&buffer[..]) {
-			Ok(age::Decryptor::Passphrase(d)) => d,
-			Err(DecryptError::InvalidHeader) => {
-				return Err(Error::new(ErrorKind::Other, "File Not Encrypted"))
-			},
-			_ => unreachable!(),
-		};
-
-		let mut decrypted = vec![];
-		let mut reader = match decryptor.decrypt(&Secret::new(passphrase.to_owned()), None) {
-			Ok(reader) => reader,
-			Err(DecryptError::DecryptionFailed) => {
-				return Err(Error::new(ErrorKind::Other, "Incorrect Passphrase"))
-			},
-			_ => unreachable!(),
-		};
-		if let Err(error) = reader.read_to_end(&mut decrypted) {
-			eprintln!("##==>>>> ERROR: {}", error);
+// Where Decrypted Output Goes When No Path Is Given: 'notes.txt.age' Becomes
+// 'notes.txt' and 'secrets.davearc.age' Becomes 'secrets'. Files Without
+// the Extension Get '.decrypted' Added Instead
+pub fn decrypted_path(path: &Path) -> PathBuf {
+	if is_archive_path(path) {
+		path.with_extension("").with_extension("")
+	} else if path.extension().is_some_and(|extension| extension == AGE_EXTENSION) {
+		path.with_extension("")
+	} else {
+		let mut name = OsString::from(path.as_os_str());
+		name.push(".decrypted");
+		PathBuf::from(name)
+	}
+}
+
+pub fn parse_recipient(recipient: &str) -> io::Result<x25519::Recipient> {
+	recipient
+		.trim()
+		.parse()
+		.map_err(|_| Error::new(ErrorKind::InvalidInput, format!("Invalid Recipient '{}'. Expected an age1... Public Key", recipient.trim())))
+}
+
+// Read Public Keys One per Line. Blank Lines and '#' Comments Are Skipped
+pub fn read_recipients_file(path: &Path) -> io::Result<Vec<x25519::Recipient>> {
+	let mut recipients = Vec::new();
+	for (number, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+		let line = line?;
+		if line.trim().is_empty() || line.trim_start().starts_with('#') {
+			continue
 		}
-		decrypted
+		let recipient = parse_recipient(&line)
+			.map_err(|error| Error::new(ErrorKind::InvalidData, format!("{}:{}: {}", path.display(), number + 1, error)))?;
+		recipients.push(recipient);
+	}
+	Ok(recipients)
+}
+
+// Read the Secret Keys in an age Identity File, Like Those Written by keygen
+pub fn read_identity_file(path: &Path) -> io::Result<Vec<x25519::Identity>> {
+	let file = IdentityFile::from_buffer(BufReader::new(File::open(path)?))
+		.map_err(|error| Error::new(error.kind(), format!("{}: {}", path.display(), error)))?;
+	Ok(file
+		.into_identities()
+		.into_iter()
+		.map(|entry| match entry {
+			IdentityFileEntry::Native(identity) => identity,
+		})
+		.collect())
+}
+
+// Write a New Identity in the Same Layout as age-keygen. The File Is
+// Only Readable by its Owner, and an Existing File Is Never Replaced
+pub fn generate_identity(path: &Path) -> io::Result<x25519::Recipient> {
+	let identity = x25519::Identity::generate();
+	let recipient = identity.to_public();
+	let mut options = OpenOptions::new();
+	options.write(true).create_new(true);
+	#[cfg(unix)]
+	{
+		use std::os::unix::fs::OpenOptionsExt;
+		options.mode(0o600);
+	}
+	let mut file = options.open(path)?;
+	file.write_all(format_identity(&identity).as_bytes())?;
+	file.sync_all()?;
+	Ok(recipient)
+}
+
+pub fn format_identity(identity: &x25519::Identity) -> String {
+	format!(
+		"# created: {}\n# public key: {}\n{}\n",
+		Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+		identity.to_public(),
+		identity.to_string().expose_secret(),
+	)
+}
+
+// Ask for a Passphrase Without Echoing It. Asking Twice Catches Typos
+// When Encrypting, Where a Mistake Would Lock the File for Good
+pub fn prompt_passphrase(confirm: bool) -> io::Result<String> {
+	let read = |prompt: &str| -> io::Result<String> {
+		let mut stdout = io::stdout();
+		write!(stdout, "{}", prompt)?;
+		stdout.flush()?;
+		let passphrase = io::stdin().read_passwd(&mut stdout)?;
+		writeln!(stdout)?;
+		passphrase.ok_or_else(|| Error::new(ErrorKind::Interrupted, "Passphrase Entry Cancelled"))
+	};
+	let passphrase = read("##==> Passphrase: ")?;
+	if passphrase.is_empty() {
+		return Err(Error::new(ErrorKind::InvalidInput, "Passphrase Can't be Empty"))
+	}
+	if confirm && read("##==> Confirm Passphrase: ")? != passphrase {
+		return Err(Error::new(ErrorKind::InvalidInput, "Passphrases Don't Match"))
+	}
+	Ok(passphrase)
+}
+
+// Wrap an Output so Everything Written to It Is Encrypted. Armored
+// Output Is Plain Text That Can be Pasted Anywhere. The Writer Must be
+// Finished, Then the Armor Finished, to Complete the File
+pub fn encrypt_writer<W: Write>(key: &EncryptWith, output: W, armor: bool) -> io::Result<StreamWriter<ArmoredWriter<W>>> {
+	let encryptor = match key {
+		EncryptWith::Passphrase(passphrase) => age::Encryptor::with_user_passphrase(Secret::new(passphrase.to_owned())),
+		EncryptWith::Recipients(recipients) => {
+			let recipients = recipients
+				.iter()
+				.map(|recipient| Box::new(recipient.clone()) as Box<dyn age::Recipient + Send>)
+				.collect();
+			age::Encryptor::with_recipients(recipients)
+				.ok_or_else(|| Error::new(ErrorKind::InvalidInput, "No Recipients Given"))?
+		},
+	};
+	let format = if armor { Format::AsciiArmor } else { Format::Binary };
+	encryptor
+		.wrap_output(ArmoredWriter::wrap_output(output, format)?)
+		.map_err(|error| Error::other(error.to_string()))
+}
+
+// Encrypt Everything Read From the Input, a Chunk at a Time
+pub fn encrypt_stream<R: Read, W: Write>(key: &EncryptWith, input: &mut R, output: W, armor: bool) -> io::Result<u64> {
+	let mut writer = encrypt_writer(key, output, armor)?;
+	let written = io::copy(input, &mut writer)?;
+	writer.finish()?.finish()?;
+	Ok(written)
+}
+
+// Wrap an Input, Armored or Not, so Reads Return Plaintext. A Truncated
+// or Tampered File Fails While Reading Rather Than Ending Early
+pub fn decrypt_reader<R: Read>(key: &DecryptWith, input: R) -> io::Result<StreamReader<ArmoredReader<BufReader<R>>>> {
+	// Input Too Short to Hold a Header Isn't an age File at All
+	let decryptor = age::Decryptor::new(ArmoredReader::new(input)).map_err(|error| match error {
+		DecryptError::Io(error) if error.kind() == ErrorKind::UnexpectedEof => decrypt_error(DecryptError::InvalidHeader),
+		error => decrypt_error(error),
+	})?;
+	match (decryptor, key) {
+		(age::Decryptor::Passphrase(decryptor), DecryptWith::Passphrase(passphrase)) => {
+			decryptor.decrypt(&Secret::new(passphrase.to_owned()), None).map_err(decrypt_error)
+		},
+		(age::Decryptor::Recipients(decryptor), DecryptWith::Identities(identities)) => {
+			decryptor
+				.decrypt(identities.iter().map(|identity| identity as &dyn age::Identity))
+				.map_err(decrypt_error)
+		},
+		(age::Decryptor::Passphrase(_), DecryptWith::Identities(_)) => {
+			Err(Error::new(ErrorKind::InvalidInput, "File Was Encrypted With a Passphrase, Not to Recipients"))
+		},
+		(age::Decryptor::Recipients(_), DecryptWith::Passphrase(_)) => {
+			Err(Error::new(ErrorKind::InvalidInput, "File Was Encrypted to Recipients. Pass an Identity With '-i'"))
+		},
+	}
+}
+
+// Decrypt Everything Read From the Input
+pub fn decrypt_stream<R: Read, W: Write>(key: &DecryptWith, input: R, output: &mut W) -> io::Result<u64> {
+	io::copy(&mut decrypt_reader(key, input)?, output)
+}
+
+fn check_output(input: &Path, output: &Path) -> io::Result<()> {
+	let same = match (input.canonicalize(), output.canonicalize()) {
+		(Ok(input), Ok(output)) => input == output,
+		_ => false,
 	};
+	if same {
+		return Err(Error::new(ErrorKind::InvalidInput, "Output Would Overwrite the Input File"))
+	}
+	Ok(())
+}
+
+// Encrypt a File, Writing to the Given Output or Alongside It With '.age'
+// Added. Returns Where the Ciphertext Was Written
+pub fn dave_encrypt(key: &EncryptWith, path: &Path, output: Option<&Path>, armor: bool) -> io::Result<PathBuf> {
+	let output = output.map_or_else(|| encrypted_path(path), Path::to_path_buf);
+	check_output(path, &output)?;
+	let mut input = BufReader::new(File::open(path)?);
+	write_atomic_with(&output, |file| {
+		let mut writer = BufWriter::new(file);
+		encrypt_stream(key, &mut input, &mut writer, armor)?;
+		writer.flush()
+	})?;
+	Ok(output)
+}
+
+// Encrypt a Whole Directory Tree Into a Single Archive, Written to the
+// Given Output or Beside the Directory With '.davearc.age' Added
+pub fn dave_encrypt_directory(key: &EncryptWith, dir: &Path, output: Option<&Path>, armor: bool) -> io::Result<(PathBuf, ArchiveSummary)> {
+	let output = output.map_or_else(|| encrypted_archive_path(dir), Path::to_path_buf);
+	let inside = match (dir.canonicalize(), output.parent().map(|parent| parent.canonicalize())) {
+		(Ok(dir), Some(Ok(parent))) => parent.starts_with(dir),
+		_ => false,
+	};
+	if inside {
+		return Err(Error::new(ErrorKind::InvalidInput, "Output Can't be Inside the Directory Being Encrypted"))
+	}
+	let mut summary = ArchiveSummary::default();
+	write_atomic_with(&output, |file| {
+		let mut writer = encrypt_writer(key, BufWriter::new(file), armor)?;
+		summary = write_archive(dir, &mut writer)?;
+		writer.finish()?.finish()?.flush()
+	})?;
+	Ok((output, summary))
+}
+
+// Decrypt a File, Writing to the Given Output or Alongside It With '.age'
+// Removed. With archive the File Is Restored as a Directory, Otherwise
+// Its Contents Are Written Out Whatever They Are. Nothing Is Written
+// Unless the Whole File Decrypts
+pub fn dave_decrypt(key: &DecryptWith, path: &Path, output: Option<&Path>, archive: bool) -> io::Result<Decrypted> {
+	let output = output.map_or_else(|| decrypted_path(path), Path::to_path_buf);
+	check_output(path, &output)?;
+	let mut reader = BufReader::new(decrypt_reader(key, File::open(path)?)?);
+	if archive {
+		let summary = extract_archive(reader, &output)?;
+		return Ok(Decrypted::Directory(output, summary))
+	}
+	write_atomic_with(&output, |file| {
+		let mut writer = BufWriter::new(file);
+		io::copy(&mut reader, &mut writer)?;
+		writer.flush()
+	})?;
+	Ok(Decrypted::File(output))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::fs;
+
+	fn passphrase(passphrase: &str) -> EncryptWith {
+		EncryptWith::Passphrase(passphrase.to_string())
+	}
+
+	#[test]
+	fn can_round_trip_binary_files() {
+		let dir = tempfile::tempdir().unwrap();
+		let rom = dir.path().join("game.ch8");
+		// Every Byte Value, Including Ones That Aren't Valid UTF-8
+		let contents: Vec<u8> = (0..=255u8).cycle().take(200_000).collect();
+		fs::write(&rom, &contents).unwrap();
+
+		let encrypted = dave_encrypt(&passphrase("hunter2"), &rom, None, false).unwrap();
+		assert_eq!(dir.path().join("game.ch8.age"), encrypted);
+		assert_ne!(contents, fs::read(&encrypted).unwrap());
+
+		let restored = dir.path().join("restored.ch8");
+		assert_eq!(Decrypted::File(restored.clone()), dave_decrypt(&DecryptWith::Passphrase("hunter2".to_string()), &encrypted, Some(&restored), false).unwrap());
+		assert_eq!(contents, fs::read(&restored).unwrap());
+		assert_eq!(dir.path().join("game.ch8"), decrypted_path(&encrypted));
+	}
+
+	#[test]
+	fn can_reject_bad_input_without_panicking() {
+		let dir = tempfile::tempdir().unwrap();
+		let plain = dir.path().join("plain.txt");
+		fs::write(&plain, "not encrypted").unwrap();
+		let error = dave_decrypt(&DecryptWith::Passphrase("hunter2".to_string()), &plain, None, false).unwrap_err();
+		assert_eq!(ErrorKind::InvalidData, error.kind());
+
+		let encrypted = dave_encrypt(&passphrase("hunter2"), &plain, None, false).unwrap();
+		let error = dave_decrypt(&DecryptWith::Passphrase("wrong".to_string()), &encrypted, None, false).unwrap_err();
+		assert_eq!(ErrorKind::PermissionDenied, error.kind());
+
+		// Truncated Ciphertext Is an Error and Leaves No Output Behind
+		let bytes = fs::read(&encrypted).unwrap();
+		let truncated = dir.path().join("truncated.txt.age");
+		fs::write(&truncated, &bytes[..bytes.len() - 10]).unwrap();
+		assert!(dave_decrypt(&DecryptWith::Passphrase("hunter2".to_string()), &truncated, None, false).is_err());
+		assert!(!dir.path().join("truncated.txt").exists());
+
+		assert!(dave_encrypt(&passphrase("hunter2"), &plain, Some(&plain), false).is_err());
+	}
+
+	#[test]
+	fn can_encrypt_to_recipients_with_armor() {
+		let dir = tempfile::tempdir().unwrap();
+		let key_path = dir.path().join("key.txt");
+		let recipient = generate_identity(&key_path).unwrap();
+		assert!(generate_identity(&key_path).is_err());
+		let identities = read_identity_file(&key_path).unwrap();
+		assert_eq!(recipient.to_string(), identities[0].to_public().to_string());
+
+		let recipients_path = dir.path().join("recipients.txt");
+		fs::write(&recipients_path, format!("# Team Keys\n\n{}\n", recipient)).unwrap();
+		let recipients = read_recipients_file(&recipients_path).unwrap();
+		assert!(parse_recipient("age1nope").is_err());
+
+		let secret = dir.path().join("secret.bin");
+		fs::write(&secret, [0u8, 159, 146, 150, 255]).unwrap();
+		let armored = dave_encrypt(&EncryptWith::Recipients(recipients), &secret, None, true).unwrap();
+		let text = fs::read_to_string(&armored).unwrap();
+		assert!(text.starts_with("-----BEGIN AGE ENCRYPTED FILE-----"));
+
+		let restored = dir.path().join("restored.bin");
+		dave_decrypt(&DecryptWith::Identities(identities), &armored, Some(&restored), false).unwrap();
+		assert_eq!(vec![0u8, 159, 146, 150, 255], fs::read(&restored).unwrap());
+
+		let stranger = DecryptWith::Identities(vec![x25519::Identity::generate()]);
+		assert_eq!(ErrorKind::PermissionDenied, dave_decrypt(&stranger, &armored, Some(&restored), false).unwrap_err().kind());
+		let guess = DecryptWith::Passphrase("hunter2".to_string());
+		assert_eq!(ErrorKind::InvalidInput, dave_decrypt(&guess, &armored, Some(&restored), false).unwrap_err().kind());
+	}
+
+	#[test]
+	fn can_encrypt_directories() {
+		let dir = tempfile::tempdir().unwrap();
+		let secrets = dir.path().join("secrets");
+		fs::create_dir_all(secrets.join("certs")).unwrap();
+		fs::write(secrets.join("certs/server.key"), "-----BEGIN KEY-----").unwrap();
+
+		assert!(dave_encrypt_directory(&passphrase("hunter2"), &secrets, Some(&secrets.join("inside.age")), false).is_err());
+		let (encrypted, summary) = dave_encrypt_directory(&passphrase("hunter2"), &secrets, None, false).unwrap();
+		assert_eq!(dir.path().join("secrets.davearc.age"), encrypted);
+		assert!(is_archive_path(&encrypted));
+		assert_eq!(secrets, decrypted_path(&encrypted));
+		assert_eq!((1, 1), (summary.directories, summary.files));
+
+		let key = DecryptWith::Passphrase("hunter2".to_string());
+		let restored = dir.path().join("restored");
+		match dave_decrypt(&key, &encrypted, Some(&restored), true).unwrap() {
+			Decrypted::Directory(path, summary) => assert_eq!((restored.clone(), 1), (path, summary.files)),
+			decrypted => panic!("Expected a Directory, Got {:?}", decrypted),
+		}
+		assert_eq!("-----BEGIN KEY-----", fs::read_to_string(restored.join("certs/server.key")).unwrap());
+
+		// A Damaged Archive Restores Nothing
+		let bytes = fs::read(&encrypted).unwrap();
+		fs::write(&encrypted, &bytes[..bytes.len() - 5]).unwrap();
+		let partial = dir.path().join("partial");
+		assert!(dave_decrypt(&key, &encrypted, Some(&partial), true).is_err());
+		assert!(!partial.exists());
 
-	Ok(decrypted)
+		// A Plain File That Happens to Start Like an Archive Stays a File
+		let lookalike = dir.path().join("lookalike.bin");
+		fs::write(&lookalike, b"DAVEARC1 just some bytes").unwrap();
+		let encrypted = dave_encrypt(&passphrase("hunter2"), &lookalike, None, false).unwrap();
+		let restored = dir.path().join("lookalike.out");
+		assert_eq!(Decrypted::File(restored.clone()), dave_decrypt(&key, &encrypted, Some(&restored), is_archive_path(&encrypted)).unwrap());
+		assert_eq!(b"DAVEARC1 just some bytes".to_vec(), fs::read(&restored).unwrap());
+	}
 }
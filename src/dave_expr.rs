@@ -0,0 +1,434 @@
+use nom::{
+	self,
+	IResult,
+	branch::alt,
+	character::complete::{alpha1, alphanumeric1, char, digit0, digit1, multispace0, one_of},
+	combinator::{cut, map, map_res, opt, recognize},
+	error::ErrorKind,
+	multi::{many0_count, separated_list0, separated_list1},
+	sequence::{delimited, pair, preceded, tuple},
+};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::f64::consts;
+use std::fmt;
+
+// Named Values That Can't be Assigned To
+const CONSTANTS: [(&str, f64); 4] = [
+	("pi", consts::PI),
+	("e", consts::E),
+	("tau", consts::TAU),
+	("phi", 1.618_033_988_749_895),
+];
+
+// The Previous Result
+const ANS: &str = "ans";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Operator {
+	Add,
+	Subtract,
+	Multiply,
+	Divide,
+	Remainder,
+	Power,
+}
+
+impl Operator {
+	fn from_char(symbol: char) -> Self {
+		match symbol {
+			'+' => Operator::Add,
+			'-' => Operator::Subtract,
+			'*' => Operator::Multiply,
+			'/' => Operator::Divide,
+			'%' => Operator::Remainder,
+			_ => Operator::Power,
+		}
+	}
+}
+
+// Positions Are Kept as How Much Input Was Left, Which Is All a Parser
+// Knows. CalcError Turns Them Into Columns Against the Whole Line
+#[derive(Clone, Debug, PartialEq)]
+enum Expr {
+	Number(f64),
+	Variable(String, usize),
+	Negate(Box<Expr>),
+	Binary(Box<Expr>, Operator, Box<Expr>, usize),
+	Call(String, Vec<Expr>, usize),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Statement {
+	Assign(String, usize, Expr),
+	Evaluate(Expr),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CalcError {
+	// Counted in Characters From 1
+	pub column: usize,
+	pub message: String,
+}
+
+impl CalcError {
+	fn at(source: &str, remaining: usize, message: String) -> Self {
+		let consumed = source.len().saturating_sub(remaining);
+		CalcError { column: source[..consumed].chars().count() + 1, message }
+	}
+}
+
+impl fmt::Display for CalcError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} at Column {}", self.message, self.column)
+	}
+}
+
+impl Error for CalcError {}
+
+fn number(input: &str) -> IResult<&str, Expr> {
+	let mantissa = alt((recognize(pair(digit1, opt(pair(char('.'), digit0)))), recognize(pair(char('.'), digit1))));
+	let exponent = tuple((one_of("eE"), opt(one_of("+-")), digit1));
+	map_res(recognize(pair(mantissa, opt(exponent))), |text: &str| text.parse::<f64>().map(Expr::Number))(input)
+}
+
+fn identifier(input: &str) -> IResult<&str, &str> {
+	recognize(pair(alt((alpha1, recognize(char('_')))), many0_count(alt((alphanumeric1, recognize(char('_')))))))(input)
+}
+
+// A Closing Bracket. Failing Here Is Reported With Its Own Error Kind
+// so It Can be Told Apart From Other Characters That Didn't Match
+fn close_paren(input: &str) -> IResult<&str, char> {
+	preceded(multispace0, char(')'))(input)
+		.map_err(|error| error.map(|error: nom::error::Error<&str>| nom::error::Error::new(error.input, ErrorKind::Verify)))
+}
+
+// A Number, a Variable, a Function Call or a Bracketed Expression
+fn primary(input: &str) -> IResult<&str, Expr> {
+	let (input, _) = multispace0(input)?;
+	let at = input.len();
+	let arguments = delimited(
+		preceded(multispace0, char('(')),
+		separated_list0(char(','), expression),
+		cut(close_paren),
+	);
+	alt((
+		number,
+		map(pair(identifier, opt(arguments)), move |(name, arguments)| match arguments {
+			Some(arguments) => Expr::Call(name.to_string(), arguments, at),
+			None => Expr::Variable(name.to_string(), at),
+		}),
+		delimited(char('('), cut(expression), cut(close_paren)),
+	))(input)
+}
+
+// Powers Bind Tighter Than a Leading Minus and Group to the Right, so
+// -2^2 Is -4 and 2^3^2 Is 2^9
+fn power(input: &str) -> IResult<&str, Expr> {
+	let (input, base) = primary(input)?;
+	let (rest, _) = multispace0(input)?;
+	let at = rest.len();
+	match char::<_, nom::error::Error<&str>>('^')(rest) {
+		Ok((rest, _)) => {
+			let (rest, exponent) = cut(unary)(rest)?;
+			Ok((rest, Expr::Binary(Box::new(base), Operator::Power, Box::new(exponent), at)))
+		},
+		Err(_) => Ok((input, base)),
+	}
+}
+
+fn unary(input: &str) -> IResult<&str, Expr> {
+	let (input, _) = multispace0(input)?;
+	match one_of::<_, _, nom::error::Error<&str>>("+-")(input) {
+		Ok((rest, '-')) => map(cut(unary), |operand| Expr::Negate(Box::new(operand)))(rest),
+		Ok((rest, _)) => cut(unary)(rest),
+		Err(_) => power(input),
+	}
+}
+
+// Operands Joined by Any of the Operators, Left to Right
+fn chain<'a>(input: &'a str, operators: &str, operand: fn(&'a str) -> IResult<&'a str, Expr>) -> IResult<&'a str, Expr> {
+	let (mut input, mut left) = operand(input)?;
+	loop {
+		let (rest, _) = multispace0(input)?;
+		let at = rest.len();
+		match one_of::<_, _, nom::error::Error<&str>>(operators)(rest) {
+			Ok((rest, symbol)) => {
+				let (rest, right) = cut(operand)(rest)?;
+				left = Expr::Binary(Box::new(left), Operator::from_char(symbol), Box::new(right), at);
+				input = rest;
+			},
+			Err(_) => return Ok((input, left)),
+		}
+	}
+}
+
+fn term(input: &str) -> IResult<&str, Expr> {
+	chain(input, "*/%", unary)
+}
+
+fn expression(input: &str) -> IResult<&str, Expr> {
+	chain(input, "+-", term)
+}
+
+fn assignment(input: &str) -> IResult<&str, Statement> {
+	let (input, _) = multispace0(input)?;
+	let at = input.len();
+	let (input, name) = identifier(input)?;
+	let (input, _) = preceded(multispace0, char('='))(input)?;
+	let (input, value) = cut(expression)(input)?;
+	Ok((input, Statement::Assign(name.to_string(), at, value)))
+}
+
+fn statement(input: &str) -> IResult<&str, Statement> {
+	alt((assignment, map(expression, Statement::Evaluate)))(input)
+}
+
+// Statements Separated by ';', With Nothing Left Over
+fn parse(source: &str) -> Result<Vec<Statement>, CalcError> {
+	let parsed = separated_list1(preceded(multispace0, char(';')), statement)(source);
+	let (rest, statements) = match parsed {
+		Ok(parsed) => parsed,
+		Err(nom::Err::Error(error)) | Err(nom::Err::Failure(error)) => {
+			let message = match (error.code, error.input.chars().next()) {
+				(ErrorKind::Verify, _) => "Missing ')'".to_string(),
+				(_, None) => "Unexpected End of Expression".to_string(),
+				(_, Some(found)) => format!("Unexpected '{}'", found),
+			};
+			return Err(CalcError::at(source, error.input.len(), message))
+		},
+		Err(nom::Err::Incomplete(_)) => {
+			return Err(CalcError::at(source, 0, "Unexpected End of Expression".to_string()))
+		},
+	};
+	let (rest, _) = multispace0::<_, nom::error::Error<&str>>(rest).unwrap_or((rest, ""));
+	let rest = rest.strip_prefix(';').map(str::trim_start).unwrap_or(rest);
+	match rest.chars().next() {
+		Some(found) => Err(CalcError::at(source, rest.len(), format!("Unexpected '{}'", found))),
+		None => Ok(statements),
+	}
+}
+
+fn arity(name: &str, expected: &str, arguments: &[f64]) -> String {
+	format!("{}() Takes {}, Got {}", name, expected, arguments.len())
+}
+
+// Run a Built In Function
+fn apply(name: &str, arguments: &[f64]) -> Result<f64, String> {
+	let one = |function: fn(f64) -> f64| match arguments {
+		[x] => Ok(function(*x)),
+		_ => Err(arity(name, "1 Argument", arguments)),
+	};
+	let two = |function: fn(f64, f64) -> f64| match arguments {
+		[x, y] => Ok(function(*x, *y)),
+		_ => Err(arity(name, "2 Arguments", arguments)),
+	};
+	match name {
+		"sqrt" => one(f64::sqrt),
+		"cbrt" => one(f64::cbrt),
+		"abs" => one(f64::abs),
+		"sin" => one(f64::sin),
+		"cos" => one(f64::cos),
+		"tan" => one(f64::tan),
+		"asin" => one(f64::asin),
+		"acos" => one(f64::acos),
+		"atan" => one(f64::atan),
+		"sinh" => one(f64::sinh),
+		"cosh" => one(f64::cosh),
+		"tanh" => one(f64::tanh),
+		"exp" => one(f64::exp),
+		"ln" => one(f64::ln),
+		"log2" => one(f64::log2),
+		"log10" => one(f64::log10),
+		"floor" => one(f64::floor),
+		"ceil" => one(f64::ceil),
+		"round" => one(f64::round),
+		"trunc" => one(f64::trunc),
+		"deg" => one(f64::to_degrees),
+		"rad" => one(f64::to_radians),
+		"atan2" => two(f64::atan2),
+		"hypot" => two(f64::hypot),
+		"pow" => two(f64::powf),
+		// Base 10 Unless a Base Is Given
+		"log" => match arguments {
+			[x] => Ok(x.log10()),
+			[x, base] => Ok(x.log(*base)),
+			_ => Err(arity(name, "1 or 2 Arguments", arguments)),
+		},
+		"min" | "max" => {
+			let pick = if name == "min" { f64::min } else { f64::max };
+			arguments.iter().copied().reduce(pick).ok_or_else(|| arity(name, "at Least 1 Argument", arguments))
+		},
+		_ => Err(format!("Unknown Function '{}'", name)),
+	}
+}
+
+// Evaluates Expressions, Remembering Variables, the Last Answer and
+// Everything Worked Out So Far
+#[derive(Clone, Debug, Default)]
+pub struct Calculator {
+	variables: BTreeMap<String, f64>,
+	ans: f64,
+	history: Vec<(String, f64)>,
+}
+
+impl Calculator {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn variables(&self) -> &BTreeMap<String, f64> {
+		&self.variables
+	}
+
+	pub fn history(&self) -> &[(String, f64)] {
+		&self.history
+	}
+
+	// Evaluate a Line Like 'x = 3; x^2 + 1' and Return the Last Value
+	pub fn evaluate(&mut self, line: &str) -> Result<f64, CalcError> {
+		let statements = parse(line)?;
+		// Nothing Is Assigned Unless the Whole Line Works
+		let mut scratch = self.clone();
+		for statement in &statements {
+			let value = match statement {
+				Statement::Assign(name, at, expr) => {
+					if name == ANS || CONSTANTS.iter().any(|(constant, _)| constant == name) {
+						return Err(CalcError::at(line, *at, format!("Can't Assign to '{}'", name)))
+					}
+					let value = scratch.eval(line, expr)?;
+					scratch.variables.insert(name.clone(), value);
+					value
+				},
+				Statement::Evaluate(expr) => scratch.eval(line, expr)?,
+			};
+			scratch.ans = value;
+		}
+		scratch.history.push((line.trim().to_string(), scratch.ans));
+		*self = scratch;
+		Ok(self.ans)
+	}
+
+	fn lookup(&self, name: &str) -> Option<f64> {
+		if name == ANS {
+			return Some(self.ans)
+		}
+		CONSTANTS
+			.iter()
+			.find(|(constant, _)| *constant == name)
+			.map(|(_, value)| *value)
+			.or_else(|| self.variables.get(name).copied())
+	}
+
+	fn eval(&self, line: &str, expr: &Expr) -> Result<f64, CalcError> {
+		match expr {
+			Expr::Number(value) => Ok(*value),
+			Expr::Variable(name, at) => self
+				.lookup(name)
+				.ok_or_else(|| CalcError::at(line, *at, format!("Unknown Variable '{}'", name))),
+			Expr::Negate(operand) => Ok(-self.eval(line, operand)?),
+			Expr::Binary(left, operator, right, at) => {
+				let (left, right) = (self.eval(line, left)?, self.eval(line, right)?);
+				let fail = |message: &str| CalcError::at(line, *at, message.to_string());
+				let value = match operator {
+					Operator::Add => left + right,
+					Operator::Subtract => left - right,
+					Operator::Multiply => left * right,
+					Operator::Divide | Operator::Remainder if right == 0.0 => return Err(fail("Division by Zero")),
+					Operator::Divide => left / right,
+					Operator::Remainder => left % right,
+					Operator::Power => left.powf(right),
+				};
+				checked(value, &[left, right]).map_err(|message| fail(&message))
+			},
+			Expr::Call(name, arguments, at) => {
+				let arguments = arguments.iter().map(|argument| self.eval(line, argument)).collect::<Result<Vec<_>, _>>()?;
+				apply(name, &arguments)
+					.and_then(|value| checked(value, &arguments))
+					.map_err(|message| CalcError::at(line, *at, message))
+			},
+		}
+	}
+}
+
+// Catch Results That Went Wrong Even Though What Went In Was Fine
+fn checked(value: f64, inputs: &[f64]) -> Result<f64, String> {
+	if inputs.iter().all(|input| input.is_finite()) {
+		if value.is_nan() {
+			return Err("Result Is Undefined".to_string())
+		}
+		if value.is_infinite() {
+			return Err("Result Is Too Large".to_string())
+		}
+	}
+	Ok(value)
+}
+
+// Show a Result Without Floating Point Noise, Like 0.1 + 0.2 = 0.3
+pub fn format_number(value: f64) -> String {
+	let magnitude = value.abs();
+	if value != 0.0 && !(1e-9..1e15).contains(&magnitude) {
+		return format!("{:e}", value)
+	}
+	let text = format!("{:.12}", value);
+	let text = text.trim_end_matches('0').trim_end_matches('.');
+	match text {
+		"-0" => "0".to_string(),
+		_ => text.to_string(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn calc(line: &str) -> Result<f64, CalcError> {
+		Calculator::new().evaluate(line)
+	}
+
+	#[test]
+	fn can_respect_precedence() {
+		assert_eq!(Ok(14.0), calc("2 + 3 * 4"));
+		assert_eq!(Ok(20.0), calc("(2 + 3) * 4"));
+		assert_eq!(Ok(-4.0), calc("-2^2"));
+		assert_eq!(Ok(512.0), calc("2^3^2"));
+		assert_eq!(Ok(0.25), calc("2^-2"));
+		assert_eq!(Ok(1.0), calc("7 % 3"));
+		assert_eq!(Ok(5.0), calc("10 - 3 - 2"));
+		assert_eq!(Ok(2.0), calc("--2"));
+		assert_eq!(Ok(1500.0), calc("1.5e3"));
+		assert_eq!("0.3", format_number(calc("0.1 + 0.2").unwrap()));
+	}
+
+	#[test]
+	fn can_call_functions_and_use_variables() {
+		let mut calculator = Calculator::new();
+		assert_eq!(Ok(3.0), calculator.evaluate("sqrt(9)"));
+		assert_eq!(Ok(6.0), calculator.evaluate("ans * 2"));
+		assert_eq!(Ok(9.0), calculator.evaluate("x = 3; x^2"));
+		assert_eq!(Some(&3.0), calculator.variables().get("x"));
+		assert_eq!(Ok(3.0), calculator.evaluate("log(8, 2)"));
+		assert_eq!(Ok(1.0), calculator.evaluate("max(x, 1) - min(2, x)"));
+		assert!((calculator.evaluate("sin(pi / 2)").unwrap() - 1.0).abs() < 1e-12);
+		assert_eq!(6, calculator.history().len());
+
+		// A Failing Line Changes Nothing
+		assert!(calculator.evaluate("x = 10; y").is_err());
+		assert_eq!(Some(&3.0), calculator.variables().get("x"));
+		assert_eq!(6, calculator.history().len());
+	}
+
+	#[test]
+	fn can_report_error_columns() {
+		let error = |line| calc(line).unwrap_err();
+		assert_eq!(CalcError { column: 5, message: "Unexpected '*'".to_string() }, error("2 + * 3"));
+		assert_eq!(CalcError { column: 7, message: "Missing ')'".to_string() }, error("(1 + 2"));
+		assert_eq!(CalcError { column: 5, message: "Unknown Variable 'y'".to_string() }, error("1 + y"));
+		assert_eq!(CalcError { column: 3, message: "Division by Zero".to_string() }, error("1 / 0"));
+		assert_eq!(CalcError { column: 1, message: "Result Is Undefined".to_string() }, error("sqrt(-1)"));
+		assert_eq!(CalcError { column: 1, message: "Unknown Function 'nope'".to_string() }, error("nope(1)"));
+		assert_eq!(CalcError { column: 1, message: "Can't Assign to 'pi'".to_string() }, error("pi = 3"));
+		assert_eq!(CalcError { column: 3, message: "Unexpected '3'".to_string() }, error("2 3"));
+		assert_eq!(CalcError { column: 4, message: "Unexpected End of Expression".to_string() }, error("2 +"));
+	}
+}
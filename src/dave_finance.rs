@@ -0,0 +1,436 @@
+use chrono::{Days, Months, NaiveDate};
+use serde::Serialize;
+use std::error::Error;
+use std::fmt;
+use std::io::Write;
+use std::str::FromStr;
+use tabled::{
+	builder::Builder,
+	settings::Style,
+	Table,
+};
+
+// Format Used for Payment Dates
+pub const FINANCE_DATE_FORMAT: &str = "%Y-%m-%d";
+
+// Names Accepted Wherever a Frequency Is Asked For
+pub const FREQUENCY_NAMES: [&str; 7] = ["annually", "semiannually", "quarterly", "monthly", "biweekly", "weekly", "daily"];
+
+// Loans Are Refused Past This Many Payments, Which Only Happens When
+// the Payment Barely Covers the Interest
+const MAX_PAYMENTS: usize = 365 * 100;
+
+#[derive(Debug, PartialEq)]
+pub struct FinanceError(pub String);
+
+impl fmt::Display for FinanceError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.0)
+	}
+}
+
+impl Error for FinanceError {}
+
+fn invalid<T>(message: &str) -> Result<T, FinanceError> {
+	Err(FinanceError(message.to_string()))
+}
+
+// How Often Interest Compounds or Payments Are Made
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Period {
+	Annually,
+	SemiAnnually,
+	Quarterly,
+	Monthly,
+	Biweekly,
+	Weekly,
+	Daily,
+}
+
+impl Period {
+	pub fn per_year(self) -> u32 {
+		match self {
+			Period::Annually => 1,
+			Period::SemiAnnually => 2,
+			Period::Quarterly => 4,
+			Period::Monthly => 12,
+			Period::Biweekly => 26,
+			Period::Weekly => 52,
+			Period::Daily => 365,
+		}
+	}
+
+	// The Date a Number of Periods After the Start
+	pub fn step(self, start: NaiveDate, periods: u32) -> Option<NaiveDate> {
+		match self {
+			Period::Annually => start.checked_add_months(Months::new(12 * periods)),
+			Period::SemiAnnually => start.checked_add_months(Months::new(6 * periods)),
+			Period::Quarterly => start.checked_add_months(Months::new(3 * periods)),
+			Period::Monthly => start.checked_add_months(Months::new(periods)),
+			Period::Biweekly => start.checked_add_days(Days::new(14 * periods as u64)),
+			Period::Weekly => start.checked_add_days(Days::new(7 * periods as u64)),
+			Period::Daily => start.checked_add_days(Days::new(periods as u64)),
+		}
+	}
+}
+
+impl FromStr for Period {
+	type Err = FinanceError;
+
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		match name.to_lowercase().as_str() {
+			"annually" | "yearly" => Ok(Period::Annually),
+			"semiannually" => Ok(Period::SemiAnnually),
+			"quarterly" => Ok(Period::Quarterly),
+			"monthly" => Ok(Period::Monthly),
+			"biweekly" => Ok(Period::Biweekly),
+			"weekly" => Ok(Period::Weekly),
+			"daily" => Ok(Period::Daily),
+			_ => Err(FinanceError(format!("Unknown Period '{}'. Use One of: {}", name, FREQUENCY_NAMES.join(", ")))),
+		}
+	}
+}
+
+impl fmt::Display for Period {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let name = match self {
+			Period::Annually => "annually",
+			Period::SemiAnnually => "semiannually",
+			Period::Quarterly => "quarterly",
+			Period::Monthly => "monthly",
+			Period::Biweekly => "biweekly",
+			Period::Weekly => "weekly",
+			Period::Daily => "daily",
+		};
+		write!(f, "{}", name)
+	}
+}
+
+fn round_cents(amount: f64) -> f64 {
+	(amount * 100.0).round() / 100.0
+}
+
+fn check_amount(amount: f64, name: &str) -> Result<(), FinanceError> {
+	if !amount.is_finite() || amount < 0.0 {
+		return invalid(&format!("{} Must be Zero or More", name))
+	}
+	Ok(())
+}
+
+// Money Growing With Compound Interest and Regular Deposits
+#[derive(Clone, Debug, PartialEq)]
+pub struct Savings {
+	pub principal: f64,
+	// Yearly Rate as a Percentage, Like 5 for 5%
+	pub annual_rate: f64,
+	pub years: f64,
+	pub compounding: Period,
+	// Deposited at the End of Each Contribution Period
+	pub contribution: f64,
+	pub contribution_frequency: Period,
+}
+
+impl Savings {
+	fn validate(&self) -> Result<(), FinanceError> {
+		check_amount(self.principal, "Principal")?;
+		check_amount(self.contribution, "Contribution")?;
+		if !self.annual_rate.is_finite() || self.annual_rate <= -100.0 {
+			return invalid("Rate Must be More Than -100%")
+		}
+		if !self.years.is_finite() || self.years <= 0.0 || self.years > 1000.0 {
+			return invalid("Years Must be More Than 0 and at Most 1000")
+		}
+		Ok(())
+	}
+
+	// Contribution Periods in the Whole Term
+	fn periods(&self) -> u32 {
+		(self.years * self.contribution_frequency.per_year() as f64).round().max(1.0) as u32
+	}
+
+	// Growth Over One Contribution Period, Whatever the Compounding
+	fn period_rate(&self) -> f64 {
+		let compounds = self.compounding.per_year() as f64;
+		let deposits = self.contribution_frequency.per_year() as f64;
+		(1.0 + self.annual_rate / 100.0 / compounds).powf(compounds / deposits) - 1.0
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ProjectionRow {
+	pub year: f64,
+	pub contributions: f64,
+	pub interest: f64,
+	pub balance: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Projection {
+	// One Row per Year, Plus a Last Row for Any Part Year
+	pub rows: Vec<ProjectionRow>,
+	pub total_contributions: f64,
+	pub total_interest: f64,
+	pub final_balance: f64,
+}
+
+pub fn compound_projection(savings: &Savings) -> Result<Projection, FinanceError> {
+	savings.validate()?;
+	let rate = savings.period_rate();
+	let per_year = savings.contribution_frequency.per_year();
+	let mut balance = savings.principal;
+	let (mut contributions, mut interest) = (0.0, 0.0);
+	let (mut year_contributions, mut year_interest) = (0.0, 0.0);
+	let mut rows = Vec::new();
+	let periods = savings.periods();
+	for period in 1..=periods {
+		let earned = balance * rate;
+		balance += earned + savings.contribution;
+		year_interest += earned;
+		year_contributions += savings.contribution;
+		if period % per_year == 0 || period == periods {
+			rows.push(ProjectionRow {
+				year: round_cents(period as f64 / per_year as f64),
+				contributions: round_cents(year_contributions),
+				interest: round_cents(year_interest),
+				balance: round_cents(balance),
+			});
+			contributions += year_contributions;
+			interest += year_interest;
+			year_contributions = 0.0;
+			year_interest = 0.0;
+		}
+	}
+	Ok(Projection {
+		rows,
+		total_contributions: round_cents(contributions),
+		total_interest: round_cents(interest),
+		final_balance: round_cents(balance),
+	})
+}
+
+// The Deposit Each Period That Grows the Savings to the Target. The
+// Contribution Already in savings Is Ignored. Zero if It Gets There Anyway
+pub fn required_contribution(savings: &Savings, target: f64) -> Result<f64, FinanceError> {
+	savings.validate()?;
+	check_amount(target, "Goal")?;
+	let rate = savings.period_rate();
+	let periods = savings.periods() as f64;
+	let growth = (1.0 + rate).powf(periods);
+	let shortfall = target - savings.principal * growth;
+	if shortfall <= 0.0 {
+		return Ok(0.0)
+	}
+	let contribution = if rate.abs() < 1e-12 { shortfall / periods } else { shortfall * rate / (growth - 1.0) };
+	// Rounded Up so the Goal Is Reached, Not Just Missed
+	Ok((contribution * 100.0).ceil() / 100.0)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Loan {
+	pub principal: f64,
+	// Yearly Rate as a Percentage, Compounded Each Payment
+	pub annual_rate: f64,
+	pub years: f64,
+	pub frequency: Period,
+	// Paid on Top of Every Scheduled Payment
+	pub extra_payment: f64,
+	pub start: NaiveDate,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Payment {
+	pub number: u32,
+	pub date: NaiveDate,
+	pub payment: f64,
+	pub interest: f64,
+	pub principal: f64,
+	pub extra: f64,
+	pub balance: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Schedule {
+	// The Payment Due Each Period, Not Counting Extra
+	pub scheduled_payment: f64,
+	pub payments: Vec<Payment>,
+	pub total_paid: f64,
+	pub total_interest: f64,
+	pub payoff_date: NaiveDate,
+}
+
+impl Loan {
+	fn validate(&self) -> Result<(), FinanceError> {
+		check_amount(self.principal, "Principal")?;
+		check_amount(self.extra_payment, "Extra Payment")?;
+		if !self.annual_rate.is_finite() || self.annual_rate < 0.0 {
+			return invalid("Rate Must be Zero or More")
+		}
+		if !self.years.is_finite() || self.years <= 0.0 || self.years > 100.0 {
+			return invalid("Years Must be More Than 0 and at Most 100")
+		}
+		Ok(())
+	}
+
+	pub fn scheduled_payment(&self) -> f64 {
+		let rate = self.annual_rate / 100.0 / self.frequency.per_year() as f64;
+		let periods = (self.years * self.frequency.per_year() as f64).round().max(1.0);
+		let payment = if rate == 0.0 {
+			self.principal / periods
+		} else {
+			self.principal * rate / (1.0 - (1.0 + rate).powf(-periods))
+		};
+		(payment * 100.0).ceil() / 100.0
+	}
+}
+
+// Every Payment Until the Loan Is Paid Off. Interest Is Worked Out in
+// Cents Each Period and the Last Payment Is Only What's Left
+pub fn amortize(loan: &Loan) -> Result<Schedule, FinanceError> {
+	loan.validate()?;
+	let rate = loan.annual_rate / 100.0 / loan.frequency.per_year() as f64;
+	let scheduled_payment = loan.scheduled_payment();
+	let mut balance = round_cents(loan.principal);
+	let mut payments = Vec::new();
+	let (mut total_paid, mut total_interest) = (0.0, 0.0);
+	let mut number = 0;
+	while balance > 0.0 {
+		if payments.len() >= MAX_PAYMENTS {
+			return invalid("The Payment Never Pays Off the Loan")
+		}
+		number += 1;
+		let interest = round_cents(balance * rate);
+		let owed = round_cents(balance + interest);
+		let payment = scheduled_payment.min(owed);
+		let extra = loan.extra_payment.min(round_cents(owed - payment));
+		let principal = round_cents(payment + extra - interest);
+		balance = round_cents(balance - principal);
+		total_paid += payment + extra;
+		total_interest += interest;
+		let date = loan.frequency.step(loan.start, number).ok_or_else(|| FinanceError("Date Out of Range".to_string()))?;
+		payments.push(Payment { number, date, payment, interest, principal, extra, balance });
+	}
+	Ok(Schedule {
+		scheduled_payment,
+		payoff_date: payments.last().map_or(loan.start, |payment| payment.date),
+		payments,
+		total_paid: round_cents(total_paid),
+		total_interest: round_cents(total_interest),
+	})
+}
+
+fn money(amount: f64) -> String {
+	format!("{:.2}", amount)
+}
+
+pub fn projection_table(projection: &Projection) -> Table {
+	let mut builder = Builder::default();
+	builder.push_record(["Year", "Contributions", "Interest", "Balance"]);
+	for row in &projection.rows {
+		builder.push_record([row.year.to_string(), money(row.contributions), money(row.interest), money(row.balance)]);
+	}
+	let mut table = builder.build();
+	table.with(Style::modern());
+	table
+}
+
+pub fn schedule_table(schedule: &Schedule) -> Table {
+	let mut builder = Builder::default();
+	builder.push_record(["#", "Date", "Payment", "Interest", "Principal", "Extra", "Balance"]);
+	for payment in &schedule.payments {
+		builder.push_record([
+			payment.number.to_string(),
+			payment.date.format(FINANCE_DATE_FORMAT).to_string(),
+			money(payment.payment),
+			money(payment.interest),
+			money(payment.principal),
+			money(payment.extra),
+			money(payment.balance),
+		]);
+	}
+	let mut table = builder.build();
+	table.with(Style::modern());
+	table
+}
+
+// Write Rows as CSV With a Header Taken From Their Field Names
+pub fn write_csv<W: Write, R: Serialize>(rows: &[R], writer: W) -> Result<(), Box<dyn Error>> {
+	let mut csv_writer = csv::Writer::from_writer(writer);
+	for row in rows {
+		csv_writer.serialize(row)?;
+	}
+	csv_writer.flush()?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn savings(principal: f64, contribution: f64) -> Savings {
+		Savings {
+			principal,
+			annual_rate: 5.0,
+			years: 10.0,
+			compounding: Period::Monthly,
+			contribution,
+			contribution_frequency: Period::Monthly,
+		}
+	}
+
+	#[test]
+	fn can_compound_with_contributions() {
+		// 1000 at 5% Compounded Monthly for 10 Years Is 1000 * (1 + 0.05/12)^120
+		let projection = compound_projection(&savings(1000.0, 0.0)).unwrap();
+		assert_eq!(10, projection.rows.len());
+		assert_eq!(1647.01, projection.final_balance);
+
+		let projection = compound_projection(&savings(0.0, 100.0)).unwrap();
+		assert_eq!(12000.0, projection.total_contributions);
+		assert_eq!(15528.23, projection.final_balance);
+		assert_eq!(projection.final_balance, projection.rows.last().unwrap().balance);
+
+		// Yearly Deposits With Monthly Compounding Still Line Up
+		let mut yearly = savings(1000.0, 0.0);
+		yearly.contribution_frequency = Period::Annually;
+		assert_eq!(1647.01, compound_projection(&yearly).unwrap().final_balance);
+		assert!(compound_projection(&savings(-1.0, 0.0)).is_err());
+	}
+
+	#[test]
+	fn can_solve_for_a_goal() {
+		let contribution = required_contribution(&savings(0.0, 0.0), 15000.0).unwrap();
+		assert_eq!(96.6, contribution);
+		assert!(compound_projection(&savings(0.0, contribution)).unwrap().final_balance >= 15000.0);
+		assert!(compound_projection(&savings(0.0, contribution - 0.01)).unwrap().final_balance < 15000.0);
+		assert_eq!(0.0, required_contribution(&savings(20000.0, 0.0), 15528.23).unwrap());
+	}
+
+	#[test]
+	fn can_amortize_loans() {
+		let loan = Loan {
+			principal: 200_000.0,
+			annual_rate: 6.0,
+			years: 30.0,
+			frequency: Period::Monthly,
+			extra_payment: 0.0,
+			start: NaiveDate::from_ymd_opt(2026, 1, 15).unwrap(),
+		};
+		let schedule = amortize(&loan).unwrap();
+		assert_eq!(1199.11, schedule.scheduled_payment);
+		assert_eq!(360, schedule.payments.len());
+		assert_eq!(NaiveDate::from_ymd_opt(2056, 1, 15).unwrap(), schedule.payoff_date);
+		assert_eq!(1000.0, schedule.payments[0].interest);
+		assert_eq!(0.0, schedule.payments.last().unwrap().balance);
+		let principal: f64 = schedule.payments.iter().map(|payment| payment.principal).sum();
+		assert!((principal - 200_000.0).abs() < 0.01);
+
+		// Paying Extra Finishes Sooner and Costs Less
+		let faster = amortize(&Loan { extra_payment: 200.0, ..loan.clone() }).unwrap();
+		assert!(faster.payments.len() < 300);
+		assert!(faster.total_interest < schedule.total_interest - 50_000.0);
+
+		let free = amortize(&Loan { annual_rate: 0.0, years: 1.0, principal: 1200.0, ..loan }).unwrap();
+		assert_eq!(12, free.payments.len());
+		assert_eq!(0.0, free.total_interest);
+	}
+}
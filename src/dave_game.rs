@@ -2,7 +2,7 @@ use std::f32::consts::PI;
 use std::io;
 use bevy::prelude::*;
 use rand::Rng;
-use crate::dave_graphics::ASSETS_DIR;
+use crate::dave_graphics::assets_dir;
 
 #[derive(Clone, Eq, PartialEq, Debug, Hash, Default, States)]
 enum GameState {
@@ -332,7 +332,7 @@ fn setup(
 
 	// Game Audio
 	commands.spawn(AudioBundle {
-		source: asset_server.load(ASSETS_DIR.to_owned() + "/audio/slopes.ogg"),
+		source: asset_server.load(assets_dir() + "/audio/slopes.ogg"),
 		settings: PlaybackSettings {
 			mode: bevy::audio::PlaybackMode::Loop,
 			..default()
@@ -340,13 +340,13 @@ fn setup(
 		..default()
 	});
 	let score_sound = asset_server.load(
-		ASSETS_DIR.to_owned() + "/audio/collision.ogg",
+		assets_dir() + "/audio/collision.ogg",
 	);
 	commands.insert_resource(ScoreSound(score_sound));
 
 	// Spawn Game Board
 	let cell_scene = asset_server.load(
-		ASSETS_DIR.to_owned() + "/models/dave_game/tile.glb#Scene0",
+		assets_dir() + "/models/dave_game/tile.glb#Scene0",
 	);
 	game.board = (0..BOARD_SIZE_J)
 		.map(|j| {
@@ -377,7 +377,7 @@ fn setup(
 				..default()
 			},
 			scene: asset_server.load(
-				ASSETS_DIR.to_owned() + "/models/dave_game/alien.glb#Scene0",
+				assets_dir() + "/models/dave_game/alien.glb#Scene0",
 			),
 			..default()
 		})
@@ -386,7 +386,7 @@ fn setup(
 
 	// Load Scene for Point
 	game.bonus.handle = asset_server.load(
-		ASSETS_DIR.to_owned() + "/models/dave_game/cakeBirthday.glb#Scene0",
+		assets_dir() + "/models/dave_game/cakeBirthday.glb#Scene0",
 	);
 
 	// Scoreboard
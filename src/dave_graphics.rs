@@ -39,9 +39,13 @@ use bevy::{
         WinitSettings,
     },
 };
+use crate::config::{find_assets_path, CONFIG};
 use crate::utils::*;
 
-pub const ASSETS_DIR: &str = "./dave_conf/etc/daves_assets";
+// Where Textures, Models, Audio and Fonts Are Loaded From
+pub fn assets_dir() -> String {
+	CONFIG.read().map_or_else(|_| find_assets_path(), |config| config.assets_path()).display().to_string()
+}
 
 //
 // Cube Program
@@ -273,17 +277,17 @@ fn morph_setup(asset_server: Res<AssetServer>, mut commands: Commands) {
     // Animation
     commands.insert_resource(MorphData {
         the_wave: asset_server.load(
-            ASSETS_DIR.to_owned() + "/models/morph/MorphStressTest.gltf#Animation2",
+            assets_dir() + "/models/morph/MorphStressTest.gltf#Animation2",
         ),
         mesh: asset_server.load(
-            ASSETS_DIR.to_owned() + "/models/morph/MorphStressTest.gltf#Mesh0/Primitive0",
+            assets_dir() + "/models/morph/MorphStressTest.gltf#Mesh0/Primitive0",
         ),
     });
 
     // Scene
     commands.spawn(SceneBundle {
         scene: asset_server.load(
-            ASSETS_DIR.to_owned() + "/models/morph/MorphStressTest.gltf#Scene0",
+            assets_dir() + "/models/morph/MorphStressTest.gltf#Scene0",
         ),
         ..default()
     });
@@ -466,10 +470,10 @@ fn pbr_setup(
         CameraController::default(),
         EnvironmentMapLight {
             diffuse_map: asset_server.load(
-                ASSETS_DIR.to_owned() + "/env_maps/pisa_diffuse_rgb9e5_zstd.ktx2",
+                assets_dir() + "/env_maps/pisa_diffuse_rgb9e5_zstd.ktx2",
             ),
             specular_map: asset_server.load(
-                ASSETS_DIR.to_owned() + "/env_maps/pisa_specular_rgb9e5_zstd.ktx2",
+                assets_dir() + "/env_maps/pisa_specular_rgb9e5_zstd.ktx2",
             ),
             intensity: 900.0,
         },
@@ -579,7 +583,7 @@ fn fog_setup_terrain_scene(
     // Terrain
     commands.spawn(SceneBundle {
         scene: asset_server.load(
-            ASSETS_DIR.to_owned() + "/scenes/Mountains.gltf#Scene0",
+            assets_dir() + "/scenes/Mountains.gltf#Scene0",
         ),
         ..default()
     });
@@ -642,10 +646,10 @@ fn render_setup(mut commands: Commands, asset_server: Res<AssetServer>) {
         CameraController::default(),
         EnvironmentMapLight {
             diffuse_map: asset_server.load(
-                ASSETS_DIR.to_owned() + "/env_maps/pisa_diffuse_rgb9e5_zstd.ktx2",
+                assets_dir() + "/env_maps/pisa_diffuse_rgb9e5_zstd.ktx2",
             ),
             specular_map: asset_server.load(
-                ASSETS_DIR.to_owned() + "/env_maps/pisa_specular_rgb9e5_zstd.ktx2",
+                assets_dir() + "/env_maps/pisa_specular_rgb9e5_zstd.ktx2",
             ),
             intensity: 250.0,
         },
@@ -669,7 +673,7 @@ fn render_setup(mut commands: Commands, asset_server: Res<AssetServer>) {
     // Scene
     commands.spawn(SceneBundle {
         scene: asset_server.load(
-            ASSETS_DIR.to_owned() + "/models/flight_helmet/FlightHelmet.gltf#Scene0",
+            assets_dir() + "/models/flight_helmet/FlightHelmet.gltf#Scene0",
         ),
         ..default()
     });
@@ -781,9 +785,9 @@ fn fox_animation_setup(
 ) {
     // Insert Resource With Current Scene Info
     commands.insert_resource(FoxAnimations(vec![
-        asset_server.load(ASSETS_DIR.to_owned() + "/models/fox/Fox.glb#Animation2"),
-        asset_server.load(ASSETS_DIR.to_owned() + "/models/fox/Fox.glb#Animation1"),
-        asset_server.load(ASSETS_DIR.to_owned() + "/models/fox/Fox.glb#Animation0"),
+        asset_server.load(assets_dir() + "/models/fox/Fox.glb#Animation2"),
+        asset_server.load(assets_dir() + "/models/fox/Fox.glb#Animation1"),
+        asset_server.load(assets_dir() + "/models/fox/Fox.glb#Animation0"),
     ]));
 
     // Camera
@@ -818,7 +822,7 @@ fn fox_animation_setup(
 
     // Fox
     commands.spawn(SceneBundle {
-        scene: asset_server.load(ASSETS_DIR.to_owned() + "/models/fox/Fox.glb#Scene0"),
+        scene: asset_server.load(assets_dir() + "/models/fox/Fox.glb#Scene0"),
         ..default()
     });
 
@@ -991,13 +995,13 @@ fn foxes_setup(
 ) {
     // Insert Resource With Current Scene Information
     commands.insert_resource(FoxAnimations(vec![
-        asset_server.load(ASSETS_DIR.to_owned() + "/models/fox/Fox.glb#Animation2"),
-        asset_server.load(ASSETS_DIR.to_owned() + "/models/fox/Fox.glb#Animation1"),
-        asset_server.load(ASSETS_DIR.to_owned() + "/models/fox/Fox.glb#Animation0"),
+        asset_server.load(assets_dir() + "/models/fox/Fox.glb#Animation2"),
+        asset_server.load(assets_dir() + "/models/fox/Fox.glb#Animation1"),
+        asset_server.load(assets_dir() + "/models/fox/Fox.glb#Animation0"),
     ]));
 
     // Concentric Ring of Foxes, Running in Opposite Directions
-    let fox_handle = asset_server.load(ASSETS_DIR.to_owned() + "/models/fox/Fox.glb#Scene0");
+    let fox_handle = asset_server.load(assets_dir() + "/models/fox/Fox.glb#Scene0");
     let ring_directions = [
         (
             Quat::from_rotation_y(PI),
@@ -1257,7 +1261,7 @@ fn lighting_setup(
             transform,
             material: materials.add(StandardMaterial {
                 base_color_texture: Some(asset_server.load(
-                    ASSETS_DIR.to_owned() + "/textures/bevy_logo_light.png",
+                    assets_dir() + "/textures/bevy_logo_light.png",
                 )),
                 perceptual_roughness: 1.0,
                 alpha_mode: AlphaMode::Mask(0.5),
@@ -1559,7 +1563,7 @@ fn build_ui(
         text_sections.push(TextSection::new(
             format!("{:?}\n", label),
             TextStyle {
-                font: asset_server.load(ASSETS_DIR.to_owned() + FONT_BOLD),
+                font: asset_server.load(assets_dir() + FONT_BOLD),
                 font_size: FONT_SIZE,
                 color: FONT_COLOR,
             },
@@ -1579,7 +1583,7 @@ fn build_ui(
             text_sections.push(TextSection::new(
                 "   ",
                 TextStyle {
-                    font: asset_server.load(ASSETS_DIR.to_owned() + FONT_MEDIUM),
+                    font: asset_server.load(assets_dir() + FONT_MEDIUM),
                     font_size: FONT_SIZE,
                     color: FONT_COLOR,
                 },
@@ -1588,7 +1592,7 @@ fn build_ui(
             text_sections.push(TextSection::new(
                 format!("{}\n", system.name()),
                 TextStyle {
-                    font: asset_server.load(ASSETS_DIR.to_owned() + FONT_MEDIUM),
+                    font: asset_server.load(assets_dir() + FONT_MEDIUM),
                     font_size: FONT_SIZE,
                     color: FONT_COLOR,
                 },
@@ -1622,7 +1626,7 @@ fn build_help(mut commands: Commands, asset_server: Res<AssetServer>) {
     commands.spawn((TextBundle::from_sections([TextSection::new(
         "Press the ~ key to toggle frame stepping mode (S: Step System, Space: Step Frame)\nPress ESC to Quit",
         TextStyle {
-            font: asset_server.load(ASSETS_DIR.to_owned() + FONT_MEDIUM),
+            font: asset_server.load(assets_dir() + FONT_MEDIUM),
             font_size: 18.0,
             color: FONT_COLOR,
         },
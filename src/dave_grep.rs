@@ -1,123 +1,601 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
 use std::error::Error;
 use std::fs;
-use regex::Regex;
+use std::io::{self, BufRead, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use regex::{NoExpand, Regex, RegexBuilder};
 use colored::*;
+use crate::dave_search::{self, SearchOptions};
+use crate::dave_store;
 
+// Lines of Unchanged Text Shown Around Each Change in a Diff
+const DIFF_CONTEXT: usize = 3;
+
+// What Gets Printed for Each File
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputMode {
+	// Matching Lines With Their File and Line Number
+	#[default]
+	Lines,
+	// How Many Lines Matched in Each File
+	Count,
+	// Just the Names of Files With a Match
+	FilesWithMatches,
+}
+
+#[derive(Clone, Debug)]
 pub struct Config {
 	pub case_sensitive: bool,
 	pub regex: bool,
+	pub whole_word: bool,
+	pub invert: bool,
+	pub before_context: usize,
+	pub after_context: usize,
+	pub mode: OutputMode,
+	pub color: bool,
+	pub search: SearchOptions,
 	pub pattern: String,
-	pub filename: String,
+	pub paths: Vec<PathBuf>,
 }
 
 impl Config {
+	// '-o i' Makes the Search Case Insensitive and '-o r' Treats the
+	// Pattern as a REGEX. Everything Else Starts Off
 	pub fn new(
 		gotten_option: String,
 		gotten_pattern: String,
-		gotten_filename: String
+		gotten_paths: Vec<PathBuf>,
 	) -> Result<Config, &'static str> {
-		let case_sensitive: bool;
-	    if gotten_option == "i" || gotten_option == "I" || gotten_option == "insensitive" {
-	        case_sensitive = true;
-	    } else {
-	        case_sensitive = false;
-	    }
-	    let regex: bool;
-	    if gotten_option == "r" || gotten_option == "R" || gotten_option == "regex" {
-	    	regex = true;
-	    } else {
-	    	regex = false;
-	    }
-		let pattern = gotten_pattern;
-		let filename = gotten_filename;
+		let (case_sensitive, regex) = match gotten_option.as_str() {
+			"" => (true, false),
+			"i" | "I" | "insensitive" => (false, false),
+			"r" | "R" | "regex" => (true, true),
+			_ => return Err("Unknown Option. Pass '-o i' for Case Insensitivity or '-o r' for REGEX Pattern Matching"),
+		};
+		if gotten_paths.is_empty() {
+			return Err("A File or Path Must be Passed to DGREP")
+		}
 
 		Ok(Config {
 			case_sensitive,
 			regex,
-			pattern,
-			filename,
+			whole_word: false,
+			invert: false,
+			before_context: 0,
+			after_context: 0,
+			mode: OutputMode::Lines,
+			color: false,
+			search: SearchOptions::default(),
+			pattern: gotten_pattern,
+			paths: gotten_paths,
 		})
 	}
+
+	// Plain Patterns Are Escaped so Every Search Runs Through One REGEX,
+	// Which Also Gives the Span of Each Match for Highlighting
+	pub fn matcher(&self) -> Result<Regex, regex::Error> {
+		let pattern = if self.regex { self.pattern.clone() } else { regex::escape(&self.pattern) };
+		let pattern = if self.whole_word { format!(r"\b(?:{})\b", pattern) } else { pattern };
+		RegexBuilder::new(&pattern).case_insensitive(!self.case_sensitive).build()
+	}
 }
 
-pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
-	let contents = fs::read_to_string(config.filename)?;
+// A Matching Line, Numbered From 1, and Where on It Each Match Is
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineMatch {
+	pub number: usize,
+	pub spans: Vec<Range<usize>>,
+}
 
-	if !config.regex {
-		let results = if config.case_sensitive {
-			search(&config.pattern, &contents)
-		} else {
-			search_case_insensitive(&config.pattern, &contents)
-		};
-		if results.is_empty() {
-			println!("{}", "##==> There Were No Matches For Your Pattern".red());
-		} else {
-			for line in results {
-				println!("{}", line.yellow());
+// Every Line That Matches, or With invert Every Line That Doesn't. A
+// Line With Several Matches Is Still Only One Result
+pub fn search_lines(matcher: &Regex, invert: bool, contents: &str) -> Vec<LineMatch> {
+	contents
+		.lines()
+		.enumerate()
+		.filter(|(_, line)| matcher.is_match(line) != invert)
+		.map(|(index, line)| LineMatch {
+			number: index + 1,
+			spans: if invert {
+				Vec::new()
+			} else {
+				matcher.find_iter(line).map(|found| found.range()).filter(|span| !span.is_empty()).collect()
+			},
+		})
+		.collect()
+}
+
+fn highlight(line: &str, spans: &[Range<usize>], color: bool) -> String {
+	if !color || spans.is_empty() {
+		return line.to_string()
+	}
+	let mut highlighted = String::new();
+	let mut last = 0;
+	for span in spans {
+		highlighted.push_str(&line[last..span.start]);
+		highlighted.push_str(&line[span.clone()].red().bold().to_string());
+		last = span.end;
+	}
+	highlighted.push_str(&line[last..]);
+	highlighted
+}
+
+// Prints Matching Lines as 'file:line:text' and Context as 'file-line-text',
+// With '--' Between Groups of Lines That Aren't Next to Each Other
+struct LinePrinter<'a, W: Write> {
+	config: &'a Config,
+	out: &'a mut W,
+	groups: usize,
+}
+
+impl<W: Write> LinePrinter<'_, W> {
+	fn print_file(&mut self, path: &Path, contents: &str, matches: &[LineMatch]) -> io::Result<()> {
+		let lines: Vec<&str> = contents.lines().collect();
+		let matched: BTreeMap<usize, &[Range<usize>]> =
+			matches.iter().map(|line_match| (line_match.number, line_match.spans.as_slice())).collect();
+
+		// Merge Each Match's Window of Context Into Runs of Lines
+		let mut runs: Vec<Range<usize>> = Vec::new();
+		for number in matched.keys() {
+			let start = number.saturating_sub(self.config.before_context).max(1);
+			let end = (number + self.config.after_context).min(lines.len());
+			match runs.last_mut() {
+				Some(run) if start <= run.end + 1 => run.end = run.end.max(end),
+				_ => runs.push(start..end),
 			}
 		}
-	} else {
-		let regex = Regex::new(&config.pattern)?;
-		if regex.is_match(&contents) {
-			for line in contents.lines() {
-				let matches: Vec<_> = regex.find_iter(line).map(|m| m).collect();
-				for _m in matches {
-					println!("{}", line.yellow());
-				}
+
+		let label = path.display().to_string();
+		for run in runs {
+			let has_context = self.config.before_context > 0 || self.config.after_context > 0;
+			if has_context && self.groups > 0 {
+				writeln!(self.out, "{}", self.paint("--", |text| text.cyan()))?;
+			}
+			self.groups += 1;
+			for number in run.start..=run.end {
+				let line = lines[number - 1];
+				let (separator, text) = match matched.get(&number) {
+					Some(spans) => (":", highlight(line, spans, self.config.color)),
+					None => ("-", line.to_string()),
+				};
+				writeln!(
+					self.out,
+					"{}{}{}{}{}",
+					self.paint(&label, |text| text.magenta()),
+					self.paint(separator, |text| text.cyan()),
+					self.paint(&number.to_string(), |text| text.green()),
+					self.paint(separator, |text| text.cyan()),
+					text,
+				)?;
 			}
-		} else {
-			println!("{}", "##==> There Were No Matches For Your REGEX Pattern".red());
+		}
+		Ok(())
+	}
+
+	fn paint(&self, text: &str, style: fn(&str) -> ColoredString) -> String {
+		paint(text, self.config.color, style)
+	}
+}
+
+fn paint(text: &str, color: bool, style: fn(&str) -> ColoredString) -> String {
+	if color { style(text).to_string() } else { text.to_string() }
+}
+
+// Search Every File and Write the Results. True if Anything Matched
+pub fn run_to<W: Write>(config: &Config, out: &mut W) -> Result<bool, Box<dyn Error>> {
+	let matcher = config.matcher()?;
+	// Only Files Being Printed Line by Line Need Their Text Kept Around
+	let keep_text = config.mode == OutputMode::Lines;
+	let results = dave_search::search(&config.paths, &config.search, |_, contents| {
+		let matches = search_lines(&matcher, config.invert, contents);
+		let text = (keep_text && !matches.is_empty()).then(|| contents.to_string());
+		(matches, text)
+	})?;
+	for (path, error) in &results.errors {
+		eprintln!("{}{}: {}", "##==>>>> ERROR: ".red(), path.display(), error);
+	}
+
+	let mut any_matched = false;
+	let mut printer = LinePrinter { config, out, groups: 0 };
+	for (path, (matches, text)) in &results.files {
+		any_matched |= !matches.is_empty();
+		match config.mode {
+			OutputMode::Lines => printer.print_file(path, text.as_deref().unwrap_or_default(), matches)?,
+			OutputMode::Count => writeln!(printer.out, "{}:{}", path.display(), matches.len())?,
+			OutputMode::FilesWithMatches if !matches.is_empty() => writeln!(printer.out, "{}", path.display())?,
+			OutputMode::FilesWithMatches => {},
 		}
 	}
+	Ok(any_matched)
+}
 
+pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
+	let stdout = io::stdout();
+	let matched = run_to(&config, &mut stdout.lock())?;
+	if !matched && config.mode != OutputMode::Count {
+		println!("{}", "##==> There Were No Matches For Your Pattern".red());
+	}
 	Ok(())
 }
 
-pub fn search<'a>(pattern: &str, contents: &'a str) -> Vec<&'a str> {
-	contents
-		.lines()
-		.filter(|line| line.contains(pattern))
-		.collect()
+// What '--replace' Does With the Changes It Finds
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplaceMode {
+	// Print a Diff of Every Change and Touch Nothing
+	Preview,
+	// Make Every Change
+	Write,
+	// Show Each Hunk and Ask Before Making It
+	Interactive,
+}
+
+// A Run of Changed Lines Close Enough to Share Context. Lines Are
+// Counted From 0 and Each Change Keeps Its Line Ending
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hunk {
+	pub start: usize,
+	pub end: usize,
+	pub changes: Vec<(usize, String)>,
 }
 
-pub fn search_case_insensitive<'a>(pattern: &str, contents: &'a str) -> Vec<&'a str> {
-	let mut results = Vec::new();
-	let pattern = pattern.to_lowercase();
+// Every Change to One File, Along With the Text They Were Worked Out
+// From so the File Can be Checked Before it's Rewritten
+#[derive(Clone, Debug)]
+pub struct FileReplacement {
+	pub original: String,
+	pub hunks: Vec<Hunk>,
+}
 
-	for line in contents.lines() {
-		if line.to_lowercase().contains(&pattern) {
-			results.push(line);
+fn split_ending(line: &str) -> (&str, &str) {
+	if let Some(body) = line.strip_suffix("\r\n") {
+		(body, "\r\n")
+	} else if let Some(body) = line.strip_suffix('\n') {
+		(body, "\n")
+	} else {
+		(line, "")
+	}
+}
+
+impl FileReplacement {
+	// With expand, '$1' and '${name}' in the Template Become Captures.
+	// Otherwise the Template Goes in Exactly as Written
+	pub fn plan(matcher: &Regex, template: &str, expand: bool, contents: &str) -> FileReplacement {
+		let lines: Vec<&str> = contents.split_inclusive('\n').collect();
+		let mut hunks: Vec<Hunk> = Vec::new();
+		for (index, line) in lines.iter().enumerate() {
+			let (body, ending) = split_ending(line);
+			let replaced: Cow<str> = if expand {
+				matcher.replace_all(body, template)
+			} else {
+				matcher.replace_all(body, NoExpand(template))
+			};
+			if replaced == body {
+				continue
+			}
+			let start = index.saturating_sub(DIFF_CONTEXT);
+			let end = (index + 1 + DIFF_CONTEXT).min(lines.len());
+			let change = (index, format!("{}{}", replaced, ending));
+			match hunks.last_mut() {
+				Some(hunk) if start <= hunk.end => {
+					hunk.end = end;
+					hunk.changes.push(change);
+				},
+				_ => hunks.push(Hunk { start, end, changes: vec![change] }),
+			}
 		}
+		FileReplacement { original: contents.to_string(), hunks }
 	}
 
-	results
+	// The Text With Only the Accepted Hunks Applied
+	pub fn apply(&self, accepted: &[bool]) -> String {
+		let changes: HashMap<usize, &str> = self
+			.hunks
+			.iter()
+			.zip(accepted)
+			.filter(|(_, accepted)| **accepted)
+			.flat_map(|(hunk, _)| hunk.changes.iter().map(|(index, text)| (*index, text.as_str())))
+			.collect();
+		self.original
+			.split_inclusive('\n')
+			.enumerate()
+			.map(|(index, line)| changes.get(&index).copied().unwrap_or(line))
+			.collect()
+	}
+
+	// A Hunk in Unified Diff Form. offset Is How Many Lines Earlier
+	// Hunks Added or Removed; the Change This One Makes Is Returned
+	pub fn render_hunk(&self, hunk: &Hunk, offset: isize, color: bool) -> (String, isize) {
+		let lines: Vec<&str> = self.original.split_inclusive('\n').collect();
+		let changes: HashMap<usize, &str> = hunk.changes.iter().map(|(index, text)| (*index, text.as_str())).collect();
+		let mut body = String::new();
+		let mut new_length = 0;
+		for (index, line) in lines.iter().enumerate().take(hunk.end).skip(hunk.start) {
+			let (old, _) = split_ending(line);
+			match changes.get(&index) {
+				Some(new) => {
+					body.push_str(&paint(&format!("-{}", old), color, |text| text.red()));
+					body.push('\n');
+					for added in new.split_inclusive('\n') {
+						body.push_str(&paint(&format!("+{}", split_ending(added).0), color, |text| text.green()));
+						body.push('\n');
+						new_length += 1;
+					}
+				},
+				None => {
+					body.push_str(&format!(" {}\n", old));
+					new_length += 1;
+				},
+			}
+		}
+		let old_length = hunk.end - hunk.start;
+		let header = format!(
+			"@@ -{},{} +{},{} @@",
+			hunk.start + 1,
+			old_length,
+			hunk.start as isize + 1 + offset,
+			new_length,
+		);
+		let text = format!("{}\n{}", paint(&header, color, |text| text.cyan()), body);
+		(text, new_length as isize - old_length as isize)
+	}
+}
+
+enum Answer {
+	Yes,
+	No,
+	All,
+	Quit,
+}
+
+// Running Out of Input Counts as Quitting
+fn ask<R: BufRead, W: Write>(input: &mut R, out: &mut W) -> io::Result<Answer> {
+	loop {
+		write!(out, "##==> Apply This Change? [y]es, [n]o, [a]ll, [q]uit: ")?;
+		out.flush()?;
+		let mut answer = String::new();
+		if input.read_line(&mut answer)? == 0 {
+			return Ok(Answer::Quit)
+		}
+		match answer.trim().to_lowercase().as_str() {
+			"y" | "yes" => return Ok(Answer::Yes),
+			"n" | "no" => return Ok(Answer::No),
+			"a" | "all" => return Ok(Answer::All),
+			"q" | "quit" => return Ok(Answer::Quit),
+			_ => {},
+		}
+	}
+}
+
+// Rewrite a File Atomically, Keeping Its Permissions. It's Left Alone
+// if It No Longer Holds the Text the Changes Were Worked Out From,
+// Which Includes Files That Aren't Valid UTF-8
+fn write_replacement(path: &Path, replacement: &FileReplacement, accepted: &[bool]) -> io::Result<()> {
+	if fs::read(path)? != replacement.original.as_bytes() {
+		return Err(io::Error::other("File Changed Since it Was Searched or Isn't Valid UTF-8. Left Untouched"))
+	}
+	let permissions = fs::metadata(path)?.permissions();
+	dave_store::write_atomic_with(path, |file| {
+		file.write_all(replacement.apply(accepted).as_bytes())?;
+		file.set_permissions(permissions)
+	})
+}
+
+// How Much a Replacement Changed, or Would Change for a Preview
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReplaceSummary {
+	pub files: usize,
+	pub lines: usize,
+}
+
+// Replace Every Match With the Template. Answers for Interactive Mode
+// Are Read From input
+pub fn replace_to<R: BufRead, W: Write>(
+	config: &Config,
+	template: &str,
+	mode: ReplaceMode,
+	input: &mut R,
+	out: &mut W,
+) -> Result<ReplaceSummary, Box<dyn Error>> {
+	let matcher = config.matcher()?;
+	let results = dave_search::search(&config.paths, &config.search, |_, contents| {
+		FileReplacement::plan(&matcher, template, config.regex, contents)
+	})?;
+	for (path, error) in &results.errors {
+		eprintln!("{}{}: {}", "##==>>>> ERROR: ".red(), path.display(), error);
+	}
+
+	let mut summary = ReplaceSummary::default();
+	let mut accept_all = mode != ReplaceMode::Interactive;
+	let mut quit = false;
+	for (path, replacement) in results.files.iter().filter(|(_, replacement)| !replacement.hunks.is_empty()) {
+		if mode != ReplaceMode::Write {
+			let header = format!("--- {}\n+++ {}", path.display(), path.display());
+			writeln!(out, "{}", paint(&header, config.color, |text| text.bold()))?;
+		}
+		let mut accepted = Vec::new();
+		let mut offset = 0;
+		for hunk in &replacement.hunks {
+			let (text, change) = replacement.render_hunk(hunk, offset, config.color);
+			if mode != ReplaceMode::Write {
+				write!(out, "{}", text)?;
+			}
+			let apply = accept_all || match ask(input, out)? {
+				Answer::Yes => true,
+				Answer::No => false,
+				Answer::All => {
+					accept_all = true;
+					true
+				},
+				Answer::Quit => {
+					quit = true;
+					break
+				},
+			};
+			if apply {
+				offset += change;
+			}
+			accepted.push(apply);
+		}
+		accepted.resize(replacement.hunks.len(), false);
+
+		let lines: usize = replacement
+			.hunks
+			.iter()
+			.zip(&accepted)
+			.filter(|(_, accepted)| **accepted)
+			.map(|(hunk, _)| hunk.changes.len())
+			.sum();
+		if lines > 0 && mode != ReplaceMode::Preview {
+			if let Err(error) = write_replacement(path, replacement, &accepted) {
+				eprintln!("{}{}: {}", "##==>>>> ERROR: ".red(), path.display(), error);
+				continue
+			}
+			writeln!(out, "##==> Rewrote '{}'", path.display())?;
+		}
+		if lines > 0 {
+			summary.files += 1;
+			summary.lines += lines;
+		}
+		if quit {
+			break
+		}
+	}
+	Ok(summary)
+}
+
+pub fn replace(config: Config, template: &str, mode: ReplaceMode) -> Result<(), Box<dyn Error>> {
+	let stdout = io::stdout();
+	let stdin = io::stdin();
+	let summary = replace_to(&config, template, mode, &mut stdin.lock(), &mut stdout.lock())?;
+	if summary.files == 0 && mode != ReplaceMode::Interactive {
+		println!("{}", "##==> There Were No Matches For Your Pattern".red());
+	} else if mode == ReplaceMode::Preview {
+		println!(
+			"##==> {} Line(s) Would Change in {} File(s). Run Again With '--write' to Apply Them",
+			summary.lines,
+			summary.files,
+		);
+	} else {
+		println!("##==> Changed {} Line(s) in {} File(s)", summary.lines, summary.files);
+	}
+	Ok(())
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use std::fs;
+
+	fn config(option: &str, pattern: &str) -> Config {
+		Config::new(option.to_string(), pattern.to_string(), vec![PathBuf::from(".")]).unwrap()
+	}
+
+	fn numbers(matches: &[LineMatch]) -> Vec<usize> {
+		matches.iter().map(|line_match| line_match.number).collect()
+	}
 
 	#[test]
 	fn can_grep_case_sensitive() {
 		let pattern = "duct";
 		let contents = "\
-			Rust:
-			safe, fast, productive.
-			Pick three.
-			Duct tape.";
+Rust:
+safe, fast, productive.
+Pick three.
+Duct tape.";
 
-		assert_eq!(vec!["safe, fast, productive."], search(pattern, contents));
+		let matches = search_lines(&config("", pattern).matcher().unwrap(), false, contents);
+		assert_eq!(vec![LineMatch { number: 2, spans: vec![Range { start: 15, end: 19 }] }], matches);
 	}
 
 	#[test]
 	fn can_grep_case_insensitive() {
 		let pattern = "rUsT";
 		let contents = "\
-			Rust:
-			safe, fast, productive.
-			Pick three.
-			Trust me.";
+Rust:
+safe, fast, productive.
+Pick three.
+Trust me.";
+
+		let matches = search_lines(&config("i", pattern).matcher().unwrap(), false, contents);
+		assert_eq!(vec![1, 4], numbers(&matches));
+	}
+
+	#[test]
+	fn can_match_words_invert_and_regex() {
+		let contents = "cat\nconcatenate\nthe cat sat\ndog";
+		let mut words = config("", "cat");
+		words.whole_word = true;
+		assert_eq!(vec![1, 3], numbers(&search_lines(&words.matcher().unwrap(), false, contents)));
+		assert_eq!(vec![2, 4], numbers(&search_lines(&words.matcher().unwrap(), true, contents)));
+
+		// One Result per Line, Whatever the Number of Matches on It
+		let regex = config("r", "[cs]at");
+		let matches = search_lines(&regex.matcher().unwrap(), false, contents);
+		assert_eq!(vec![4..7, 8..11], matches[2].spans);
+		assert_eq!(3, matches.len());
+		assert!(Config::new("x".to_string(), "cat".to_string(), vec![PathBuf::from(".")]).is_err());
+	}
+
+	#[test]
+	fn can_print_context_across_files() {
+		let dir = tempfile::tempdir().unwrap();
+		fs::write(dir.path().join("a.txt"), "one\ntwo\nneedle\nfour\nfive\nsix\nneedle\n").unwrap();
+		fs::create_dir(dir.path().join("sub")).unwrap();
+		fs::write(dir.path().join("sub/b.txt"), "needle\n").unwrap();
+
+		let mut grep = Config::new(String::new(), "needle".to_string(), vec![dir.path().to_path_buf()]).unwrap();
+		grep.before_context = 1;
+		grep.after_context = 1;
+		let mut out = Vec::new();
+		assert!(run_to(&grep, &mut out).unwrap());
+		let a = dir.path().join("a.txt").display().to_string();
+		let b = dir.path().join("sub/b.txt").display().to_string();
+		let expected = format!(
+			"{a}-2-two\n{a}:3:needle\n{a}-4-four\n--\n{a}-6-six\n{a}:7:needle\n--\n{b}:1:needle\n",
+		);
+		assert_eq!(expected, String::from_utf8(out).unwrap());
+
+		grep.mode = OutputMode::Count;
+		let mut out = Vec::new();
+		run_to(&grep, &mut out).unwrap();
+		assert_eq!(format!("{a}:2\n{b}:1\n"), String::from_utf8(out).unwrap());
+
+		grep.mode = OutputMode::FilesWithMatches;
+		grep.pattern = "six".to_string();
+		let mut out = Vec::new();
+		run_to(&grep, &mut out).unwrap();
+		assert_eq!(format!("{a}\n"), String::from_utf8(out).unwrap());
+	}
+
+	#[test]
+	fn can_preview_and_replace_with_captures() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("main.rs");
+		let original = "let x = foo(1);\r\nlet y = 2;\r\n\n\n\n\n\n\nlet z = foo(3);";
+		fs::write(&path, original).unwrap();
+
+		let mut grep = Config::new("r".to_string(), r"foo\((?<arg>\d)\)".to_string(), vec![dir.path().to_path_buf()]).unwrap();
+		let mut out = Vec::new();
+		let summary = replace_to(&grep, "bar($1, ${arg})", ReplaceMode::Preview, &mut io::empty(), &mut out).unwrap();
+		assert_eq!(ReplaceSummary { files: 1, lines: 2 }, summary);
+		let name = path.display();
+		let expected = format!(
+			"--- {name}\n+++ {name}\n@@ -1,4 +1,4 @@\n-let x = foo(1);\n+let x = bar(1, 1);\n let y = 2;\n \n \n@@ -6,4 +6,4 @@\n \n \n \n-let z = foo(3);\n+let z = bar(3, 3);\n",
+		);
+		assert_eq!(expected, String::from_utf8(out).unwrap());
+		assert_eq!(original, fs::read_to_string(&path).unwrap());
+
+		// Only the Second Hunk Is Accepted, and Line Endings Survive
+		let mut answers = io::Cursor::new("n\nmaybe\ny\n");
+		replace_to(&grep, "bar($1)", ReplaceMode::Interactive, &mut answers, &mut Vec::new()).unwrap();
+		assert_eq!(original.replace("foo(3)", "bar(3)"), fs::read_to_string(&path).unwrap());
 
-		assert_eq!(vec!["Rust:", "Trust me."], search_case_insensitive(pattern, contents));
+		// Plain Patterns Put the Template in Exactly as Written
+		grep.regex = false;
+		grep.pattern = "bar".to_string();
+		replace_to(&grep, "$1", ReplaceMode::Write, &mut io::empty(), &mut Vec::new()).unwrap();
+		assert!(fs::read_to_string(&path).unwrap().ends_with("let z = $1(3);"));
 	}
 }
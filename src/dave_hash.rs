@@ -1,26 +1,45 @@
+use blake2::{Blake2b512, Blake2s256};
+use hmac::{Mac, SimpleHmac};
+use rayon::prelude::*;
 use sha2::Digest;
-use std::error::Error;
-use std::io;
+use sha2::digest::core_api::BlockSizeUser;
+use std::fs::File;
+use std::io::{self, Read};
 use std::fmt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use walkdir::WalkDir;
 
 // Possible Hashing Algorithms
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
 pub enum HashType {
 	Md5,
+	Sha1,
 	Sha256,
 	Sha384,
-	Sha512
+	Sha512,
+	Sha3_256,
+	Sha3_512,
+	Blake2b,
+	Blake2s,
+	Blake3,
+	Crc32,
 }
 
 impl Into<Box<dyn Hasher>> for HashType {
 	fn into(self) -> Box<dyn Hasher> {
 		match self {
 			HashType::Md5 		=> Box::new(Md5Hash {}),
+			HashType::Sha1 		=> Box::new(Sha1Hash {}),
 			HashType::Sha256 	=> Box::new(Sha256Hash {}),
 			HashType::Sha384 	=> Box::new(Sha384Hash {}),
 			HashType::Sha512 	=> Box::new(Sha512Hash {}),
+			HashType::Sha3_256 	=> Box::new(Sha3_256Hash {}),
+			HashType::Sha3_512 	=> Box::new(Sha3_512Hash {}),
+			HashType::Blake2b 	=> Box::new(Blake2bHash {}),
+			HashType::Blake2s 	=> Box::new(Blake2sHash {}),
+			HashType::Blake3 	=> Box::new(Blake3Hash { key: None }),
+			HashType::Crc32 	=> Box::new(Crc32Hash {}),
 		}
 	}
 }
@@ -29,146 +48,209 @@ impl fmt::Display for HashType {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		match *self {
 			HashType::Md5 => write!(f, "MD5"),
+			HashType::Sha1 => write!(f, "SHA_1"),
 			HashType::Sha256 => write!(f, "SHA_256"),
 			HashType::Sha384 => write!(f, "SHA_384"),
 			HashType::Sha512 => write!(f, "SHA_512"),
+			HashType::Sha3_256 => write!(f, "SHA3_256"),
+			HashType::Sha3_512 => write!(f, "SHA3_512"),
+			HashType::Blake2b => write!(f, "BLAKE2B"),
+			HashType::Blake2s => write!(f, "BLAKE2S"),
+			HashType::Blake3 => write!(f, "BLAKE3"),
+			HashType::Crc32 => write!(f, "CRC32"),
 		}
 	}
 }
 
-#[derive(Debug)]
-pub struct DaveError {
-	source: DaveErrorPal,
-}
+// Names Accepted on the Command Line, in the Order Shown in Help
+pub const HASH_TYPE_NAMES: [&str; 11] = [
+	"md5", "sha-1", "sha-256", "sha-384", "sha-512", "sha3-256", "sha3-512", "blake2b", "blake2s", "blake3", "crc32",
+];
 
-impl fmt::Display for DaveError {
-	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		write!(f, "Dave Error Occurred!")
+impl FromStr for HashType {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<HashType, String> {
+		match s.to_lowercase().replace('_', "-").as_str() {
+			"md5" 					=> Ok(HashType::Md5),
+			"sha1" | "sha-1" 		=> Ok(HashType::Sha1),
+			"sha256" | "sha-256" 	=> Ok(HashType::Sha256),
+			"sha384" | "sha-384" 	=> Ok(HashType::Sha384),
+			"sha512" | "sha-512" 	=> Ok(HashType::Sha512),
+			"sha3-256" 				=> Ok(HashType::Sha3_256),
+			"sha3-512" 				=> Ok(HashType::Sha3_512),
+			"blake2b" 				=> Ok(HashType::Blake2b),
+			"blake2s" 				=> Ok(HashType::Blake2s),
+			"blake3" 				=> Ok(HashType::Blake3),
+			"crc32" 				=> Ok(HashType::Crc32),
+			_ => Err(format!("'{}' is not a hashing algorithm. Use one of {}", s, HASH_TYPE_NAMES.join(", "))),
+		}
 	}
 }
 
-impl Error for DaveError {
-	fn source(&self) -> Option<&(dyn Error + 'static)> {
-		Some(&self.source)
+impl HashType {
+	// A Hasher Keyed With a Secret. SHA and BLAKE2 Families Use HMAC,
+	// BLAKE3 Uses Its Own Keyed Mode Which Needs a 32 Byte Key
+	pub fn keyed(self, key: &[u8]) -> Result<Box<dyn Hasher>, String> {
+		match self {
+			HashType::Sha1 		=> Ok(Box::new(HmacHash::<sha1::Sha1>::new(key))),
+			HashType::Sha256 	=> Ok(Box::new(HmacHash::<sha2::Sha256>::new(key))),
+			HashType::Sha384 	=> Ok(Box::new(HmacHash::<sha2::Sha384>::new(key))),
+			HashType::Sha512 	=> Ok(Box::new(HmacHash::<sha2::Sha512>::new(key))),
+			HashType::Sha3_256 	=> Ok(Box::new(HmacHash::<sha3::Sha3_256>::new(key))),
+			HashType::Sha3_512 	=> Ok(Box::new(HmacHash::<sha3::Sha3_512>::new(key))),
+			HashType::Blake2b 	=> Ok(Box::new(HmacHash::<Blake2b512>::new(key))),
+			HashType::Blake2s 	=> Ok(Box::new(HmacHash::<Blake2s256>::new(key))),
+			HashType::Blake3 => match <[u8; blake3::KEY_LEN]>::try_from(key) {
+				Ok(key) => Ok(Box::new(Blake3Hash { key: Some(key) })),
+				Err(_) => Err(format!("BLAKE3 Keys Must be Exactly {} Bytes, Got {}", blake3::KEY_LEN, key.len())),
+			},
+			HashType::Md5 | HashType::Crc32 => Err(format!("{} Can't be Used as a Keyed Hash", self)),
+		}
 	}
 }
 
-#[derive(Debug)]
-struct DaveErrorPal;
+// Size of Each Read While Hashing, so Large Files Never Sit in Memory
+pub const HASH_CHUNK_SIZE: usize = 64 * 1024;
 
-impl fmt::Display for DaveErrorPal {
-	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		write!(f, "Dave Error Pal Occurred!")
+pub trait Hasher: Send + Sync {
+	fn hash_reader(&self, reader: &mut dyn Read) -> io::Result<Vec<u8>>;
+
+	fn hash(&self, file: PathBuf) -> io::Result<Vec<u8>> {
+		let mut file = File::open(file)?;
+		self.hash_reader(&mut file)
 	}
 }
 
-impl Error for DaveErrorPal {}
-
-#[allow(dead_code)]
-fn get_dave_error() -> Result<(), DaveError> {
-	Err(DaveError { source: DaveErrorPal })
+pub struct Md5Hash;
+pub struct Sha1Hash;
+pub struct Sha256Hash;
+pub struct Sha384Hash;
+pub struct Sha512Hash;
+pub struct Sha3_256Hash;
+pub struct Sha3_512Hash;
+pub struct Blake2bHash;
+pub struct Blake2sHash;
+pub struct Crc32Hash;
+pub struct Blake3Hash {
+	key: Option<[u8; blake3::KEY_LEN]>,
+}
+pub struct HmacHash<D> {
+	key: Vec<u8>,
+	digest: std::marker::PhantomData<D>,
 }
 
-impl FromStr for HashType {
-	type Err = DaveError;
-	fn from_str(s: &str) -> Result<HashType, DaveError> {
-		let gotten_hash_type = match s {
-			"md5" 		=> HashType::Md5,
-			"sha-384" 	=> HashType::Sha384,
-			"sha-512" 	=> HashType::Sha512,
-			_ 			=> HashType::Sha256,
-		};
-		
-		Ok(gotten_hash_type)
+impl<D> HmacHash<D> {
+	pub fn new(key: &[u8]) -> Self {
+		HmacHash { key: key.to_vec(), digest: std::marker::PhantomData }
 	}
 }
 
-pub trait Hasher {
-	fn hash(&self, file: PathBuf) -> io::Result<Vec<u8>>;
+// Hand a Reader's Bytes to a Hashing Function One Chunk at a Time
+fn read_chunks(reader: &mut dyn Read, mut update: impl FnMut(&[u8])) -> io::Result<()> {
+	let mut buffer = vec![0; HASH_CHUNK_SIZE];
+	loop {
+		match reader.read(&mut buffer) {
+			Ok(0) => return Ok(()),
+			Ok(read) => update(&buffer[..read]),
+			Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+			Err(error) => return Err(error),
+		}
+	}
 }
 
-pub struct Md5Hash;
-pub struct Sha256Hash;
-pub struct Sha384Hash;
-pub struct Sha512Hash;
+fn digest_reader<D: Digest>(reader: &mut dyn Read) -> io::Result<Vec<u8>> {
+	let mut hasher = D::new();
+	read_chunks(reader, |chunk| hasher.update(chunk))?;
+	Ok(hasher.finalize().to_vec())
+}
 
 impl Hasher for Md5Hash {
-	fn hash(&self, file: PathBuf) -> io::Result<Vec<u8>> {
+	fn hash_reader(&self, reader: &mut dyn Read) -> io::Result<Vec<u8>> {
 		let mut md5_context = md5::Context::new();
-		match std::fs::read(file) {
-			Ok(bytes) => {
-				md5_context.consume(&bytes);
-			},
-			Err(error) => {
-				if error.kind() == std::io::ErrorKind::PermissionDenied {
-					eprintln!("Please Run Again With Appropriate Permissions");
-				}
-				panic!("{}", error);
-			}
-		}
+		read_chunks(reader, |chunk| md5_context.consume(chunk))?;
 
 		let md5_digest = md5_context.compute();
 		Ok(md5_digest.to_vec())
 	}
 }
 
-impl Hasher for Sha256Hash {
-	fn hash(&self, file: PathBuf) -> io::Result<Vec<u8>> {
-		let mut hasher = sha2::Sha256::new();
-		match std::fs::read(file) {
-			Ok(bytes) => {
-				hasher.update(&bytes);
-			},
-			Err(error) => {
-				if error.kind() == std::io::ErrorKind::PermissionDenied {
-					eprintln!("Please Run Again With Appropriate Permissions");
-				}
-				panic!("{}", error);
-			}
-		}
+impl Hasher for Sha1Hash {
+	fn hash_reader(&self, reader: &mut dyn Read) -> io::Result<Vec<u8>> {
+		digest_reader::<sha1::Sha1>(reader)
+	}
+}
 
-		let hash = hasher.finalize();
-		Ok(hash.to_vec())
+impl Hasher for Sha256Hash {
+	fn hash_reader(&self, reader: &mut dyn Read) -> io::Result<Vec<u8>> {
+		digest_reader::<sha2::Sha256>(reader)
 	}
 }
 
 impl Hasher for Sha384Hash {
-	fn hash(&self, file: PathBuf) -> io::Result<Vec<u8>> {
-		let mut hasher = sha2::Sha384::new();
-		match std::fs::read(file) {
-			Ok(bytes) => {
-				hasher.update(&bytes);
-			},
-			Err(error) => {
-				if error.kind() == std::io::ErrorKind::PermissionDenied {
-					eprintln!("Please Run Again With Appropriate Permissions");
-				}
-				panic!("{}", error);
-			}
-		}
-
-		let hash = hasher.finalize();
-		Ok(hash.to_vec())
+	fn hash_reader(&self, reader: &mut dyn Read) -> io::Result<Vec<u8>> {
+		digest_reader::<sha2::Sha384>(reader)
 	}
 }
 
 impl Hasher for Sha512Hash {
-	fn hash(&self, file: PathBuf) -> io::Result<Vec<u8>> {
-		let mut hasher = sha2::Sha512::new();
-		match std::fs::read(file) {
-			Ok(bytes) => {
-				hasher.update(&bytes);
-			},
-			Err(error) => {
-				if error.kind() == std::io::ErrorKind::PermissionDenied {
-					eprintln!("Please Run Again With Appropriate Permissions");
-				}
-				panic!("{}", error);
-			}
-		}
+	fn hash_reader(&self, reader: &mut dyn Read) -> io::Result<Vec<u8>> {
+		digest_reader::<sha2::Sha512>(reader)
+	}
+}
+
+impl Hasher for Sha3_256Hash {
+	fn hash_reader(&self, reader: &mut dyn Read) -> io::Result<Vec<u8>> {
+		digest_reader::<sha3::Sha3_256>(reader)
+	}
+}
 
-		let hash = hasher.finalize();
-		Ok(hash.to_vec())
+impl Hasher for Sha3_512Hash {
+	fn hash_reader(&self, reader: &mut dyn Read) -> io::Result<Vec<u8>> {
+		digest_reader::<sha3::Sha3_512>(reader)
+	}
+}
+
+impl Hasher for Blake2bHash {
+	fn hash_reader(&self, reader: &mut dyn Read) -> io::Result<Vec<u8>> {
+		digest_reader::<Blake2b512>(reader)
+	}
+}
+
+impl Hasher for Blake2sHash {
+	fn hash_reader(&self, reader: &mut dyn Read) -> io::Result<Vec<u8>> {
+		digest_reader::<Blake2s256>(reader)
+	}
+}
+
+impl Hasher for Blake3Hash {
+	fn hash_reader(&self, reader: &mut dyn Read) -> io::Result<Vec<u8>> {
+		let mut hasher = match &self.key {
+			Some(key) => blake3::Hasher::new_keyed(key),
+			None => blake3::Hasher::new(),
+		};
+		read_chunks(reader, |chunk| {
+			hasher.update(chunk);
+		})?;
+		Ok(hasher.finalize().as_bytes().to_vec())
+	}
+}
+
+// Written Big Endian, Matching the Usual Eight Hex Digit Form
+impl Hasher for Crc32Hash {
+	fn hash_reader(&self, reader: &mut dyn Read) -> io::Result<Vec<u8>> {
+		let mut hasher = crc32fast::Hasher::new();
+		read_chunks(reader, |chunk| hasher.update(chunk))?;
+		Ok(hasher.finalize().to_be_bytes().to_vec())
+	}
+}
+
+impl<D: Digest + BlockSizeUser + Clone + Send + Sync> Hasher for HmacHash<D> {
+	fn hash_reader(&self, reader: &mut dyn Read) -> io::Result<Vec<u8>> {
+		let mut mac = <SimpleHmac<D> as Mac>::new_from_slice(&self.key)
+			.map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error.to_string()))?;
+		read_chunks(reader, |chunk| mac.update(chunk))?;
+		Ok(mac.finalize().into_bytes().to_vec())
 	}
 }
 
@@ -180,3 +262,213 @@ pub fn hash_file(hash_type: HashType, path: PathBuf) -> io::Result<Vec<u8>> {
 	let hasher: Box<dyn Hasher> = hash_type.into();
 	hasher.hash(file)
 }
+
+// Expand Directories Into the Files Beneath Them, Sorted so Output
+// Is the Same From Run to Run. Files Are Kept in the Order Given, and
+// Anything That Can't be Walked Comes Back With the Path That Failed
+pub fn collect_files(paths: &[PathBuf]) -> Vec<Result<PathBuf, (PathBuf, io::Error)>> {
+	let mut files = Vec::new();
+	for path in paths {
+		if !path.is_dir() {
+			files.push(Ok(path.clone()));
+			continue
+		}
+		let mut found = Vec::new();
+		for entry in WalkDir::new(path).sort_by_file_name() {
+			match entry {
+				Ok(entry) if entry.file_type().is_file() => found.push(Ok(entry.into_path())),
+				Ok(_) => {},
+				Err(error) => {
+					let failed = error.path().unwrap_or(path).to_path_buf();
+					found.push(Err((failed, io::Error::from(error))));
+				},
+			}
+		}
+		files.extend(found);
+	}
+	files
+}
+
+// Hash Many Files at Once, Returning Results in the Order Given
+pub fn hash_files(hasher: &dyn Hasher, files: &[PathBuf]) -> Vec<io::Result<Vec<u8>>> {
+	files
+		.par_iter()
+		.map(|file| hasher.hash(file.clone()))
+		.collect()
+}
+
+// One Line of a sha256sum Style Manifest. Names Holding a Backslash
+// or Newline Are Escaped and the Line Marked With a Leading Backslash
+pub fn format_sum_line(digest: &[u8], path: &Path) -> String {
+	let name = path.to_string_lossy();
+	if name.contains('\\') || name.contains('\n') {
+		let escaped = name.replace('\\', "\\\\").replace('\n', "\\n");
+		format!("\\{}  {}", hex::encode(digest), escaped)
+	} else {
+		format!("{}  {}", hex::encode(digest), name)
+	}
+}
+
+// An Expected Digest Read From a Manifest
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SumEntry {
+	pub digest: String,
+	pub path: PathBuf,
+}
+
+// Read a Line Written by sha256sum, md5sum or format_sum_line. Both
+// Text ("  ") and Binary (" *") Separators Are Accepted
+pub fn parse_sum_line(line: &str) -> Option<SumEntry> {
+	let (escaped, line) = match line.strip_prefix('\\') {
+		Some(rest) => (true, rest),
+		None => (false, line),
+	};
+	let (digest, name) = line.split_once(' ')?;
+	let name = name.strip_prefix(' ').or_else(|| name.strip_prefix('*'))?;
+	if digest.is_empty() || name.is_empty() || !digest.chars().all(|c| c.is_ascii_hexdigit()) {
+		return None
+	}
+
+	let name = if escaped {
+		let mut unescaped = String::new();
+		let mut chars = name.chars();
+		while let Some(c) = chars.next() {
+			if c != '\\' {
+				unescaped.push(c);
+				continue
+			}
+			match chars.next()? {
+				'n' => unescaped.push('\n'),
+				'\\' => unescaped.push('\\'),
+				_ => return None,
+			}
+		}
+		unescaped
+	} else {
+		name.to_string()
+	};
+	Some(SumEntry { digest: digest.to_lowercase(), path: PathBuf::from(name) })
+}
+
+#[derive(Debug)]
+pub enum CheckStatus {
+	Ok,
+	Failed,
+	Missing,
+	Unreadable(io::Error),
+}
+
+impl fmt::Display for CheckStatus {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			CheckStatus::Ok => write!(f, "OK"),
+			CheckStatus::Failed => write!(f, "FAILED"),
+			CheckStatus::Missing => write!(f, "MISSING"),
+			CheckStatus::Unreadable(_) => write!(f, "FAILED open or read"),
+		}
+	}
+}
+
+// Verify Every Entry of a Manifest, Returning Statuses in Manifest Order
+pub fn check_sums(hasher: &dyn Hasher, entries: &[SumEntry]) -> Vec<CheckStatus> {
+	entries
+		.par_iter()
+		.map(|entry| match hasher.hash(entry.path.clone()) {
+			Ok(digest) if hex::encode(&digest) == entry.digest => CheckStatus::Ok,
+			Ok(_) => CheckStatus::Failed,
+			Err(error) if error.kind() == io::ErrorKind::NotFound => CheckStatus::Missing,
+			Err(error) => CheckStatus::Unreadable(error),
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn can_stream_known_digests() {
+		let digest = |hash_type: HashType, bytes: &[u8]| {
+			let hasher: Box<dyn Hasher> = hash_type.into();
+			hex::encode(hasher.hash_reader(&mut &bytes[..]).unwrap())
+		};
+		assert_eq!("900150983cd24fb0d6963f7d28e17f72", digest(HashType::Md5, b"abc"));
+		assert_eq!("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad", digest(HashType::Sha256, b"abc"));
+
+		// Inputs Spanning Several Chunks Hash the Same as One Read
+		let large = vec![7u8; HASH_CHUNK_SIZE * 3 + 5];
+		let expected = hex::encode(sha2::Sha512::digest(&large));
+		assert_eq!(expected, digest(HashType::Sha512, &large));
+	}
+
+	#[test]
+	fn can_hash_with_every_algorithm() {
+		let digest = |hasher: Box<dyn Hasher>| hex::encode(hasher.hash_reader(&mut &b"abc"[..]).unwrap());
+		let expected = [
+			(HashType::Sha1, "a9993e364706816aba3e25717850c26c9cd0d89d"),
+			(HashType::Sha3_256, "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532"),
+			(HashType::Blake2s, "508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982"),
+			(HashType::Blake3, "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"),
+			(HashType::Crc32, "352441c2"),
+		];
+		for (hash_type, hex) in expected {
+			assert_eq!(hex, digest(hash_type.into()), "{}", hash_type);
+		}
+		for name in HASH_TYPE_NAMES {
+			let hash_type = HashType::from_str(name).unwrap();
+			let hasher: Box<dyn Hasher> = hash_type.into();
+			assert!(!hasher.hash_reader(&mut &b""[..]).unwrap().is_empty());
+		}
+		assert!(HashType::from_str("sha-999").is_err());
+	}
+
+	#[test]
+	fn can_hash_with_a_key() {
+		// RFC 4231 Test Case 2
+		let hmac = HashType::Sha256.keyed(b"Jefe").unwrap();
+		assert_eq!(
+			"5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+			hex::encode(hmac.hash_reader(&mut &b"what do ya want for nothing?"[..]).unwrap()),
+		);
+		assert!(HashType::Blake3.keyed(&[7; 32]).is_ok());
+		assert!(HashType::Blake3.keyed(b"short").is_err());
+		assert!(HashType::Crc32.keyed(b"key").is_err());
+	}
+
+	#[test]
+	fn can_round_trip_sum_lines() {
+		for name in ["plain.txt", "dir/with space.txt", "back\\slash", "new\nline"] {
+			let line = format_sum_line(&[0xab, 0xcd], Path::new(name));
+			let entry = parse_sum_line(&line).unwrap();
+			assert_eq!(SumEntry { digest: "abcd".to_string(), path: PathBuf::from(name) }, entry);
+		}
+		assert_eq!(Some(PathBuf::from("bin.iso")), parse_sum_line("ABCD *bin.iso").map(|entry| entry.path));
+		assert_eq!(None, parse_sum_line("not a sum line"));
+		assert_eq!(None, parse_sum_line("abcd"));
+	}
+
+	#[test]
+	fn can_check_sums() {
+		let dir = tempfile::tempdir().unwrap();
+		std::fs::create_dir(dir.path().join("nested")).unwrap();
+		std::fs::write(dir.path().join("nested/b.txt"), "bee").unwrap();
+		std::fs::write(dir.path().join("a.txt"), "ay").unwrap();
+
+		let files: Vec<PathBuf> = collect_files(&[dir.path().to_path_buf()]).into_iter().map(Result::unwrap).collect();
+		assert_eq!(vec![dir.path().join("a.txt"), dir.path().join("nested/b.txt")], files);
+
+		let hasher: Box<dyn Hasher> = HashType::Sha256.into();
+		let manifest: Vec<String> = files
+			.iter()
+			.zip(hash_files(hasher.as_ref(), &files))
+			.map(|(file, digest)| format_sum_line(&digest.unwrap(), file))
+			.collect();
+		let entries: Vec<SumEntry> = manifest.iter().filter_map(|line| parse_sum_line(line)).collect();
+		assert!(check_sums(hasher.as_ref(), &entries).iter().all(|status| matches!(status, CheckStatus::Ok)));
+
+		std::fs::write(dir.path().join("a.txt"), "changed").unwrap();
+		std::fs::remove_file(dir.path().join("nested/b.txt")).unwrap();
+		let statuses: Vec<String> = check_sums(hasher.as_ref(), &entries).iter().map(|status| status.to_string()).collect();
+		assert_eq!(vec!["FAILED", "MISSING"], statuses);
+	}
+}
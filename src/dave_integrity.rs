@@ -0,0 +1,251 @@
+use chrono::{DateTime, Utc};
+use colored::*;
+use crate::dave_hash::{collect_files, HashType, Hasher};
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// What Was Known About One File When the Baseline Was Taken
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FileRecord {
+	pub size: u64,
+	pub modified: Option<DateTime<Utc>>,
+	pub mode: u32,
+	pub digest: String,
+}
+
+// Snapshot of Every File Under a Directory
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct IntegrityBaseline {
+	pub root: PathBuf,
+	pub hash_type: HashType,
+	pub created: DateTime<Utc>,
+	// Keyed by Path Relative to the Root
+	pub files: BTreeMap<String, FileRecord>,
+}
+
+// Permission Bits, or Just Read Only vs Writable Where There Are None
+#[cfg(unix)]
+fn file_mode(metadata: &fs::Metadata) -> u32 {
+	use std::os::unix::fs::PermissionsExt;
+	metadata.permissions().mode() & 0o7777
+}
+
+#[cfg(not(unix))]
+fn file_mode(metadata: &fs::Metadata) -> u32 {
+	if metadata.permissions().readonly() { 0o444 } else { 0o644 }
+}
+
+fn record_file(hasher: &dyn Hasher, path: &Path) -> io::Result<FileRecord> {
+	let metadata = fs::metadata(path)?;
+	Ok(FileRecord {
+		size: metadata.len(),
+		modified: metadata.modified().ok().map(DateTime::<Utc>::from),
+		mode: file_mode(&metadata),
+		digest: hex::encode(hasher.hash(path.to_path_buf())?),
+	})
+}
+
+// Record Every File Under a Directory. Files That Can't be Read Are
+// Returned Separately Rather Than Failing the Whole Scan
+pub fn scan_directory(root: &Path, hash_type: HashType) -> io::Result<(IntegrityBaseline, Vec<(PathBuf, io::Error)>)> {
+	let root = root.canonicalize()?;
+	if !root.is_dir() {
+		return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("{} is not a directory", root.display())))
+	}
+	let mut errors = Vec::new();
+	let mut files = Vec::new();
+	for file in collect_files(std::slice::from_ref(&root)) {
+		match file {
+			Ok(file) => files.push(file),
+			Err(error) => errors.push(error),
+		}
+	}
+
+	let hasher: Box<dyn Hasher> = hash_type.into();
+	let records: Vec<io::Result<FileRecord>> = files.par_iter().map(|file| record_file(hasher.as_ref(), file)).collect();
+	let mut baseline = IntegrityBaseline { root: root.clone(), hash_type, created: Utc::now(), files: BTreeMap::new() };
+	for (file, record) in files.into_iter().zip(records) {
+		match record {
+			Ok(record) => {
+				let relative = file.strip_prefix(&root).unwrap_or(&file).to_string_lossy().to_string();
+				baseline.files.insert(relative, record);
+			},
+			Err(error) => errors.push((file, error)),
+		}
+	}
+	Ok((baseline, errors))
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ContentChange {
+	pub path: String,
+	pub old_size: u64,
+	pub new_size: u64,
+	pub old_digest: String,
+	pub new_digest: String,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ModeChange {
+	pub path: String,
+	pub old_mode: String,
+	pub new_mode: String,
+}
+
+// Differences Between a Baseline and the Directory as It Is Now
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct IntegrityReport {
+	pub root: PathBuf,
+	pub baseline_created: Option<DateTime<Utc>>,
+	pub checked: usize,
+	pub added: Vec<String>,
+	pub removed: Vec<String>,
+	pub modified: Vec<ContentChange>,
+	pub permissions: Vec<ModeChange>,
+	pub unreadable: Vec<String>,
+}
+
+impl IntegrityReport {
+	pub fn is_clean(&self) -> bool {
+		self.added.is_empty()
+			&& self.removed.is_empty()
+			&& self.modified.is_empty()
+			&& self.permissions.is_empty()
+			&& self.unreadable.is_empty()
+	}
+}
+
+// Compare a Fresh Scan Against the Baseline. Modification Times Are
+// Kept for Reference Only; Content Is Judged by Size and Digest
+pub fn compare(baseline: &IntegrityBaseline, current: &IntegrityBaseline) -> IntegrityReport {
+	let mut report = IntegrityReport {
+		root: baseline.root.clone(),
+		baseline_created: Some(baseline.created),
+		checked: current.files.len(),
+		..IntegrityReport::default()
+	};
+	for (path, old) in &baseline.files {
+		let Some(new) = current.files.get(path) else {
+			report.removed.push(path.clone());
+			continue
+		};
+		if old.size != new.size || old.digest != new.digest {
+			report.modified.push(ContentChange {
+				path: path.clone(),
+				old_size: old.size,
+				new_size: new.size,
+				old_digest: old.digest.clone(),
+				new_digest: new.digest.clone(),
+			});
+		}
+		if old.mode != new.mode {
+			report.permissions.push(ModeChange {
+				path: path.clone(),
+				old_mode: format!("{:o}", old.mode),
+				new_mode: format!("{:o}", new.mode),
+			});
+		}
+	}
+	report.added = current.files.keys().filter(|path| !baseline.files.contains_key(*path)).cloned().collect();
+	report
+}
+
+// Scan the Baseline's Directory Again and Compare
+pub fn check_baseline(baseline: &IntegrityBaseline) -> io::Result<IntegrityReport> {
+	let (current, errors) = scan_directory(&baseline.root, baseline.hash_type)?;
+	let mut report = compare(baseline, &current);
+	for (path, _) in errors {
+		let relative = path.strip_prefix(&baseline.root).unwrap_or(&path).to_string_lossy().to_string();
+		// Files That Exist but Can't be Read Aren't Missing, Nor Is
+		// Anything Under a Directory That Couldn't be Listed
+		report.removed.retain(|removed| !Path::new(removed).starts_with(&relative));
+		report.unreadable.push(relative);
+	}
+	Ok(report)
+}
+
+pub fn print_integrity_report(report: &IntegrityReport) {
+	println!("##==>> Integrity of {}", report.root.display());
+	if let Some(created) = report.baseline_created {
+		println!("##==>> Baseline Taken {}", created.format("%Y-%m-%d %H:%M:%S UTC"));
+	}
+	for path in &report.added {
+		println!("    {} {}", "ADDED    ".yellow(), path);
+	}
+	for path in &report.removed {
+		println!("    {} {}", "REMOVED  ".red(), path);
+	}
+	for change in &report.modified {
+		println!("    {} {} ({} -> {} bytes)", "MODIFIED ".red(), change.path, change.old_size, change.new_size);
+	}
+	for change in &report.permissions {
+		println!("    {} {} ({} -> {})", "MODE     ".yellow(), change.path, change.old_mode, change.new_mode);
+	}
+	for path in &report.unreadable {
+		println!("    {} {}", "UNREADABLE".red(), path);
+	}
+
+	let summary = format!(
+		"{} Checked, {} Added, {} Removed, {} Modified, {} Permission Changes",
+		report.checked,
+		report.added.len(),
+		report.removed.len(),
+		report.modified.len(),
+		report.permissions.len(),
+	);
+	if report.is_clean() {
+		println!("##==>> {} {}", "OK:".green(), summary);
+	} else {
+		println!("##==>> {} {}", "DRIFT:".red(), summary);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn can_detect_drift() {
+		let dir = tempfile::tempdir().unwrap();
+		let root = dir.path();
+		fs::create_dir(root.join("bin")).unwrap();
+		fs::write(root.join("bin/app"), "v1").unwrap();
+		fs::write(root.join("config.toml"), "port = 80").unwrap();
+		fs::write(root.join("old.log"), "log").unwrap();
+
+		let (baseline, errors) = scan_directory(root, HashType::Sha256).unwrap();
+		assert!(errors.is_empty());
+		assert_eq!(vec!["bin/app", "config.toml", "old.log"], baseline.files.keys().collect::<Vec<_>>());
+		assert!(check_baseline(&baseline).unwrap().is_clean());
+
+		fs::write(root.join("bin/app"), "v2-tampered").unwrap();
+		fs::remove_file(root.join("old.log")).unwrap();
+		fs::write(root.join("new.sh"), "echo hi").unwrap();
+		#[cfg(unix)]
+		{
+			use std::os::unix::fs::PermissionsExt;
+			fs::set_permissions(root.join("config.toml"), fs::Permissions::from_mode(0o600)).unwrap();
+		}
+
+		let report = check_baseline(&baseline).unwrap();
+		assert!(!report.is_clean());
+		assert_eq!(vec!["new.sh"], report.added);
+		assert_eq!(vec!["old.log"], report.removed);
+		assert_eq!(1, report.modified.len());
+		assert_eq!("bin/app", report.modified[0].path);
+		assert_eq!((2, 11), (report.modified[0].old_size, report.modified[0].new_size));
+		if cfg!(unix) {
+			assert_eq!("600", report.permissions[0].new_mode);
+		}
+
+		let json = serde_json::to_value(&report).unwrap();
+		assert_eq!("new.sh", json["added"][0]);
+
+		// Baselines Survive the Database's Encoding
+		let bytes = bincode::serialize(&baseline).unwrap();
+		assert_eq!(baseline, bincode::deserialize::<IntegrityBaseline>(&bytes).unwrap());
+	}
+}
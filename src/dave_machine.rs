@@ -1,4 +1,6 @@
-use std::fs::File;
+use crate::config::{find_machines_path, CONFIG};
+use std::fs::{self, File};
+use std::path::PathBuf;
 use std::io::{
 	self,
 	ErrorKind,
@@ -26,6 +28,13 @@ use ndarray::{
 };
 use ndarray_npy::write_npy;
 
+// Where Clustering Results Are Saved, Created if Needed
+fn machines_dir() -> io::Result<PathBuf> {
+	let machines_path = CONFIG.read().map_or_else(|_| find_machines_path(), |config| config.machines_path());
+	fs::create_dir_all(&machines_path)?;
+	Ok(machines_path)
+}
+
 // Routine K-Means Task:
 // Build Synthetic Dataset, Fit Algorithm On It
 // Save Training Data and Predictions to Disk
@@ -56,22 +65,23 @@ pub fn kmeans_task() -> io::Result<()> {
 
 	// Save Dataset to Disk and Cluster Label Assigned to Each
 	// Observation. Using 'npy' Format for Compatibility with NumPy
-	let dataset_file_string = "./dave_conf/var/daves_machines/kmeans_clustered_dataset.npy";
-	File::create(dataset_file_string)?;
-	let memberships_file_string = "./dave_conf/var/daves_machines/kmeans_clustered_memberships.npy";
-	File::create(memberships_file_string)?;
+	let machines_path = machines_dir()?;
+	let dataset_file_string = machines_path.join("kmeans_clustered_dataset.npy");
+	File::create(&dataset_file_string)?;
+	let memberships_file_string = machines_path.join("kmeans_clustered_memberships.npy");
+	File::create(&memberships_file_string)?;
 
 	println!("---------------------------------------------------------");
 	println!("##==> Writing KMeans Records to Dataset File ...");
 	write_npy(
-		dataset_file_string,
+		&dataset_file_string,
 		&records,
 	).expect("Failed to write kmeans_clustered_dataset.npy file");
 	println!("##==> INFO! KMeans Dataset File Written Successfully\n");
 
 	println!("##==> Writing KMeans Targets to Memberships File ...");
 	write_npy(
-		memberships_file_string,
+		&memberships_file_string,
 		&targets.map(|&x| x as u64),
 	).expect("Failed to write to kmeans_clustered_memberships.npy file");
 	println!("##==> INFO! KMeans Memberships File Written Successfully");
@@ -133,23 +143,24 @@ pub fn dbscan_task() -> io::Result<()> {
 
 	// Save Dataset + Cluster Label Assigned To Each Observation
 	// To Disk Using 'npy' Format For Compatibility With NumPy
-	let dataset_file_string = "./dave_conf/var/daves_machines/dbscan_clustered_dataset.npy";
-	File::create(dataset_file_string)?;
-	let memberships_file_string = "./dave_conf/var/daves_machines/dbscan_clustered_memberships.npy";
-	File::create(memberships_file_string)?;
+	let machines_path = machines_dir()?;
+	let dataset_file_string = machines_path.join("dbscan_clustered_dataset.npy");
+	File::create(&dataset_file_string)?;
+	let memberships_file_string = machines_path.join("dbscan_clustered_memberships.npy");
+	File::create(&memberships_file_string)?;
 	let (records, cluster_memberships) = (cluster_memberships.clone()?.records, cluster_memberships?.targets);
 
 	println!("---------------------------------------------------------");
 	println!("##==> Writing DBScan Records to Dataset File ...");
 	write_npy(
-		dataset_file_string,
+		&dataset_file_string,
 		&records,
 	).expect("Failed to write dbscan_clustered_dataset.npy file");
 	println!("##==> INFO! DBScan Dataset File Written Successfully\n");
 
 	println!("##==> Writing DBScan Targets to Memberships File ...");
 	write_npy(
-		memberships_file_string,
+		&memberships_file_string,
 		&cluster_memberships.map(|&x| x.map(|c| c as i64).unwrap_or(-1)),
 	).expect("Failed to write to dbscan_clustered_memberships.npy file");
 	println!("##==> INFO! DBScan Memberships File Written Successfully");
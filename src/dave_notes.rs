@@ -1,12 +1,488 @@
+use chrono::{
+	DateTime,
+	Local,
+	NaiveDate,
+	Utc,
+};
+use colored::*;
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::str::FromStr;
+
+// Format Used When Parsing and Displaying Due Dates
+pub const DUE_DATE_FORMAT: &str = "%Y-%m-%d";
+
+// How Important a Note Is
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NotePriority {
+	Low,
+	#[default]
+	Normal,
+	High,
+}
+
+impl fmt::Display for NotePriority {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			NotePriority::Low => write!(f, "low"),
+			NotePriority::Normal => write!(f, "normal"),
+			NotePriority::High => write!(f, "high"),
+		}
+	}
+}
+
+impl FromStr for NotePriority {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_lowercase().as_str() {
+			"low" | "l" => Ok(NotePriority::Low),
+			"normal" | "n" => Ok(NotePriority::Normal),
+			"high" | "h" => Ok(NotePriority::High),
+			_ => Err(format!("'{}' is not a valid priority. Use low, normal or high", s)),
+		}
+	}
+}
+
+// Parse a Due Date Given as YYYY-MM-DD
+pub fn parse_due_date(date: &str) -> Result<NaiveDate, String> {
+	NaiveDate::parse_from_str(date, DUE_DATE_FORMAT)
+		.map_err(|error| format!("'{}' is not a valid date (YYYY-MM-DD): {}", date, error))
+}
+
+// Note as It Was Stored Before Tags, Priorities and Dates Existed
 #[derive(Serialize, Deserialize)]
+pub struct LegacyDaveNote {
+	pub id: u64,
+	pub title: String,
+	pub completed: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct DaveNote {
 	pub id: u64,
 	pub title: String,
 	pub completed: bool,
+	pub tags: Vec<String>,
+	pub priority: NotePriority,
+	pub due: Option<NaiveDate>,
+	pub created: DateTime<Utc>,
+	pub updated: DateTime<Utc>,
+}
+
+impl Default for DaveNote {
+	fn default() -> Self {
+		Self::new()
+	}
 }
 
 impl DaveNote {
 	pub fn new() -> DaveNote {
-		DaveNote { id: 0, title: "".to_string(), completed: false }
+		let now = Utc::now();
+		DaveNote {
+			id: 0,
+			title: "".to_string(),
+			completed: false,
+			tags: Vec::new(),
+			priority: NotePriority::Normal,
+			due: None,
+			created: now,
+			updated: now,
+		}
+	}
+
+	// Mark Note as Modified
+	pub fn touch(&mut self) {
+		self.updated = Utc::now();
+	}
+
+	pub fn has_tag(&self, tag: &str) -> bool {
+		self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+	}
+
+	// Replace Tags, Dropping Empty and Duplicate Entries
+	pub fn set_tags<I: IntoIterator<Item = String>>(&mut self, tags: I) {
+		self.tags.clear();
+		for tag in tags {
+			let tag = tag.trim().trim_start_matches('#').to_string();
+			if !tag.is_empty() && !self.has_tag(&tag) {
+				self.tags.push(tag);
+			}
+		}
+	}
+
+	pub fn is_overdue(&self, today: NaiveDate) -> bool {
+		match self.due {
+			Some(due) => !self.completed && due < today,
+			None => false,
+		}
+	}
+}
+
+impl From<LegacyDaveNote> for DaveNote {
+	fn from(legacy: LegacyDaveNote) -> Self {
+		let mut note = DaveNote::new();
+		note.title = legacy.title;
+		note.completed = legacy.completed;
+		note
+	}
+}
+
+impl fmt::Display for DaveNote {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let status = if self.completed { "[X]" } else { "[ ]" };
+		write!(f, "{} {} - {}", status, self.id, self.title)?;
+		if self.priority != NotePriority::Normal {
+			write!(f, " ({})", self.priority)?;
+		}
+		for tag in &self.tags {
+			write!(f, " #{}", tag)?;
+		}
+		if let Some(due) = self.due {
+			write!(f, " due {}", due.format(DUE_DATE_FORMAT))?;
+		}
+		Ok(())
+	}
+}
+
+// Criteria for Which Notes to Show When Listing
+#[derive(Clone, Debug, Default)]
+pub struct NoteFilter {
+	pub tag: Option<String>,
+	pub overdue: bool,
+	pub done: bool,
+}
+
+impl NoteFilter {
+	pub fn matches(&self, note: &DaveNote, today: NaiveDate) -> bool {
+		if let Some(tag) = &self.tag {
+			if !note.has_tag(tag) {
+				return false
+			}
+		}
+		if self.overdue && !note.is_overdue(today) {
+			return false
+		}
+		if self.done && !note.completed {
+			return false
+		}
+		true
+	}
+}
+
+// Open Notes First, Then Earliest Due, Then Highest Priority, Then Oldest
+pub fn compare_notes(a: &DaveNote, b: &DaveNote) -> Ordering {
+	a.completed.cmp(&b.completed)
+		.then_with(|| match (a.due, b.due) {
+			(Some(x), Some(y)) => x.cmp(&y),
+			(Some(_), None) => Ordering::Less,
+			(None, Some(_)) => Ordering::Greater,
+			(None, None) => Ordering::Equal,
+		})
+		.then_with(|| b.priority.cmp(&a.priority))
+		.then_with(|| a.id.cmp(&b.id))
+}
+
+// Filter and Sort Notes for Display
+pub fn select_notes(notes: Vec<DaveNote>, filter: &NoteFilter, today: NaiveDate) -> Vec<DaveNote> {
+	let mut selected: Vec<DaveNote> = notes
+		.into_iter()
+		.filter(|note| filter.matches(note, today))
+		.collect();
+	selected.sort_by(compare_notes);
+	selected
+}
+
+// Formats Notes Can be Exported to and Imported From
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NoteFormat {
+	Json,
+	Csv,
+	Markdown,
+}
+
+impl FromStr for NoteFormat {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_lowercase().as_str() {
+			"json" => Ok(NoteFormat::Json),
+			"csv" => Ok(NoteFormat::Csv),
+			"md" | "markdown" => Ok(NoteFormat::Markdown),
+			_ => Err(format!("'{}' is not a valid note format. Use json, csv or md", s)),
+		}
+	}
+}
+
+// Flat Shape of a Note Used for CSV Rows
+#[derive(Serialize, Deserialize)]
+struct NoteRow {
+	id: u64,
+	title: String,
+	completed: bool,
+	tags: String,
+	priority: NotePriority,
+	due: Option<NaiveDate>,
+	created: DateTime<Utc>,
+	updated: DateTime<Utc>,
+}
+
+impl From<&DaveNote> for NoteRow {
+	fn from(note: &DaveNote) -> Self {
+		NoteRow {
+			id: note.id,
+			title: note.title.clone(),
+			completed: note.completed,
+			tags: note.tags.join(";"),
+			priority: note.priority,
+			due: note.due,
+			created: note.created,
+			updated: note.updated,
+		}
+	}
+}
+
+impl From<NoteRow> for DaveNote {
+	fn from(row: NoteRow) -> Self {
+		let mut note = DaveNote::new();
+		note.id = row.id;
+		note.title = row.title;
+		note.completed = row.completed;
+		note.set_tags(row.tags.split(';').map(String::from));
+		note.priority = row.priority;
+		note.due = row.due;
+		note.created = row.created;
+		note.updated = row.updated;
+		note
+	}
+}
+
+// Write a Note as a GitHub Style Task List Item
+fn markdown_line(note: &DaveNote) -> String {
+	let mut line = format!("- [{}] {}", if note.completed { "x" } else { " " }, note.title);
+	for tag in &note.tags {
+		line.push_str(&format!(" #{}", tag));
+	}
+	if note.priority != NotePriority::Normal {
+		line.push_str(&format!(" (priority: {})", note.priority));
+	}
+	if let Some(due) = note.due {
+		line.push_str(&format!(" (due: {})", due.format(DUE_DATE_FORMAT)));
+	}
+	line
+}
+
+// Read a Task List Item Back, Peeling Annotations Off the End
+fn parse_markdown_line(line: &str) -> Option<DaveNote> {
+	let line = line.trim();
+	let (completed, rest) = if let Some(rest) = line.strip_prefix("- [ ] ") {
+		(false, rest)
+	} else if let Some(rest) = line.strip_prefix("- [x] ").or_else(|| line.strip_prefix("- [X] ")) {
+		(true, rest)
+	} else {
+		return None
+	};
+
+	let mut note = DaveNote::new();
+	note.completed = completed;
+	let mut rest = rest.trim_end();
+	let mut tags = Vec::new();
+	loop {
+		if let Some(start) = rest.rfind(" (due: ").filter(|_| rest.ends_with(')')) {
+			if let Ok(due) = parse_due_date(&rest[start + 7..rest.len() - 1]) {
+				note.due = Some(due);
+				rest = rest[..start].trim_end();
+				continue
+			}
+		}
+		if let Some(start) = rest.rfind(" (priority: ").filter(|_| rest.ends_with(')')) {
+			if let Ok(priority) = NotePriority::from_str(&rest[start + 12..rest.len() - 1]) {
+				note.priority = priority;
+				rest = rest[..start].trim_end();
+				continue
+			}
+		}
+		match rest.rsplit_once(" #") {
+			Some((head, tag)) if !tag.is_empty() && !tag.contains(char::is_whitespace) => {
+				tags.push(tag.to_string());
+				rest = head.trim_end();
+			},
+			_ => break,
+		}
+	}
+	tags.reverse();
+	note.set_tags(tags);
+	note.title = rest.to_string();
+	if note.title.is_empty() { None } else { Some(note) }
+}
+
+pub fn export_notes<W: Write>(notes: &[DaveNote], format: NoteFormat, mut writer: W) -> Result<(), Box<dyn Error>> {
+	match format {
+		NoteFormat::Json => {
+			serde_json::to_writer_pretty(&mut writer, notes)?;
+			writeln!(writer)?;
+		},
+		NoteFormat::Csv => {
+			let mut csv_writer = csv::Writer::from_writer(writer);
+			for note in notes {
+				csv_writer.serialize(NoteRow::from(note))?;
+			}
+			csv_writer.flush()?;
+		},
+		NoteFormat::Markdown => {
+			for note in notes {
+				writeln!(writer, "{}", markdown_line(note))?;
+			}
+		},
+	}
+	Ok(())
+}
+
+pub fn parse_notes<R: Read>(format: NoteFormat, reader: R) -> Result<Vec<DaveNote>, Box<dyn Error>> {
+	let notes = match format {
+		NoteFormat::Json => serde_json::from_reader(reader)?,
+		NoteFormat::Csv => {
+			let mut notes = Vec::new();
+			for row in csv::Reader::from_reader(reader).deserialize::<NoteRow>() {
+				notes.push(DaveNote::from(row?));
+			}
+			notes
+		},
+		NoteFormat::Markdown => {
+			let mut notes = Vec::new();
+			for line in BufReader::new(reader).lines() {
+				if let Some(note) = parse_markdown_line(&line?) {
+					notes.push(note);
+				}
+			}
+			notes
+		},
+	};
+	Ok(notes)
+}
+
+// What an Import Did to the Notes Database
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NoteImport {
+	pub added: usize,
+	pub updated: usize,
+	pub unchanged: usize,
+}
+
+// Find the Stored Note an Imported One Refers To. Markdown
+// Carries No Timestamps, so Titles Alone Decide There
+pub fn find_existing<'a>(existing: &'a [DaveNote], imported: &DaveNote, format: NoteFormat) -> Option<&'a DaveNote> {
+	existing.iter().find(|note| {
+		note.title == imported.title
+			&& (format == NoteFormat::Markdown || note.created == imported.created)
+	})
+}
+
+pub fn print_notes(notes: &[DaveNote]) {
+	let today = Local::now().date_naive();
+	if notes.is_empty() {
+		println!("##==> No Notes Found");
+		return
+	}
+	for note in notes {
+		if note.is_overdue(today) {
+			println!("{}", note.to_string().red());
+		} else if note.completed {
+			println!("{}", note.to_string().dimmed());
+		} else {
+			println!("{}", note);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn note(id: u64, completed: bool, due: Option<&str>, priority: NotePriority) -> DaveNote {
+		let mut note = DaveNote::new();
+		note.id = id;
+		note.title = format!("note {}", id);
+		note.completed = completed;
+		note.due = due.map(|d| parse_due_date(d).unwrap());
+		note.priority = priority;
+		note
+	}
+
+	#[test]
+	fn can_sort_notes() {
+		let notes = vec![
+			note(1, true, None, NotePriority::High),
+			note(2, false, None, NotePriority::Low),
+			note(3, false, Some("2024-05-01"), NotePriority::Normal),
+			note(4, false, None, NotePriority::High),
+			note(5, false, Some("2024-04-01"), NotePriority::Low),
+		];
+		let sorted = select_notes(notes, &NoteFilter::default(), parse_due_date("2024-01-01").unwrap());
+		let ids: Vec<u64> = sorted.iter().map(|n| n.id).collect();
+		assert_eq!(vec![5, 3, 4, 2, 1], ids);
+	}
+
+	#[test]
+	fn can_filter_notes() {
+		let today = parse_due_date("2024-04-15").unwrap();
+		let mut tagged = note(1, false, Some("2024-04-01"), NotePriority::Normal);
+		tagged.set_tags(vec!["#Work".to_string(), "work".to_string(), "".to_string()]);
+		let notes = vec![
+			tagged,
+			note(2, true, Some("2024-04-01"), NotePriority::Normal),
+			note(3, false, Some("2024-05-01"), NotePriority::Normal),
+		];
+
+		assert_eq!(vec!["Work".to_string()], notes[0].tags);
+
+		let overdue = NoteFilter { overdue: true, ..Default::default() };
+		let ids: Vec<u64> = select_notes(notes.clone(), &overdue, today).iter().map(|n| n.id).collect();
+		assert_eq!(vec![1], ids);
+
+		let done = NoteFilter { done: true, ..Default::default() };
+		let ids: Vec<u64> = select_notes(notes.clone(), &done, today).iter().map(|n| n.id).collect();
+		assert_eq!(vec![2], ids);
+
+		let tag = NoteFilter { tag: Some("work".to_string()), ..Default::default() };
+		let ids: Vec<u64> = select_notes(notes, &tag, today).iter().map(|n| n.id).collect();
+		assert_eq!(vec![1], ids);
+	}
+
+	#[test]
+	fn can_migrate_legacy_note() {
+		let legacy = LegacyDaveNote { id: 7, title: "old".to_string(), completed: true };
+		let bytes = bincode::serialize(&legacy).unwrap();
+		let decoded: LegacyDaveNote = bincode::deserialize(&bytes).unwrap();
+		let note = DaveNote::from(decoded);
+		assert_eq!("old", note.title);
+		assert!(note.completed);
+		assert!(note.tags.is_empty());
+	}
+
+	#[test]
+	fn can_round_trip_export_formats() {
+		let mut first = note(1, false, Some("2024-05-01"), NotePriority::High);
+		first.title = "Buy milk, eggs".to_string();
+		first.set_tags(vec!["home".to_string(), "errands".to_string()]);
+		let second = note(2, true, None, NotePriority::Normal);
+		let notes = vec![first, second];
+
+		for format in [NoteFormat::Json, NoteFormat::Csv, NoteFormat::Markdown] {
+			let mut buffer = Vec::new();
+			export_notes(&notes, format, &mut buffer).unwrap();
+			let parsed = parse_notes(format, &buffer[..]).unwrap();
+			assert_eq!(notes.len(), parsed.len());
+			for (original, parsed) in notes.iter().zip(parsed.iter()) {
+				assert_eq!(original.title, parsed.title);
+				assert_eq!(original.completed, parsed.completed);
+				assert_eq!(original.tags, parsed.tags);
+				assert_eq!(original.priority, parsed.priority);
+				assert_eq!(original.due, parsed.due);
+				assert!(find_existing(&notes, parsed, format).is_some());
+			}
+		}
 	}
 }
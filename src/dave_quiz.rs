@@ -1,4 +1,5 @@
 use colored::*;
+use crate::config::{find_quiz_path, CONFIG};
 use rand::seq::SliceRandom;
 use std::fs;
 use std::io::{self, Read, Write};
@@ -51,22 +52,23 @@ fn test_question(question: &str, answer: &str, timeout: u32) -> Option<bool> {
 	result
 }
 
-pub fn dave_quiz(quiz_choice: String, total_questions: usize, user_difficulty: &str) -> io::Result<()> {
+pub fn dave_quiz(quiz_choice: String, total_questions: usize, user_difficulty: &str) -> io::Result<usize> {
 	// Determine Which Quiz Was Chosen by User
 	let quiz_tsv_filename: &str;
 	if quiz_choice == "animals" {
-		quiz_tsv_filename = "./dave_conf/var/daves_quiz/animal_quiz.tsv";
+		quiz_tsv_filename = "animal_quiz.tsv";
 	} else if quiz_choice == "strek" {
-		quiz_tsv_filename = "./dave_conf/var/daves_quiz/strek_quiz.tsv";
+		quiz_tsv_filename = "strek_quiz.tsv";
 	} else if quiz_choice == "swars" {
-		quiz_tsv_filename = "./dave_conf/var/daves_quiz/swars_quiz.tsv";
+		quiz_tsv_filename = "swars_quiz.tsv";
 	} else if quiz_choice == "mil-av" {
-		quiz_tsv_filename = "./dave_conf/var/daves_quiz/mil_av_quiz.tsv";
+		quiz_tsv_filename = "mil_av_quiz.tsv";
 	} else if quiz_choice == "gen-av" {
-		quiz_tsv_filename = "./dave_conf/var/daves_quiz/gen_av_quiz.tsv";
+		quiz_tsv_filename = "gen_av_quiz.tsv";
 	} else {
 		std::process::exit(1);
 	}
+	let quiz_path = CONFIG.read().map_or_else(|_| find_quiz_path(), |config| config.quiz_path());
 
 	// Time Given For Each Question Based
 	// on Chosen User Difficulty
@@ -80,7 +82,7 @@ pub fn dave_quiz(quiz_choice: String, total_questions: usize, user_difficulty: &
 	};
 
 	// Open and Read Contents From Quiz File
-	let mut quiz_file = fs::File::open(quiz_tsv_filename)?;
+	let mut quiz_file = fs::File::open(quiz_path.join(quiz_tsv_filename))?;
 	let mut buffer = String::new();
 	quiz_file.read_to_string(&mut buffer)?;
 
@@ -125,5 +127,5 @@ pub fn dave_quiz(quiz_choice: String, total_questions: usize, user_difficulty: &
 	if score == total_questions {
 		println!("{}", "!!! You Are Master Champion !!!".yellow());
 	}
-	Ok(())
+	Ok(score)
 }
@@ -0,0 +1,336 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use crate::dave_currency::{parse_amount, parse_currency_code, CurrencyError, RateFile, RateTable};
+use crate::dave_store::write_atomic;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration as Timeout;
+
+// How Long to Wait on a Rate Feed Before Falling Back to the Cache
+const FETCH_TIMEOUT: Timeout = Timeout::from_secs(10);
+
+// Where Fresh Rates Come From
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderKind {
+	// Only Use the Table in dave_conf/etc
+	#[default]
+	Offline,
+	// ECB Style XML, Rates Quoted Against the Euro
+	Ecb,
+	// JSON With base, date and rates Keys
+	Json,
+}
+
+// Rate Provider Settings Kept in the Config
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct ProviderConfig {
+	pub kind: ProviderKind,
+	pub url: String,
+	// How Long Fetched Rates Are Used Before Fetching Again
+	pub ttl_hours: i64,
+}
+
+impl Default for ProviderConfig {
+	fn default() -> Self {
+		ProviderConfig {
+			kind: ProviderKind::Offline,
+			url: "https://www.ecb.europa.eu/stats/eurofxref/eurofxref-daily.xml".to_string(),
+			ttl_hours: 12,
+		}
+	}
+}
+
+impl ProviderConfig {
+	// Build the Configured Provider, or None When Working Offline
+	pub fn provider(&self) -> Option<Box<dyn RateProvider>> {
+		match self.kind {
+			ProviderKind::Offline => None,
+			ProviderKind::Ecb => Some(Box::new(EcbXmlProvider::new(&self.url))),
+			ProviderKind::Json => Some(Box::new(JsonApiProvider::new(&self.url))),
+		}
+	}
+
+	pub fn ttl(&self) -> Duration {
+		Duration::hours(self.ttl_hours.max(0))
+	}
+}
+
+// Anything That Can Produce a Current Rate Table
+pub trait RateProvider {
+	fn name(&self) -> String;
+	fn fetch(&self) -> Result<RateTable, CurrencyError>;
+}
+
+fn http_get(url: &str) -> Result<String, CurrencyError> {
+	let network = |error: reqwest::Error| CurrencyError::Network(url.to_string(), error.to_string());
+	let client = reqwest::blocking::Client::builder()
+		.timeout(FETCH_TIMEOUT)
+		.build()
+		.map_err(network)?;
+	client
+		.get(url)
+		.send()
+		.and_then(|response| response.error_for_status())
+		.and_then(|response| response.text())
+		.map_err(network)
+}
+
+pub struct EcbXmlProvider {
+	url: String,
+}
+
+impl EcbXmlProvider {
+	pub fn new(url: &str) -> Self {
+		EcbXmlProvider { url: url.to_string() }
+	}
+}
+
+impl RateProvider for EcbXmlProvider {
+	fn name(&self) -> String {
+		format!("ECB feed at {}", self.url)
+	}
+
+	fn fetch(&self) -> Result<RateTable, CurrencyError> {
+		parse_ecb_xml(&http_get(&self.url)?)
+	}
+}
+
+// Read the ECB Daily Feed: Cube Elements Holding a time Attribute,
+// Each Wrapping a Cube per Currency With a Rate Against the Euro
+pub fn parse_ecb_xml(xml: &str) -> Result<RateTable, CurrencyError> {
+	let feed = |reason: String| CurrencyError::Feed(reason);
+	let document = roxmltree::Document::parse(xml).map_err(|error| feed(error.to_string()))?;
+	let day = document
+		.descendants()
+		.find(|node| node.has_tag_name("Cube") && node.has_attribute("time"))
+		.ok_or_else(|| feed("no dated Cube element".to_string()))?;
+	let time = day.attribute("time").unwrap_or_default();
+	let as_of = NaiveDate::parse_from_str(time, "%Y-%m-%d")
+		.map_err(|_| feed(format!("'{}' is not a date", time)))?;
+
+	let mut table = RateTable::new(parse_currency_code("EUR")?, as_of);
+	for cube in day.children().filter(|node| node.has_tag_name("Cube")) {
+		let (Some(code), Some(rate)) = (cube.attribute("currency"), cube.attribute("rate")) else {
+			continue
+		};
+		// Skip Codes That Aren't ISO 4217, as the JSON Feeds Do
+		let Ok(currency) = parse_currency_code(code) else {
+			continue
+		};
+		let rate = parse_amount(rate).map_err(|_| CurrencyError::InvalidRate(currency, rate.to_string()))?;
+		table.set_rate(currency, rate)?;
+	}
+	if table.currencies().len() < 2 {
+		return Err(feed("no rates in the feed".to_string()))
+	}
+	Ok(table)
+}
+
+pub struct JsonApiProvider {
+	url: String,
+}
+
+impl JsonApiProvider {
+	pub fn new(url: &str) -> Self {
+		JsonApiProvider { url: url.to_string() }
+	}
+}
+
+impl RateProvider for JsonApiProvider {
+	fn name(&self) -> String {
+		format!("JSON rates at {}", self.url)
+	}
+
+	fn fetch(&self) -> Result<RateTable, CurrencyError> {
+		parse_json_rates(&http_get(&self.url)?)
+	}
+}
+
+// Shape Shared by Most Free Rate APIs
+#[derive(Deserialize)]
+struct JsonRates {
+	base: String,
+	#[serde(alias = "as_of")]
+	date: NaiveDate,
+	rates: BTreeMap<String, serde_json::Number>,
+}
+
+pub fn parse_json_rates(json: &str) -> Result<RateTable, CurrencyError> {
+	let response: JsonRates = serde_json::from_str(json).map_err(CurrencyError::Parse)?;
+	let mut table = RateTable::new(parse_currency_code(&response.base)?, response.date);
+	for (code, rate) in response.rates {
+		// Skip Codes Like BTC That Aren't ISO 4217
+		let Ok(currency) = parse_currency_code(&code) else {
+			continue
+		};
+		let rate = parse_amount(&rate.to_string()).map_err(|_| CurrencyError::InvalidRate(currency, rate.to_string()))?;
+		table.set_rate(currency, rate)?;
+	}
+	Ok(table)
+}
+
+// Fetched Rates as Kept in the Data Directory
+#[derive(Serialize, Deserialize)]
+struct CachedRates {
+	fetched: DateTime<Utc>,
+	source: String,
+	table: RateFile,
+}
+
+// Where the Rates Handed Back by a Refresh Came From
+#[derive(Debug)]
+pub enum RateRefresh {
+	// Cached Rates Still Inside the TTL
+	Cached(RateTable, DateTime<Utc>),
+	Fetched(RateTable),
+	// The Fetch Failed so Older Cached Rates Are Used
+	Stale(RateTable, DateTime<Utc>, CurrencyError),
+}
+
+impl RateRefresh {
+	pub fn table(&self) -> &RateTable {
+		match self {
+			RateRefresh::Cached(table, _) | RateRefresh::Fetched(table) | RateRefresh::Stale(table, _, _) => table,
+		}
+	}
+}
+
+pub struct RateCache {
+	path: PathBuf,
+	ttl: Duration,
+}
+
+impl RateCache {
+	pub fn new<P: Into<PathBuf>>(path: P, ttl: Duration) -> Self {
+		RateCache { path: path.into(), ttl }
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	// A Missing or Unreadable Cache Just Means Fetching Again
+	fn load(&self) -> Option<(RateTable, DateTime<Utc>)> {
+		let json = fs::read_to_string(&self.path).ok()?;
+		let cached: CachedRates = serde_json::from_str(&json).ok()?;
+		let table = RateTable::from_file(cached.table).ok()?;
+		Some((table, cached.fetched))
+	}
+
+	fn save(&self, table: &RateTable, source: &str, fetched: DateTime<Utc>) -> Result<(), CurrencyError> {
+		let cached = CachedRates {
+			fetched,
+			source: source.to_string(),
+			table: table.to_file(),
+		};
+		let json = serde_json::to_vec_pretty(&cached).map_err(CurrencyError::Parse)?;
+		write_atomic(&self.path, &json).map_err(|error| CurrencyError::Io(self.path.clone(), error))
+	}
+
+	// Use Cached Rates Until They Expire, Then Ask the Provider. When
+	// the Provider Can't be Reached, Older Cached Rates Are Still Used
+	pub fn refresh(&self, provider: &dyn RateProvider, now: DateTime<Utc>, force: bool) -> Result<RateRefresh, CurrencyError> {
+		let cached = self.load();
+		if let Some((table, fetched)) = &cached {
+			if !force && now - *fetched < self.ttl {
+				return Ok(RateRefresh::Cached(table.clone(), *fetched))
+			}
+		}
+		match provider.fetch() {
+			Ok(table) => {
+				self.save(&table, &provider.name(), now)?;
+				Ok(RateRefresh::Fetched(table))
+			},
+			Err(error) => match cached {
+				Some((table, fetched)) => Ok(RateRefresh::Stale(table, fetched, error)),
+				None => Err(error),
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::io::{Read, Write};
+	use std::net::TcpListener;
+	use std::thread;
+
+	const ECB_FIXTURE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gesmes:Envelope xmlns:gesmes="http://www.gesmes.org/xml/2002-08-01" xmlns="http://www.ecb.int/vocabulary/2002-08-01/eurofxref">
+	<gesmes:subject>Reference rates</gesmes:subject>
+	<Cube>
+		<Cube time='2024-03-08'>
+			<Cube currency='USD' rate='1.0939'/>
+			<Cube currency='JPY' rate='160.32'/>
+			<Cube currency='GBP' rate='0.85040'/>
+			<Cube currency='BTC' rate='0.000016'/>
+		</Cube>
+	</Cube>
+</gesmes:Envelope>"#;
+
+	// Serve a Fixed Body to the Given Number of Requests
+	fn mock_server(body: &'static str, requests: usize) -> String {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let url = format!("http://{}/eurofxref-daily.xml", listener.local_addr().unwrap());
+		thread::spawn(move || {
+			for stream in listener.incoming().take(requests) {
+				let mut stream = stream.unwrap();
+				let mut request = [0; 1024];
+				let _ = stream.read(&mut request);
+				let response = format!(
+					"HTTP/1.1 200 OK\r\nContent-Type: text/xml\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+					body.len(),
+					body,
+				);
+				stream.write_all(response.as_bytes()).unwrap();
+			}
+		});
+		url
+	}
+
+	fn code(code: &str) -> iso_currency::Currency {
+		parse_currency_code(code).unwrap()
+	}
+
+	#[test]
+	fn can_parse_rate_feeds() {
+		let table = parse_ecb_xml(ECB_FIXTURE).unwrap();
+		assert_eq!(code("EUR"), table.base);
+		assert_eq!("2024-03-08", table.as_of.to_string());
+		assert_eq!(parse_amount("1.0939").unwrap(), table.rate(code("EUR"), code("USD")).unwrap());
+		assert_eq!(4, table.currencies().len());
+		assert!(parse_ecb_xml("<Cube></Cube>").is_err());
+
+		let json = r#"{"amount":1.0,"base":"USD","date":"2024-03-08","rates":{"EUR":0.91,"BTC":0.00001}}"#;
+		let table = parse_json_rates(json).unwrap();
+		assert_eq!(vec![code("EUR"), code("USD")], table.currencies());
+	}
+
+	#[test]
+	fn can_cache_and_fall_back_to_cached_rates() {
+		let dir = tempfile::tempdir().unwrap();
+		let cache = RateCache::new(dir.path().join("rates.cache"), Duration::hours(12));
+		let provider = EcbXmlProvider::new(&mock_server(ECB_FIXTURE, 1));
+		let now = Utc::now();
+
+		let refresh = cache.refresh(&provider, now, false).unwrap();
+		assert!(matches!(refresh, RateRefresh::Fetched(_)));
+		let fetched = refresh.table().clone();
+
+		// Inside the TTL the Server Isn't Asked Again
+		let refresh = cache.refresh(&provider, now + Duration::hours(1), false).unwrap();
+		assert!(matches!(refresh, RateRefresh::Cached(_, _)));
+		assert_eq!(&fetched, refresh.table());
+
+		// Once Expired, a Server That Has Gone Away Means Stale Rates
+		let refresh = cache.refresh(&provider, now + Duration::hours(13), false).unwrap();
+		assert!(matches!(refresh, RateRefresh::Stale(_, _, CurrencyError::Network(_, _))));
+		assert_eq!(&fetched, refresh.table());
+
+		let empty = RateCache::new(dir.path().join("missing.cache"), Duration::hours(12));
+		assert!(empty.refresh(&provider, now, false).is_err());
+	}
+}
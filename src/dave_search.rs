@@ -0,0 +1,396 @@
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use file_format::{FileFormat, Kind};
+use glob::{MatchOptions, Pattern};
+use rayon::prelude::*;
+
+// Files Listing Paths to Leave Out, Checked in Every Directory Walked
+pub const IGNORE_FILES: [&str; 3] = [".gitignore", ".ignore", ".daveignore"];
+
+// How Much of a File Is Sniffed to Decide if It's Binary
+const SNIFF_LENGTH: u64 = 8192;
+
+// Names Accepted by '--type' and the Extensions Each Covers
+pub const FILE_TYPES: [(&str, &[&str]); 17] = [
+	("c", &["c", "h"]),
+	("cpp", &["cc", "cpp", "cxx", "h", "hh", "hpp"]),
+	("css", &["css"]),
+	("csv", &["csv", "tsv"]),
+	("go", &["go"]),
+	("html", &["htm", "html"]),
+	("java", &["java"]),
+	("js", &["cjs", "js", "jsx", "mjs"]),
+	("json", &["json"]),
+	("md", &["markdown", "md"]),
+	("py", &["py", "pyi"]),
+	("rust", &["rs"]),
+	("sh", &["bash", "sh", "zsh"]),
+	("toml", &["toml"]),
+	("ts", &["ts", "tsx"]),
+	("txt", &["txt"]),
+	("yaml", &["yaml", "yml"]),
+];
+
+#[derive(Debug)]
+pub enum SearchError {
+	UnknownType(String),
+	BadGlob(String, glob::PatternError),
+}
+
+impl fmt::Display for SearchError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			SearchError::UnknownType(name) => {
+				let known: Vec<&str> = FILE_TYPES.iter().map(|(name, _)| *name).collect();
+				write!(f, "Unknown File Type '{}'. Choose From: {}", name, known.join(", "))
+			},
+			SearchError::BadGlob(glob, error) => write!(f, "Invalid Glob '{}': {}", glob, error),
+		}
+	}
+}
+
+impl std::error::Error for SearchError {}
+
+// Which Files a Search Looks At. Paths Named Directly Are Always
+// Searched; These Only Apply to What's Found Walking Directories
+#[derive(Clone, Debug, Default)]
+pub struct SearchOptions {
+	pub hidden: bool,
+	pub no_ignore: bool,
+	pub binary: bool,
+	pub types: Vec<String>,
+	pub globs: Vec<String>,
+}
+
+// What Came of a Search, in the Same Order Every Time: Directory
+// Entries Sorted by Name, and Paths in the Order They Were Given
+#[derive(Debug, Default)]
+pub struct SearchResults<T> {
+	pub files: Vec<(PathBuf, T)>,
+	pub binaries: Vec<PathBuf>,
+	pub errors: Vec<(PathBuf, io::Error)>,
+}
+
+// One Line of an Ignore File. Later Rules Win, so a '!' Line Can
+// Bring Back Something an Earlier Line Left Out
+#[derive(Clone, Debug)]
+struct IgnoreRule {
+	base: PathBuf,
+	pattern: Pattern,
+	negated: bool,
+	directory_only: bool,
+	anchored: bool,
+}
+
+impl IgnoreRule {
+	fn parse(base: &Path, line: &str) -> Option<IgnoreRule> {
+		let line = line.trim_end();
+		if line.is_empty() || line.starts_with('#') {
+			return None
+		}
+		let (negated, line) = match line.strip_prefix('!') {
+			Some(rest) => (true, rest),
+			None => (false, line.strip_prefix('\\').unwrap_or(line)),
+		};
+		let (directory_only, line) = match line.strip_suffix('/') {
+			Some(rest) => (true, rest),
+			None => (false, line),
+		};
+		// A Slash Anywhere but the End Ties the Rule to This Directory
+		let anchored = line.contains('/');
+		let line = line.strip_prefix('/').unwrap_or(line);
+		Some(IgnoreRule {
+			base: base.to_path_buf(),
+			pattern: Pattern::new(line).ok()?,
+			negated,
+			directory_only,
+			anchored,
+		})
+	}
+
+	fn matches(&self, path: &Path, is_dir: bool) -> bool {
+		if self.directory_only && !is_dir {
+			return false
+		}
+		let Ok(relative) = path.strip_prefix(&self.base) else { return false };
+		if self.anchored {
+			self.pattern.matches_path_with(relative, path_options())
+		} else {
+			path.file_name().is_some_and(|name| self.pattern.matches_with(&name.to_string_lossy(), path_options()))
+		}
+	}
+}
+
+fn path_options() -> MatchOptions {
+	MatchOptions { require_literal_separator: true, ..MatchOptions::new() }
+}
+
+// The Ignore Rules in Effect for a Directory: Its Own Plus Its Parents'
+#[derive(Clone, Debug, Default)]
+struct IgnoreRules {
+	rules: Arc<Vec<IgnoreRule>>,
+}
+
+impl IgnoreRules {
+	fn for_directory(&self, dir: &Path) -> IgnoreRules {
+		let mut added = Vec::new();
+		for name in IGNORE_FILES {
+			if let Ok(contents) = fs::read_to_string(dir.join(name)) {
+				added.extend(contents.lines().filter_map(|line| IgnoreRule::parse(dir, line)));
+			}
+		}
+		if added.is_empty() {
+			return self.clone()
+		}
+		let mut rules: Vec<IgnoreRule> = Vec::with_capacity(self.rules.len() + added.len());
+		rules.extend(self.rules.iter().cloned());
+		rules.extend(added);
+		IgnoreRules { rules: Arc::new(rules) }
+	}
+
+	fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+		self.rules.iter().rev().find(|rule| rule.matches(path, is_dir)).is_some_and(|rule| !rule.negated)
+	}
+}
+
+// The '--type' and '--glob' Filters, Checked Against Files Found Walking
+#[derive(Debug, Default)]
+struct FileFilter {
+	extensions: Vec<&'static str>,
+	include: Vec<Pattern>,
+	exclude: Vec<Pattern>,
+}
+
+impl FileFilter {
+	fn new(options: &SearchOptions) -> Result<FileFilter, SearchError> {
+		let mut filter = FileFilter::default();
+		for name in &options.types {
+			let (_, extensions) = FILE_TYPES
+				.iter()
+				.find(|(known, _)| known.eq_ignore_ascii_case(name))
+				.ok_or_else(|| SearchError::UnknownType(name.to_string()))?;
+			filter.extensions.extend(extensions.iter());
+		}
+		for glob in &options.globs {
+			let (negated, text) = match glob.strip_prefix('!') {
+				Some(rest) => (true, rest),
+				None => (false, glob.as_str()),
+			};
+			let pattern = Pattern::new(text).map_err(|error| SearchError::BadGlob(glob.to_string(), error))?;
+			if negated { filter.exclude.push(pattern) } else { filter.include.push(pattern) }
+		}
+		Ok(filter)
+	}
+
+	// Globs Without a Slash Match the File Name, Others the Path Under the Root
+	fn allows(&self, root: &Path, path: &Path) -> bool {
+		let extension = path.extension().map(|extension| extension.to_string_lossy().to_lowercase());
+		if !self.extensions.is_empty() && !extension.is_some_and(|extension| self.extensions.contains(&extension.as_str())) {
+			return false
+		}
+		let relative = path.strip_prefix(root).unwrap_or(path);
+		let name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+		let matches = |pattern: &Pattern| {
+			if pattern.as_str().contains('/') {
+				pattern.matches_path_with(relative, path_options())
+			} else {
+				pattern.matches_with(&name, path_options())
+			}
+		};
+		(self.include.is_empty() || self.include.iter().any(matches)) && !self.exclude.iter().any(matches)
+	}
+}
+
+enum Walked {
+	File(PathBuf),
+	Error(PathBuf, io::Error),
+}
+
+struct Walker<'a> {
+	options: &'a SearchOptions,
+	filter: FileFilter,
+}
+
+impl Walker<'_> {
+	// Entries Are Sorted Then Walked in Parallel; Collecting Keeps That Order.
+	// Symlinks Are Never Followed, so Nothing Is Walked Twice or Forever
+	fn walk(&self, root: &Path, dir: &Path, parent_rules: &IgnoreRules) -> Vec<Walked> {
+		let rules = if self.options.no_ignore { parent_rules.clone() } else { parent_rules.for_directory(dir) };
+		let read_dir = match fs::read_dir(dir) {
+			Ok(read_dir) => read_dir,
+			Err(error) => return vec![Walked::Error(dir.to_path_buf(), error)],
+		};
+		let mut walked = Vec::new();
+		let mut entries = Vec::new();
+		for entry in read_dir {
+			match entry.and_then(|entry| Ok((entry.path(), entry.file_type()?))) {
+				Ok(entry) => entries.push(entry),
+				Err(error) => walked.push(Walked::Error(dir.to_path_buf(), error)),
+			}
+		}
+		entries.sort_by(|a, b| a.0.file_name().cmp(&b.0.file_name()));
+
+		let found: Vec<Vec<Walked>> = entries
+			.into_par_iter()
+			.map(|(path, file_type)| {
+				let hidden = path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.'));
+				if file_type.is_symlink() || (hidden && !self.options.hidden) || rules.is_ignored(&path, file_type.is_dir()) {
+					Vec::new()
+				} else if file_type.is_dir() {
+					self.walk(root, &path, &rules)
+				} else if file_type.is_file() && self.filter.allows(root, &path) {
+					vec![Walked::File(path)]
+				} else {
+					Vec::new()
+				}
+			})
+			.collect();
+		walked.extend(found.into_iter().flatten());
+		walked
+	}
+}
+
+// NUL Bytes Near the Start Give Most Binaries Away. Media and Archives
+// That Happen Not to Have Any Are Caught by Their Magic Numbers, as Long
+// as the Bytes Aren't Also Valid Text (Which Rules Out SVG and the Like)
+pub fn is_binary(head: &[u8]) -> bool {
+	if head.contains(&0) {
+		return true
+	}
+	let is_text = match std::str::from_utf8(head) {
+		Ok(_) => true,
+		// Only Cut Off Mid-Character by the Sniff Length
+		Err(error) => error.error_len().is_none(),
+	};
+	let kind = FileFormat::from_bytes(head).kind();
+	!is_text && matches!(
+		kind,
+		Kind::Archive | Kind::Audio | Kind::Compressed | Kind::Executable | Kind::Font | Kind::Image | Kind::Video,
+	)
+}
+
+// Every File to Search: Files Named Directly, Then Everything Found Under
+// Each Directory That Isn't Hidden, Ignored or Filtered Out
+pub fn search_files(paths: &[PathBuf], options: &SearchOptions) -> Result<SearchResults<()>, SearchError> {
+	let walker = Walker { options, filter: FileFilter::new(options)? };
+	let walked: Vec<Vec<Walked>> = paths
+		.par_iter()
+		.map(|path| match fs::metadata(path) {
+			Ok(metadata) if metadata.is_dir() => walker.walk(path, path, &IgnoreRules::default()),
+			Ok(_) => vec![Walked::File(path.clone())],
+			Err(error) => vec![Walked::Error(path.clone(), error)],
+		})
+		.collect();
+
+	let mut results = SearchResults::default();
+	for walked in walked.into_iter().flatten() {
+		match walked {
+			Walked::File(path) => results.files.push((path, ())),
+			Walked::Error(path, error) => results.errors.push((path, error)),
+		}
+	}
+	Ok(results)
+}
+
+fn read_text(path: &Path, binary: bool) -> io::Result<Option<String>> {
+	let mut file = File::open(path)?;
+	let mut bytes = Vec::new();
+	file.by_ref().take(SNIFF_LENGTH).read_to_end(&mut bytes)?;
+	if !binary && is_binary(&bytes) {
+		return Ok(None)
+	}
+	file.read_to_end(&mut bytes)?;
+	Ok(Some(match String::from_utf8(bytes) {
+		Ok(text) => text,
+		Err(error) => String::from_utf8_lossy(error.as_bytes()).into_owned(),
+	}))
+}
+
+// Walk the Paths and Run search_file Over Each Text File in Parallel.
+// Read-Only Files Are Searched Like Any Other
+pub fn search<T, F>(paths: &[PathBuf], options: &SearchOptions, search_file: F) -> Result<SearchResults<T>, SearchError>
+where
+	T: Send,
+	F: Fn(&Path, &str) -> T + Sync,
+{
+	let found = search_files(paths, options)?;
+	let read: Vec<(PathBuf, io::Result<Option<T>>)> = found
+		.files
+		.into_par_iter()
+		.map(|(path, _)| {
+			let result = read_text(&path, options.binary).map(|text| text.map(|text| search_file(&path, &text)));
+			(path, result)
+		})
+		.collect();
+
+	let mut results = SearchResults { files: Vec::new(), binaries: Vec::new(), errors: found.errors };
+	for (path, result) in read {
+		match result {
+			Ok(Some(value)) => results.files.push((path, value)),
+			Ok(None) => results.binaries.push(path),
+			Err(error) => results.errors.push((path, error)),
+		}
+	}
+	Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn names(root: &Path, results: &SearchResults<()>) -> Vec<String> {
+		results.files.iter().map(|(path, _)| path.strip_prefix(root).unwrap().display().to_string()).collect()
+	}
+
+	#[test]
+	fn can_walk_with_ignore_files_and_filters() {
+		let dir = tempfile::tempdir().unwrap();
+		let root = dir.path();
+		for name in ["a.rs", "b.txt", "notes.log", ".hidden.rs", "target/out.rs", "src/keep.log", "src/deep/c.rs"] {
+			let path = root.join(name);
+			fs::create_dir_all(path.parent().unwrap()).unwrap();
+			fs::write(path, "text\n").unwrap();
+		}
+		fs::write(root.join(".gitignore"), "# Build Output\ntarget/\n*.log\n").unwrap();
+		fs::write(root.join("src/.daveignore"), "!keep.log\n/deep\n").unwrap();
+
+		let options = SearchOptions::default();
+		let paths = vec![root.to_path_buf()];
+		let found = search_files(&paths, &options).unwrap();
+		assert_eq!(vec!["a.rs", "b.txt", "src/keep.log"], names(root, &found));
+
+		let everything = SearchOptions { hidden: true, no_ignore: true, ..SearchOptions::default() };
+		assert_eq!(9, search_files(&paths, &everything).unwrap().files.len());
+
+		let rust = SearchOptions { types: vec!["rust".to_string()], no_ignore: true, ..SearchOptions::default() };
+		assert_eq!(vec!["a.rs", "src/deep/c.rs", "target/out.rs"], names(root, &search_files(&paths, &rust).unwrap()));
+		let globbed = SearchOptions { globs: vec!["*.rs".to_string(), "!c.rs".to_string()], ..SearchOptions::default() };
+		assert_eq!(vec!["a.rs"], names(root, &search_files(&paths, &globbed).unwrap()));
+		let unknown = SearchOptions { types: vec!["cobol".to_string()], ..SearchOptions::default() };
+		assert!(search_files(&paths, &unknown).is_err());
+	}
+
+	#[test]
+	fn can_skip_binaries_and_search_read_only_files() {
+		let dir = tempfile::tempdir().unwrap();
+		fs::write(dir.path().join("image.bin"), b"GIF89a\x01\x00\x00needle").unwrap();
+		fs::write(dir.path().join("text.txt"), "a needle\n").unwrap();
+		let read_only = dir.path().join("read_only.txt");
+		fs::write(&read_only, "needle again\n").unwrap();
+		let mut permissions = fs::metadata(&read_only).unwrap().permissions();
+		permissions.set_readonly(true);
+		fs::set_permissions(&read_only, permissions).unwrap();
+		#[cfg(unix)]
+		std::os::unix::fs::symlink(dir.path().join("text.txt"), dir.path().join("link.txt")).unwrap();
+
+		let results = search(&[dir.path().to_path_buf()], &SearchOptions::default(), |_, text| text.contains("needle")).unwrap();
+		let searched: Vec<_> = results.files.iter().map(|(path, found)| (path.file_name().unwrap().to_owned(), *found)).collect();
+		assert_eq!(vec![("read_only.txt".into(), true), ("text.txt".into(), true)], searched);
+		assert_eq!(vec![dir.path().join("image.bin")], results.binaries);
+		assert!(is_binary(&[0xFF, 0xD8, 0xFF, 0xE0, 0x10, 0x4A, 0x46, 0x49, 0x46, 0xC3]));
+		assert!(!is_binary("<svg>café</svg>".as_bytes()));
+	}
+}
@@ -0,0 +1,375 @@
+use crate::config::{
+	DaveConfig,
+	ASSETS_DIR,
+	QUIZ_DIR,
+	RATES_FILE,
+	RATE_HISTORY_FILE,
+	ROMS_DIR,
+};
+use crate::dave_vault::SessionCache;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Error, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+// Every Path Setup Created, One per Line, Kept in the Root. Uninstall
+// Only Ever Removes What's Listed Here
+pub const MANIFEST_FILE: &str = "install_manifest";
+
+// Quizzes Offered by 'dave quiz'
+const QUIZ_FILES: [&str; 5] = [
+	"animal_quiz.tsv",
+	"gen_av_quiz.tsv",
+	"mil_av_quiz.tsv",
+	"strek_quiz.tsv",
+	"swars_quiz.tsv",
+];
+
+// ROMs Shipped for 'dave chip8'
+const ROM_FILES: [&str; 4] = ["PONG", "SNAKE", "SPACE_INVADERS", "TETRIS"];
+
+// Assets the Games and Demos Load by Name
+const ASSET_FILES: [&str; 22] = [
+	"audio/collision.ogg",
+	"audio/crash.ogg",
+	"audio/revving.ogg",
+	"audio/slopes.ogg",
+	"audio/stalker.ogg",
+	"audio/tires.ogg",
+	"cube_maps/Ryfjallet_cubemap.png",
+	"env_maps/pisa_diffuse_rgb9e5_zstd.ktx2",
+	"env_maps/pisa_specular_rgb9e5_zstd.ktx2",
+	"fonts/FiraMono-Medium.ttf",
+	"fonts/FiraSans-Bold.ttf",
+	"models/dave_cars/hatchbackSports.glb",
+	"models/dave_cars/police.glb",
+	"models/dave_cars/sedan.glb",
+	"models/dave_cars/tractor.glb",
+	"models/dave_game/alien.glb",
+	"models/dave_game/cakeBirthday.glb",
+	"models/dave_game/tile.glb",
+	"models/fox/Fox.glb",
+	"scenes/Mountains.gltf",
+	"textures/bevy_logo_light.png",
+	"textures/frog.png",
+];
+
+// One Thing Setup Will Do. Anything Already in Place Is Left Out
+#[derive(Clone, Debug, PartialEq)]
+pub enum SetupStep {
+	CreateDirectory(PathBuf),
+	CopyFile(PathBuf, PathBuf),
+	WriteConfig(PathBuf),
+}
+
+impl fmt::Display for SetupStep {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			SetupStep::CreateDirectory(path) => write!(f, "create {}", path.display()),
+			SetupStep::CopyFile(from, to) => write!(f, "copy   {} -> {}", from.display(), to.display()),
+			SetupStep::WriteConfig(path) => write!(f, "write  {}", path.display()),
+		}
+	}
+}
+
+// Copy Every File Under a Source Directory That the Target Doesn't Have Yet
+fn plan_copy(from: &Path, to: &Path, steps: &mut Vec<SetupStep>) -> io::Result<()> {
+	if from.is_file() {
+		if !to.exists() {
+			steps.push(SetupStep::CopyFile(from.to_path_buf(), to.to_path_buf()));
+		}
+		return Ok(())
+	}
+	if !from.is_dir() {
+		return Ok(())
+	}
+	for entry in WalkDir::new(from).sort_by_file_name() {
+		let entry = entry?;
+		let target = to.join(entry.path().strip_prefix(from).map_err(Error::other)?);
+		if entry.file_type().is_dir() && !target.exists() {
+			steps.push(SetupStep::CreateDirectory(target));
+		} else if entry.file_type().is_file() && !target.exists() {
+			steps.push(SetupStep::CopyFile(entry.into_path(), target));
+		}
+	}
+	Ok(())
+}
+
+// Work Out What Setup Needs to Do for This Config. The Source Is a
+// Directory Laid Out Like dave_conf, Whose etc/ Files Get Copied In
+pub fn plan_setup(config: &DaveConfig, source: Option<&Path>) -> io::Result<Vec<SetupStep>> {
+	let mut steps = Vec::new();
+	let root = config.root_path();
+	let directories = [
+		root.clone(),
+		root.join("etc"),
+		root.join("var"),
+		config.quiz_path(),
+		config.roms_path(),
+		config.assets_path(),
+		config.machines_path(),
+	];
+	for directory in directories {
+		if !directory.exists() && !steps.contains(&SetupStep::CreateDirectory(directory.clone())) {
+			steps.push(SetupStep::CreateDirectory(directory));
+		}
+	}
+
+	// Copying an Installation Onto Itself Has Nothing to Do
+	let same_place = |source: &Path| match (fs::canonicalize(source), fs::canonicalize(&root)) {
+		(Ok(source), Ok(root)) => source == root,
+		_ => false,
+	};
+	if let Some(source) = source.filter(|source| !same_place(source)) {
+		let etc = source.join("etc");
+		plan_copy(&etc.join(RATES_FILE), &config.rates_path(), &mut steps)?;
+		plan_copy(&etc.join(RATE_HISTORY_FILE), &config.rate_history_path(), &mut steps)?;
+		plan_copy(&etc.join(QUIZ_DIR), &config.quiz_path(), &mut steps)?;
+		plan_copy(&etc.join(ROMS_DIR), &config.roms_path(), &mut steps)?;
+		plan_copy(&etc.join(ASSETS_DIR), &config.assets_path(), &mut steps)?;
+	}
+
+	if !config.config_path().exists() {
+		steps.push(SetupStep::WriteConfig(config.config_path()));
+	}
+	Ok(steps)
+}
+
+// Where the Manifest of an Installation Lives
+pub fn manifest_path(config: &DaveConfig) -> PathBuf {
+	config.root_path().join(MANIFEST_FILE)
+}
+
+// Paths Listed in an Installation's Manifest, in the Order Created
+pub fn read_manifest(config: &DaveConfig) -> io::Result<Vec<PathBuf>> {
+	let contents = fs::read_to_string(manifest_path(config))?;
+	Ok(contents.lines().filter(|line| !line.trim().is_empty()).map(PathBuf::from).collect())
+}
+
+fn record_created(config: &DaveConfig, created: &[PathBuf]) -> io::Result<()> {
+	if created.is_empty() {
+		return Ok(())
+	}
+	let mut manifest = OpenOptions::new().create(true).append(true).open(manifest_path(config))?;
+	for path in created {
+		writeln!(manifest, "{}", path.display())?;
+	}
+	Ok(())
+}
+
+// Carry Out a Plan From plan_setup. Nothing That Exists Is Overwritten,
+// and Everything Made Is Added to the Manifest, Even if a Later Step Fails
+pub fn apply_setup(config: &DaveConfig, steps: &[SetupStep]) -> io::Result<()> {
+	let mut created = Vec::new();
+	let result = apply_steps(config, steps, &mut created);
+	if config.root_path().is_dir() {
+		record_created(config, &created)?;
+	}
+	result
+}
+
+fn create_directories(path: &Path, created: &mut Vec<PathBuf>) -> io::Result<()> {
+	let missing: Vec<&Path> = path.ancestors().take_while(|ancestor| !ancestor.exists()).collect();
+	fs::create_dir_all(path)?;
+	created.extend(missing.into_iter().rev().map(Path::to_path_buf));
+	Ok(())
+}
+
+fn apply_steps(config: &DaveConfig, steps: &[SetupStep], created: &mut Vec<PathBuf>) -> io::Result<()> {
+	for step in steps {
+		match step {
+			SetupStep::CreateDirectory(path) => create_directories(path, created)?,
+			SetupStep::CopyFile(from, to) => {
+				if let Some(parent) = to.parent() {
+					create_directories(parent, created)?;
+				}
+				if !to.exists() {
+					fs::copy(from, to)?;
+					created.push(to.clone());
+				}
+			},
+			SetupStep::WriteConfig(path) => {
+				if let Some(parent) = path.parent() {
+					create_directories(parent, created)?;
+				}
+				// Just Enough to Find the Installation. Every Other Path
+				// Follows root_path, so Moving the Root Moves Them Too
+				let settings = ["root_path", "hash_type"]
+					.into_iter()
+					.map(|key| config.get(key).map(|value| (key, Some(value))))
+					.collect::<Result<Vec<_>, _>>()
+					.map_err(|error| Error::other(error.to_string()))?;
+				DaveConfig::write_settings(path, &settings).map_err(|error| Error::other(error.to_string()))?;
+				created.push(path.clone());
+			},
+		}
+	}
+	Ok(())
+}
+
+// Data Files That Are Missing From an Installation
+pub fn verify_install(config: &DaveConfig) -> Vec<PathBuf> {
+	let quiz_path = config.quiz_path();
+	let roms_path = config.roms_path();
+	let assets_path = config.assets_path();
+	let required = [config.rates_path()]
+		.into_iter()
+		.chain(QUIZ_FILES.iter().map(|name| quiz_path.join(name)))
+		.chain(ROM_FILES.iter().map(|name| roms_path.join(name)))
+		.chain(ASSET_FILES.iter().map(|name| assets_path.join(name)));
+	required.filter(|path| !path.is_file()).collect()
+}
+
+// One Thing Uninstall Will Do
+#[derive(Clone, Debug, PartialEq)]
+pub enum UninstallStep {
+	RemoveFile(PathBuf),
+	// Left in Place if Anything Not Installed by Setup Is Still In It
+	RemoveDirectory(PathBuf),
+	// Only Used for Data With --purge
+	RemoveTree(PathBuf),
+}
+
+impl fmt::Display for UninstallStep {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			UninstallStep::RemoveFile(path) => write!(f, "remove {}", path.display()),
+			UninstallStep::RemoveDirectory(path) => write!(f, "remove {} (if empty)", path.display()),
+			UninstallStep::RemoveTree(path) => write!(f, "purge  {} and everything in it", path.display()),
+		}
+	}
+}
+
+// Directories Holding the Budget, Notes, Database and Vault Key
+fn data_directories(config: &DaveConfig) -> [PathBuf; 2] {
+	[config.root_path().join("var"), config.machines_path()]
+}
+
+// Everything Uninstalling Would Remove, in the Order It's Removed. Only
+// Paths in the Manifest Are Touched, so an Installation Setup Didn't Make
+// Is Refused. Data Directories Are Kept Unless purge Is Set
+pub fn uninstall_steps(config: &DaveConfig, purge: bool) -> io::Result<Vec<UninstallStep>> {
+	let manifest = read_manifest(config).map_err(|_| {
+		Error::new(
+			ErrorKind::NotFound,
+			format!(
+				"{} Has No {}, so It Wasn't Installed by 'dave setup' and Won't be Removed",
+				config.root_path().display(),
+				MANIFEST_FILE,
+			),
+		)
+	})?;
+	let data = data_directories(config);
+	let mut steps = Vec::new();
+	let sessions = SessionCache::for_config(config).map(|session| session.path().to_path_buf());
+	for session in sessions.into_iter().chain([SessionCache::legacy_path(config)]) {
+		if session.is_file() {
+			steps.push(UninstallStep::RemoveFile(session));
+		}
+	}
+	if purge {
+		// A Data Directory Inside Another Goes With It
+		let outermost = |directory: &&PathBuf| !data.iter().any(|other| other != *directory && directory.starts_with(other));
+		for directory in data.iter().filter(outermost).filter(|directory| manifest.contains(directory) && directory.is_dir()) {
+			steps.push(UninstallStep::RemoveTree(directory.clone()));
+		}
+	}
+
+	// Files, Then the Manifest, Then Directories Deepest First so Each Is
+	// Empty by the Time It's Reached
+	let mut installed: Vec<&PathBuf> = manifest.iter().filter(|path| !data.iter().any(|directory| path.starts_with(directory))).collect();
+	installed.sort_by(|a, b| b.components().count().cmp(&a.components().count()).then(a.cmp(b)));
+	installed.dedup();
+	let mut directories = Vec::new();
+	for path in installed {
+		match fs::symlink_metadata(path) {
+			Ok(metadata) if metadata.is_dir() => directories.push(UninstallStep::RemoveDirectory(path.clone())),
+			Ok(_) => steps.push(UninstallStep::RemoveFile(path.clone())),
+			Err(_) => {},
+		}
+	}
+	steps.push(UninstallStep::RemoveFile(manifest_path(config)));
+	steps.extend(directories);
+	Ok(steps)
+}
+
+// Carry Out What uninstall_steps Listed. Returns Directories That Were
+// Kept Because Something Else Is Still In Them
+pub fn apply_uninstall(steps: &[UninstallStep]) -> io::Result<Vec<PathBuf>> {
+	let mut kept = Vec::new();
+	for step in steps {
+		match step {
+			UninstallStep::RemoveFile(path) => match fs::remove_file(path) {
+				Err(error) if error.kind() != ErrorKind::NotFound => return Err(error),
+				_ => {},
+			},
+			UninstallStep::RemoveTree(path) => fs::remove_dir_all(path)?,
+			UninstallStep::RemoveDirectory(path) => {
+				if path.exists() && fs::remove_dir(path).is_err() {
+					kept.push(path.clone());
+				}
+			},
+		}
+	}
+	Ok(kept)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn can_setup_and_uninstall() {
+		let dir = tempfile::tempdir().unwrap();
+		let source = dir.path().join("dave_conf");
+		fs::create_dir_all(source.join("etc").join(QUIZ_DIR)).unwrap();
+		fs::write(source.join("etc").join(QUIZ_DIR).join("animal_quiz.tsv"), "Q\tA\n").unwrap();
+		fs::write(source.join("etc").join(RATES_FILE), "{}").unwrap();
+
+		let root = dir.path().join("install");
+		let mut config = DaveConfig::for_root(&root);
+		config.set_config_path(dir.path().join("config").join("dave.conf"));
+		let steps = plan_setup(&config, Some(&source)).unwrap();
+		assert!(steps.contains(&SetupStep::WriteConfig(config.config_path())));
+		apply_setup(&config, &steps).unwrap();
+		assert_eq!("Q\tA\n", fs::read_to_string(config.quiz_path().join("animal_quiz.tsv")).unwrap());
+		let (written, _) = DaveConfig::validate_file(&config.config_path()).unwrap();
+		assert_eq!(root, written.root_path());
+		assert_eq!(config.budget_path(), written.budget_path());
+		assert_eq!(None, DaveConfig::read_setting(&config.config_path(), "budget_file").unwrap());
+
+		// Running Again Changes Nothing, Even Files That Were Edited
+		fs::write(config.rates_path(), "{\"edited\": true}").unwrap();
+		assert!(plan_setup(&config, Some(&source)).unwrap().is_empty());
+		let missing = verify_install(&config);
+		assert!(missing.contains(&config.roms_path().join("TETRIS")));
+		assert!(!missing.contains(&config.quiz_path().join("animal_quiz.tsv")));
+
+		fs::write(config.budget_path(), "{}").unwrap();
+		fs::write(config.quiz_path().join("my_quiz.tsv"), "Mine\tYes\n").unwrap();
+		let steps = uninstall_steps(&config, false).unwrap();
+		assert!(steps.contains(&UninstallStep::RemoveFile(config.config_path())));
+		assert!(!steps.iter().any(|step| matches!(step, UninstallStep::RemoveTree(_))));
+		let kept = apply_uninstall(&steps).unwrap();
+		assert_eq!(vec![config.quiz_path(), root.join("etc"), root.clone()], kept);
+		assert!(config.budget_path().exists());
+		assert!(config.quiz_path().join("my_quiz.tsv").exists());
+		assert!(!config.quiz_path().join("animal_quiz.tsv").exists());
+		assert!(!dir.path().join("config").exists());
+		assert!(uninstall_steps(&config, true).is_err());
+
+		// Without a Manifest Nothing Is Touched, Even With etc/ and var/
+		let checkout = DaveConfig::for_root(&dir.path().join("checkout"));
+		fs::create_dir_all(checkout.root_path().join("etc")).unwrap();
+		fs::create_dir_all(checkout.root_path().join("var")).unwrap();
+		assert_eq!(ErrorKind::NotFound, uninstall_steps(&checkout, true).unwrap_err().kind());
+
+		// Purging Takes the Data Too
+		let fresh = DaveConfig::for_root(&dir.path().join("fresh"));
+		apply_setup(&fresh, &plan_setup(&fresh, Some(&source)).unwrap()).unwrap();
+		fs::write(fresh.budget_path(), "{}").unwrap();
+		assert!(apply_uninstall(&uninstall_steps(&fresh, true).unwrap()).unwrap().is_empty());
+		assert!(!fresh.root_path().exists());
+	}
+}
@@ -18,9 +18,9 @@ use bevy::{
 		texture::CompressedImageFormats,
 	},
 };
+use crate::dave_graphics::assets_dir;
 use crate::utils::*;
 
-const CUBEMAPS_DIR: &str = "./dave_conf/etc/daves_assets";
 const CUBEMAPS: &[(&str, CompressedImageFormats)] = &[
 	(
 		"cube_maps/Ryfjallet_cubemap.png",
@@ -87,7 +87,7 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
 				"FPS: ",
 				TextStyle {
 					font: asset_server.load(
-						CUBEMAPS_DIR.to_owned() + "/fonts/FiraSans-Bold.ttf",
+						assets_dir() + "/fonts/FiraSans-Bold.ttf",
 					),
 					font_size: 20.0,
 					color: Color::BLACK,
@@ -103,7 +103,7 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
 			} else {
 				TextStyle {
 					font: asset_server.load(
-						CUBEMAPS_DIR.to_owned() + "/fonts/FiraMono-Medium.ttf",
+						assets_dir() + "/fonts/FiraMono-Medium.ttf",
 					),
 					font_size: 20.0,
 					color: Color::GOLD,
@@ -113,7 +113,7 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
 				"\nFormat: ",
 				TextStyle {
 					font: asset_server.load(
-						CUBEMAPS_DIR.to_owned() + "/fonts/FiraMono-Medium.ttf",
+						assets_dir() + "/fonts/FiraMono-Medium.ttf",
 					),
 					font_size: 30.0,
 					color: Color::BLUE,
@@ -129,7 +129,7 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
 			} else {
 				TextStyle {
 					font: asset_server.load(
-						CUBEMAPS_DIR.to_owned() + "/fonts/FiraMono-Medium.ttf",
+						assets_dir() + "/fonts/FiraMono-Medium.ttf",
 					),
 					font_size: 25.0,
 					color: Color::RED,
@@ -269,6 +269,10 @@ impl Game {
 		Ok(())
 	}
 
+	pub fn score(&self) -> u16 {
+		self.score
+	}
+
 	fn calculate_interval(&self) -> Duration {
 		let speed = MAX_SPEED - self.speed;
 		Duration::from_millis(
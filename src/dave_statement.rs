@@ -0,0 +1,360 @@
+use chrono::NaiveDate;
+use crate::dave_budget::TransactionKind;
+use regex::{Regex, RegexBuilder};
+use std::error::Error;
+use std::io::Read;
+use std::path::Path;
+use std::str::FromStr;
+
+// Category Given to Imported Expenses No Rule Matches
+pub const UNCATEGORIZED: &str = "uncategorized";
+
+// Kinds of Bank Statement Dave Can Import
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatementFormat {
+	Csv,
+	Ofx,
+}
+
+impl FromStr for StatementFormat {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_lowercase().as_str() {
+			"csv" => Ok(StatementFormat::Csv),
+			"ofx" | "qfx" => Ok(StatementFormat::Ofx),
+			_ => Err(format!("'{}' is not a statement format. Use csv or ofx", s)),
+		}
+	}
+}
+
+impl StatementFormat {
+	// Guess the Format From a File's Extension
+	pub fn from_path(path: &Path) -> Result<Self, String> {
+		match path.extension().and_then(|extension| extension.to_str()) {
+			Some(extension) => StatementFormat::from_str(extension),
+			None => Err(format!("Can't tell the format of {}. Pass '--format csv' or '--format ofx'", path.display())),
+		}
+	}
+}
+
+// Which CSV Columns Hold Each Field. Columns Are Matched by Header
+// Name, Ignoring Case, or by Zero Based Position
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct CsvColumns {
+	pub date: String,
+	pub payee: String,
+	// Single Signed Column, Negative for Money Going Out
+	pub amount: Option<String>,
+	// Separate Columns Used by Banks That Split Money In and Out
+	pub debit: Option<String>,
+	pub credit: Option<String>,
+	pub memo: Option<String>,
+	pub date_format: String,
+	pub delimiter: char,
+}
+
+impl Default for CsvColumns {
+	fn default() -> Self {
+		CsvColumns {
+			date: "date".to_string(),
+			payee: "description".to_string(),
+			amount: Some("amount".to_string()),
+			debit: None,
+			credit: None,
+			memo: None,
+			date_format: "%Y-%m-%d".to_string(),
+			delimiter: ',',
+		}
+	}
+}
+
+impl CsvColumns {
+	// Apply a FIELD=COLUMN Override Given on the Command Line
+	pub fn set(&mut self, mapping: &str) -> Result<(), String> {
+		let (field, column) = match mapping.split_once('=') {
+			Some((field, column)) => (field.trim(), column.trim().to_string()),
+			None => return Err(format!("'{}' is not a column mapping. Use FIELD=COLUMN", mapping)),
+		};
+		match field {
+			"date" => self.date = column,
+			"payee" => self.payee = column,
+			"amount" => self.amount = Some(column),
+			"debit" => self.debit = Some(column),
+			"credit" => self.credit = Some(column),
+			"memo" => self.memo = Some(column),
+			"date_format" => self.date_format = column,
+			"delimiter" => match column.chars().next() {
+				Some(delimiter) if column.chars().count() == 1 => self.delimiter = delimiter,
+				_ => return Err(format!("'{}' is not a single character delimiter", column)),
+			},
+			_ => return Err(format!(
+				"'{}' is not a column. Use date, payee, amount, debit, credit, memo, date_format or delimiter",
+				field,
+			)),
+		}
+		Ok(())
+	}
+}
+
+// Files Payees Matching a Pattern Under a Category
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CategoryRule {
+	pub pattern: String,
+	pub category: String,
+}
+
+// Statement Import Settings Kept in the Config
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct ImportConfig {
+	pub csv: CsvColumns,
+	pub rules: Vec<CategoryRule>,
+}
+
+// One Line of a Bank Statement
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatementEntry {
+	pub date: NaiveDate,
+	pub kind: TransactionKind,
+	pub amount: f64,
+	pub payee: String,
+	pub memo: String,
+}
+
+impl StatementEntry {
+	fn new(date: NaiveDate, signed_amount: f64, payee: &str, memo: &str) -> Self {
+		let kind = if signed_amount < 0.0 { TransactionKind::Expense } else { TransactionKind::Income };
+		StatementEntry {
+			date,
+			kind,
+			amount: signed_amount.abs(),
+			payee: payee.trim().to_string(),
+			memo: memo.trim().to_string(),
+		}
+	}
+}
+
+// Compiled Payee Rules. The First Matching Rule Wins
+pub struct Categorizer {
+	rules: Vec<(Regex, String)>,
+}
+
+impl Categorizer {
+	pub fn new(rules: &[CategoryRule]) -> Result<Self, regex::Error> {
+		let rules = rules
+			.iter()
+			.map(|rule| {
+				let regex = RegexBuilder::new(&rule.pattern).case_insensitive(true).build()?;
+				Ok((regex, rule.category.clone()))
+			})
+			.collect::<Result<Vec<_>, regex::Error>>()?;
+		Ok(Categorizer { rules })
+	}
+
+	pub fn category(&self, entry: &StatementEntry) -> String {
+		match self.rules.iter().find(|(regex, _)| regex.is_match(&entry.payee)) {
+			Some((_, category)) => category.clone(),
+			None => match entry.kind {
+				TransactionKind::Income => "income".to_string(),
+				TransactionKind::Expense => UNCATEGORIZED.to_string(),
+			},
+		}
+	}
+}
+
+// Read Amounts the Way Banks Print Them: "$1,234.50", "-12.00" or "(12.00)"
+fn parse_amount(text: &str) -> Result<f64, String> {
+	let cleaned: String = text.chars().filter(|c| !matches!(c, '$' | ',' | ' ' | '+')).collect();
+	let (negative, digits) = match cleaned.strip_prefix('(').and_then(|rest| rest.strip_suffix(')')) {
+		Some(digits) => (true, digits),
+		None => (false, cleaned.as_str()),
+	};
+	match digits.parse::<f64>() {
+		Ok(amount) if negative => Ok(-amount),
+		Ok(amount) => Ok(amount),
+		Err(_) => Err(format!("'{}' is not an amount", text)),
+	}
+}
+
+pub fn parse_statement<R: Read>(
+	format: StatementFormat,
+	columns: &CsvColumns,
+	mut reader: R,
+) -> Result<Vec<StatementEntry>, Box<dyn Error>> {
+	match format {
+		StatementFormat::Csv => parse_csv_statement(columns, reader),
+		StatementFormat::Ofx => {
+			let mut text = String::new();
+			reader.read_to_string(&mut text)?;
+			parse_ofx_statement(&text)
+		},
+	}
+}
+
+pub fn parse_csv_statement<R: Read>(columns: &CsvColumns, reader: R) -> Result<Vec<StatementEntry>, Box<dyn Error>> {
+	if !columns.delimiter.is_ascii() {
+		return Err(format!("'{}' can't be used as a CSV delimiter", columns.delimiter).into())
+	}
+	let mut reader = csv::ReaderBuilder::new()
+		.delimiter(columns.delimiter as u8)
+		.flexible(true)
+		.trim(csv::Trim::All)
+		.from_reader(reader);
+	let headers = reader.headers()?.clone();
+
+	// Resolve Each Configured Column to a Position
+	let find = |column: &str| -> Result<usize, String> {
+		headers
+			.iter()
+			.position(|header| header.eq_ignore_ascii_case(column))
+			.or_else(|| column.parse::<usize>().ok())
+			.ok_or_else(|| format!("Statement has no '{}' column. Found: {}", column, headers.iter().collect::<Vec<_>>().join(", ")))
+	};
+	let optional = |column: &Option<String>| column.as_deref().map(find).transpose();
+	let date_column = find(&columns.date)?;
+	let payee_column = find(&columns.payee)?;
+	let memo_column = optional(&columns.memo)?;
+	let amount_column = optional(&columns.amount)?;
+	let debit_column = optional(&columns.debit)?;
+	let credit_column = optional(&columns.credit)?;
+	if amount_column.is_none() && debit_column.is_none() && credit_column.is_none() {
+		return Err("Map an amount column, or debit and credit columns, to import a CSV statement".into())
+	}
+
+	let mut entries = Vec::new();
+	for (index, record) in reader.records().enumerate() {
+		let record = record?;
+		let line = index + 2;
+		let field = |column: usize| record.get(column).unwrap_or("");
+		if record.iter().all(|value| value.is_empty()) {
+			continue
+		}
+
+		let date = NaiveDate::parse_from_str(field(date_column), &columns.date_format)
+			.map_err(|_| format!("Line {}: '{}' does not match the date format {}", line, field(date_column), columns.date_format))?;
+		let amount = match (amount_column, debit_column, credit_column) {
+			(Some(column), _, _) if !field(column).is_empty() => parse_amount(field(column)),
+			(_, Some(column), _) if !field(column).is_empty() => parse_amount(field(column)).map(|amount| -amount.abs()),
+			(_, _, Some(column)) if !field(column).is_empty() => parse_amount(field(column)).map(|amount| amount.abs()),
+			_ => Err("no amount given".to_string()),
+		}
+		.map_err(|error| format!("Line {}: {}", line, error))?;
+		let memo = memo_column.map_or("", field);
+		entries.push(StatementEntry::new(date, amount, field(payee_column), memo));
+	}
+	Ok(entries)
+}
+
+// Value of a Tag Inside an OFX Block. Handles Both the SGML Style
+// of OFX 1.x, Where Tags Are Left Open, and the XML of OFX 2.x
+fn ofx_value<'a>(block: &'a str, tag: &str) -> Option<&'a str> {
+	let open = format!("<{}>", tag);
+	let start = block.find(&open)? + open.len();
+	let rest = &block[start..];
+	let end = rest.find('<').unwrap_or(rest.len());
+	Some(rest[..end].trim())
+}
+
+// Tag Names Are Matched Without Regard to Case, so Upper Case Every
+// Tag and Leave the Values Between Them Alone
+fn upper_case_tags(text: &str) -> String {
+	let mut upper = String::with_capacity(text.len());
+	let mut in_tag = false;
+	for c in text.chars() {
+		match c {
+			'<' => in_tag = true,
+			'>' => in_tag = false,
+			_ => {},
+		}
+		if in_tag {
+			upper.extend(c.to_uppercase());
+		} else {
+			upper.push(c);
+		}
+	}
+	upper
+}
+
+pub fn parse_ofx_statement(text: &str) -> Result<Vec<StatementEntry>, Box<dyn Error>> {
+	let text = upper_case_tags(text);
+	let mut entries = Vec::new();
+	for (index, block) in text.split("<STMTTRN>").skip(1).enumerate() {
+		let block = block.split("</STMTTRN>").next().unwrap_or(block);
+		let transaction = index + 1;
+
+		let posted = ofx_value(block, "DTPOSTED")
+			.ok_or_else(|| format!("Transaction {}: missing DTPOSTED", transaction))?;
+		let date = posted
+			.get(..8)
+			.and_then(|day| NaiveDate::parse_from_str(day, "%Y%m%d").ok())
+			.ok_or_else(|| format!("Transaction {}: '{}' is not an OFX date", transaction, posted))?;
+		let amount = ofx_value(block, "TRNAMT")
+			.ok_or_else(|| format!("Transaction {}: missing TRNAMT", transaction))
+			.and_then(|amount| parse_amount(amount).map_err(|error| format!("Transaction {}: {}", transaction, error)))?;
+		let payee = ofx_value(block, "NAME").or_else(|| ofx_value(block, "PAYEE")).unwrap_or("");
+		let memo = ofx_value(block, "MEMO").unwrap_or("");
+		entries.push(StatementEntry::new(date, amount, payee, memo));
+	}
+	if entries.is_empty() {
+		return Err("No transactions found in the OFX statement".into())
+	}
+	Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn date(date: &str) -> NaiveDate {
+		NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+	}
+
+	#[test]
+	fn can_parse_csv_statement() {
+		let csv = "Posted;Details;Money Out;Money In\n\
+			03/01/2024;Corner Coffee;$4.50;\n\
+			03/02/2024;ACME PAYROLL;;\"1,200.00\"\n";
+		let mut columns = CsvColumns::default();
+		for mapping in ["date=posted", "payee=Details", "debit=Money Out", "credit=3", "date_format=%m/%d/%Y", "delimiter=;"] {
+			columns.set(mapping).unwrap();
+		}
+		columns.amount = None;
+
+		let entries = parse_csv_statement(&columns, csv.as_bytes()).unwrap();
+		assert_eq!(2, entries.len());
+		assert_eq!(StatementEntry::new(date("2024-03-01"), -4.5, "Corner Coffee", ""), entries[0]);
+		assert_eq!(TransactionKind::Income, entries[1].kind);
+		assert_eq!(1200.0, entries[1].amount);
+
+		let error = parse_csv_statement(&CsvColumns::default(), csv.as_bytes()).unwrap_err();
+		assert!(error.to_string().contains("no 'date' column"));
+	}
+
+	#[test]
+	fn can_parse_ofx_statement() {
+		let ofx = "OFXHEADER:100\n<OFX><BANKTRANLIST>\n\
+			<STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20240305120000[-5:EST]<TRNAMT>-32.10<NAME>GROCER #12<MEMO>Card\n\
+			<stmttrn>\n<trntype>CREDIT</trntype>\n<DtPosted>20240306</DtPosted>\n<trnamt>500.00</trnamt>\n<name>Refund</name>\n</stmttrn>\n\
+			</BANKTRANLIST></OFX>";
+		let entries = parse_ofx_statement(ofx).unwrap();
+		assert_eq!(StatementEntry::new(date("2024-03-05"), -32.1, "GROCER #12", "Card"), entries[0]);
+		assert_eq!(StatementEntry::new(date("2024-03-06"), 500.0, "Refund", ""), entries[1]);
+		assert!(parse_ofx_statement("<OFX></OFX>").is_err());
+	}
+
+	#[test]
+	fn can_categorize_by_payee() {
+		let categorizer = Categorizer::new(&[
+			CategoryRule { pattern: "coffee|cafe".to_string(), category: "coffee".to_string() },
+			CategoryRule { pattern: "^grocer".to_string(), category: "food".to_string() },
+		]).unwrap();
+		let entry = |amount: f64, payee: &str| StatementEntry::new(date("2024-03-01"), amount, payee, "");
+		assert_eq!("coffee", categorizer.category(&entry(-3.0, "Corner COFFEE")));
+		assert_eq!("food", categorizer.category(&entry(-3.0, "Grocer #12")));
+		assert_eq!(UNCATEGORIZED, categorizer.category(&entry(-3.0, "Hardware Store")));
+		assert_eq!("income", categorizer.category(&entry(3.0, "Hardware Store")));
+		assert!(Categorizer::new(&[CategoryRule { pattern: "(".to_string(), category: "x".to_string() }]).is_err());
+	}
+}
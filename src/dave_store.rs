@@ -0,0 +1,210 @@
+use crate::dave_vault::{open_bytes, seal_bytes, Vault};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::error::Error;
+use std::ffi::OsString;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+// Times to Try a New Random Temporary Name Before Giving Up
+const TEMP_ATTEMPTS: usize = 16;
+
+// Data Kept in a Versioned JSON Document
+pub trait Versioned: Serialize + DeserializeOwned + Default {
+	// Schema Written by This Build
+	const SCHEMA_VERSION: u32;
+
+	// Upgrade Data Written With an Older Schema. Documents
+	// Written Before Versioning Existed Arrive With No Version
+	fn migrate(data: serde_json::Value, version: Option<u32>) -> serde_json::Result<Self>;
+}
+
+#[derive(Debug)]
+pub enum StoreError {
+	Io(PathBuf, io::Error),
+	Corrupt(PathBuf, serde_json::Error),
+	UnsupportedVersion(PathBuf, u64),
+	Encrypted(PathBuf, io::Error),
+}
+
+impl fmt::Display for StoreError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			StoreError::Io(path, error) => write!(f, "{}: {}", path.display(), error),
+			StoreError::Corrupt(path, error) => write!(
+				f,
+				"{} Could Not be Read ({}). Repair the File or Move It Aside to Start Over",
+				path.display(),
+				error,
+			),
+			StoreError::UnsupportedVersion(path, version) => write!(
+				f,
+				"{} Was Written by a Newer Version of Dave (Schema {})",
+				path.display(),
+				version,
+			),
+			StoreError::Encrypted(path, error) => write!(f, "{} Could Not be Decrypted: {}", path.display(), error),
+		}
+	}
+}
+
+impl Error for StoreError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			StoreError::Io(_, error) => Some(error),
+			StoreError::Corrupt(_, error) => Some(error),
+			StoreError::UnsupportedVersion(_, _) => None,
+			StoreError::Encrypted(_, error) => Some(error),
+		}
+	}
+}
+
+// Replace a File's Contents Without Ever Leaving It Half Written.
+// Data Goes to a Sibling Temporary File Which Is Then Renamed Over
+// the Original
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+	write_atomic_with(path, |file| file.write_all(bytes))
+}
+
+// Same as write_atomic, for Contents Streamed Into the File. Nothing
+// Replaces the Original Unless the Writer Succeeds. The Temporary File
+// Gets a Fresh Name Each Time and Is Only Ever Created, Never Opened,
+// so a File That Happens to Sit Alongside Is Never Touched
+pub fn write_atomic_with<F>(path: &Path, write: F) -> io::Result<()>
+where
+	F: FnOnce(&mut File) -> io::Result<()>,
+{
+	if let Some(parent) = path.parent() {
+		if !parent.as_os_str().is_empty() {
+			fs::create_dir_all(parent)?;
+		}
+	}
+	let (temp_path, mut file) = create_temp_beside(path)?;
+
+	let result = (|| {
+		write(&mut file)?;
+		file.sync_all()?;
+		fs::rename(&temp_path, path)
+	})();
+	if result.is_err() {
+		let _ = fs::remove_file(&temp_path);
+	}
+	result
+}
+
+// A New File Next to path Named '.<name>.<random>.tmp'
+fn create_temp_beside(path: &Path) -> io::Result<(PathBuf, File)> {
+	let name = path.file_name().map(OsString::from).unwrap_or_default();
+	for _ in 0..TEMP_ATTEMPTS {
+		let mut temp_name = OsString::from(".");
+		temp_name.push(&name);
+		temp_name.push(format!(".{:016x}.tmp", rand::random::<u64>()));
+		let temp_path = path.with_file_name(temp_name);
+		match OpenOptions::new().write(true).create_new(true).open(&temp_path) {
+			Ok(file) => return Ok((temp_path, file)),
+			Err(error) if error.kind() == io::ErrorKind::AlreadyExists => continue,
+			Err(error) => return Err(error),
+		}
+	}
+	Err(io::Error::new(
+		io::ErrorKind::AlreadyExists,
+		format!("Could Not Find a Free Temporary Name Next to {}", path.display()),
+	))
+}
+
+#[derive(Serialize)]
+struct Document<'a, T> {
+	version: u32,
+	data: &'a T,
+}
+
+// Loads and Saves One Versioned JSON Document, Encrypted When a
+// Vault Is Attached
+pub struct JsonStore<T> {
+	path: PathBuf,
+	vault: Option<Vault>,
+	kind: PhantomData<T>,
+}
+
+impl<T: Versioned> JsonStore<T> {
+	pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+		JsonStore { path: path.into(), vault: None, kind: PhantomData }
+	}
+
+	pub fn with_vault(mut self, vault: Option<Vault>) -> Self {
+		self.vault = vault;
+		self
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	// Read the Document, Starting Fresh if It Doesn't Exist Yet
+	pub fn load(&self) -> Result<T, StoreError> {
+		let bytes = match fs::read(&self.path) {
+			Ok(bytes) => bytes,
+			Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(T::default()),
+			Err(error) => return Err(StoreError::Io(self.path.clone(), error)),
+		};
+		let bytes = open_bytes(self.vault.as_ref(), bytes).map_err(|error| StoreError::Encrypted(self.path.clone(), error))?;
+		let text = String::from_utf8_lossy(&bytes);
+		if text.trim().is_empty() {
+			return Ok(T::default())
+		}
+
+		let corrupt = |error| StoreError::Corrupt(self.path.clone(), error);
+		let mut value: serde_json::Value = serde_json::from_str(&text).map_err(corrupt)?;
+		match value.get("version").and_then(|version| version.as_u64()) {
+			Some(version) if version == T::SCHEMA_VERSION as u64 => {
+				serde_json::from_value(value["data"].take()).map_err(corrupt)
+			},
+			Some(version) if version > T::SCHEMA_VERSION as u64 => {
+				Err(StoreError::UnsupportedVersion(self.path.clone(), version))
+			},
+			Some(version) => self.upgrade(T::migrate(value["data"].take(), Some(version as u32)).map_err(corrupt)?),
+			None => self.upgrade(T::migrate(value, None).map_err(corrupt)?),
+		}
+	}
+
+	// Write Migrated Data Straight Back so a Migration Only Ever Runs
+	// Once, Rather Than Again, Slightly Differently, on Every Load
+	fn upgrade(&self, data: T) -> Result<T, StoreError> {
+		self.save(&data)?;
+		Ok(data)
+	}
+
+	pub fn save(&self, data: &T) -> Result<(), StoreError> {
+		let document = Document { version: T::SCHEMA_VERSION, data };
+		let bytes = serde_json::to_vec_pretty(&document)
+			.map_err(|error| StoreError::Corrupt(self.path.clone(), error))?;
+		let bytes = seal_bytes(self.vault.as_ref(), bytes).map_err(|error| StoreError::Encrypted(self.path.clone(), error))?;
+		write_atomic(&self.path, &bytes).map_err(|error| StoreError::Io(self.path.clone(), error))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn can_write_atomically_without_touching_neighbours() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("foo.rs");
+		fs::write(&path, "old").unwrap();
+		fs::write(dir.path().join("foo.rs.tmp"), "mine").unwrap();
+
+		write_atomic(&path, b"new").unwrap();
+		assert_eq!("new", fs::read_to_string(&path).unwrap());
+		assert_eq!("mine", fs::read_to_string(dir.path().join("foo.rs.tmp")).unwrap());
+
+		// A Failed Write Leaves the Original and No Temporary File Behind
+		let failed = write_atomic_with(&path, |_| Err(io::Error::other("failed")));
+		assert!(failed.is_err());
+		assert_eq!("new", fs::read_to_string(&path).unwrap());
+		assert_eq!(2, fs::read_dir(dir.path()).unwrap().count());
+	}
+}
@@ -50,7 +50,7 @@ use rand::{
 	seq::SliceRandom,
 	thread_rng,
 };
-use crate::dave_graphics::ASSETS_DIR;
+use crate::dave_graphics::assets_dir;
 
 //
 // Too Many Buttons!
@@ -84,7 +84,7 @@ fn button_system(
 
 fn setup_flex(mut commands: Commands, asset_server: Res<AssetServer>) {
 	let image = if 0 < ARG_IMAGE_FREQ {
-		Some(asset_server.load(ASSETS_DIR.to_owned() + "/textures/bevy_logo_light.png"))
+		Some(asset_server.load(assets_dir() + "/textures/bevy_logo_light.png"))
 	} else {
 		None
 	};
@@ -137,7 +137,7 @@ fn setup_flex(mut commands: Commands, asset_server: Res<AssetServer>) {
 fn setup_grid(mut commands: Commands, asset_server: Res<AssetServer>) {
     let image = if 0 < ARG_IMAGE_FREQ {
         Some(asset_server.load(
-        	ASSETS_DIR.to_owned() + "/textures/bevy_logo_light.png",
+        	assets_dir() + "/textures/bevy_logo_light.png",
         ))
     } else {
         None
@@ -589,7 +589,7 @@ fn davemark_setup(
 
 	if matches!(MODE, Mode::Sprite) || MATERIAL_TEXTURE_COUNT > 0 {
 		textures.push(asset_server.load(
-			ASSETS_DIR.to_owned() + "/textures/frog.png",
+			assets_dir() + "/textures/frog.png",
 		));
 	}
 	init_textures(&mut textures, images);
@@ -6,7 +6,8 @@ use std::io::{
 	self,
 	Write,
 };
-use std::path::Path;
+use crate::config::{find_ai_log_file, CONFIG};
+use std::path::PathBuf;
 use tabled::{
 	builder::Builder,
 	settings::Style,
@@ -15,7 +16,6 @@ use tabled::{
 const TOTAL_ROWS: usize = 3;
 const TOTAL_COLUMNS: usize = 3;
 const MAX_FILL: usize = TOTAL_ROWS * TOTAL_COLUMNS;
-const AI_LOG_FILE: &str = "./dave_conf/var/dave_ai.log";
 
 // Board Drawing Functions
 fn clearscreen() {
@@ -270,6 +270,10 @@ fn ai_best_move(
 	Ok(best_move)
 }
 
+fn ai_log_file() -> PathBuf {
+	CONFIG.read().map_or_else(|_| find_ai_log_file(), |config| config.ai_log_path())
+}
+
 fn write_ai_log(data: &str) -> Result<(), String> {
 	remove_ai_log();
 
@@ -277,7 +281,7 @@ fn write_ai_log(data: &str) -> Result<(), String> {
 		.create_new(true)
 		.write(true)
 		.append(true)
-		.open(AI_LOG_FILE)
+		.open(ai_log_file())
 		.map_err(|_| "Failed to open AI log file".to_string())?;
 
 	// Write Data to File
@@ -286,8 +290,9 @@ fn write_ai_log(data: &str) -> Result<(), String> {
 }
 
 fn remove_ai_log() {
-	if Path::new(AI_LOG_FILE).exists() {
-		match fs::remove_file(AI_LOG_FILE).map_err(|_| "File cannot be removed".to_string()) {
+	let ai_log_file = ai_log_file();
+	if ai_log_file.exists() {
+		match fs::remove_file(&ai_log_file).map_err(|_| "File cannot be removed".to_string()) {
 			Ok(_) => {},
 			Err(error) => eprintln!("##==>>>> ERROR: {}", error),
 		};
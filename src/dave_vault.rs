@@ -0,0 +1,322 @@
+use age::secrecy::ExposeSecret;
+use age::x25519;
+use chrono::{DateTime, Duration, Utc};
+use crate::config::DaveConfig;
+use crate::dave_encrypt::{
+	decrypt_stream,
+	encrypt_stream,
+	prompt_passphrase,
+	read_identity_file,
+	DecryptWith,
+	EncryptWith,
+};
+use crate::dave_store::write_atomic_with;
+use std::env;
+use std::fs;
+use std::io::{self, Error, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+// Every age File Begins With This, Which Is How Sealed Data Is Told
+// Apart From Data Written Before Encryption Was Turned On
+pub const SEALED_HEADER: &[u8] = b"age-encryption.org/v1";
+
+// Name of the File Holding an Unlocked Key Between Commands
+pub const SESSION_FILE: &str = "dave_session";
+
+fn default_unlock_minutes() -> u64 {
+	15
+}
+
+// Whether the Budget and Notes Are Encrypted at Rest
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct EncryptionConfig {
+	#[serde(default)]
+	pub enabled: bool,
+	// Identity File That Unlocks the Vault. Without One a Passphrase Is Asked For
+	#[serde(default)]
+	pub identity: Option<PathBuf>,
+	// How Long an Unlocked Vault Stays Unlocked. Zero Asks Every Time
+	#[serde(default = "default_unlock_minutes")]
+	pub unlock_minutes: u64,
+}
+
+impl Default for EncryptionConfig {
+	fn default() -> Self {
+		EncryptionConfig { enabled: false, identity: None, unlock_minutes: default_unlock_minutes() }
+	}
+}
+
+pub fn is_sealed(bytes: &[u8]) -> bool {
+	bytes.starts_with(SEALED_HEADER)
+}
+
+// How the Vault Key File Is Locked: With an Identity File if One Is
+// Given, Otherwise a Passphrase Typed at the Terminal
+pub fn vault_lock(identity: Option<&Path>, confirm: bool) -> io::Result<(EncryptWith, DecryptWith)> {
+	match identity {
+		Some(path) => {
+			let identities = read_identity_file(path)?;
+			if identities.is_empty() {
+				return Err(Error::new(ErrorKind::InvalidInput, format!("{} Has No Identities in It", path.display())))
+			}
+			let recipients = identities.iter().map(x25519::Identity::to_public).collect();
+			Ok((EncryptWith::Recipients(recipients), DecryptWith::Identities(identities)))
+		},
+		None => {
+			let passphrase = prompt_passphrase(confirm)?;
+			Ok((EncryptWith::Passphrase(passphrase.clone()), DecryptWith::Passphrase(passphrase)))
+		},
+	}
+}
+
+#[derive(Serialize, Deserialize)]
+struct Session {
+	key_file: PathBuf,
+	expires: DateTime<Utc>,
+	key: String,
+}
+
+// Remembers an Unlocked Key for a While so Every Command Doesn't Ask
+// for the Passphrase. Only Ever Kept in the Per-User Runtime Directory,
+// Which Lives in Memory and Is Emptied at Logout, Readable Only by Its
+// Owner. Without One Nothing Is Cached, Since Writing the Key to Disk
+// Beside the Data Would Undo the Encryption
+pub struct SessionCache {
+	path: PathBuf,
+	timeout: Duration,
+}
+
+impl SessionCache {
+	pub fn new<P: Into<PathBuf>>(path: P, minutes: u64) -> Self {
+		SessionCache { path: path.into(), timeout: Duration::minutes(minutes as i64) }
+	}
+
+	// The Session for This Config, or None Without a Runtime Directory.
+	// A Session That Has Expired Is Removed Straight Away
+	pub fn for_config(config: &DaveConfig) -> Option<Self> {
+		let directory = env::var_os("XDG_RUNTIME_DIR").map(PathBuf::from).filter(|directory| directory.is_dir())?;
+		let cache = SessionCache::new(directory.join(SESSION_FILE), config.encryption.unlock_minutes);
+		cache.clear_expired(Utc::now());
+		Some(cache)
+	}
+
+	// Where Older Versions Kept the Session When There Was No Runtime
+	// Directory. Anything Left There Should be Removed
+	pub fn legacy_path(config: &DaveConfig) -> PathBuf {
+		config.root_path().join("var").join(SESSION_FILE)
+	}
+
+	fn clear_expired(&self, now: DateTime<Utc>) {
+		let expired = fs::read(&self.path)
+			.ok()
+			.and_then(|bytes| serde_json::from_slice::<Session>(&bytes).ok())
+			.is_some_and(|session| session.expires <= now);
+		if expired {
+			let _ = self.clear();
+		}
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	// The Cached Key, if It Belongs to This Key File and Hasn't Expired
+	pub fn load(&self, key_file: &Path, now: DateTime<Utc>) -> Option<x25519::Identity> {
+		let session: Session = serde_json::from_slice(&fs::read(&self.path).ok()?).ok()?;
+		if session.key_file != key_file || session.expires <= now {
+			let _ = self.clear();
+			return None
+		}
+		session.key.parse().ok()
+	}
+
+	pub fn store(&self, key_file: &Path, key: &x25519::Identity, now: DateTime<Utc>) -> io::Result<()> {
+		if self.timeout <= Duration::zero() {
+			return Ok(())
+		}
+		let session = Session {
+			key_file: key_file.to_path_buf(),
+			expires: now + self.timeout,
+			key: key.to_string().expose_secret().to_string(),
+		};
+		let bytes = serde_json::to_vec(&session).map_err(Error::other)?;
+		write_atomic_with(&self.path, |file| {
+			#[cfg(unix)]
+			{
+				use std::os::unix::fs::PermissionsExt;
+				file.set_permissions(fs::Permissions::from_mode(0o600))?;
+			}
+			file.write_all(&bytes)
+		})
+	}
+
+	pub fn clear(&self) -> io::Result<()> {
+		match fs::remove_file(&self.path) {
+			Err(error) if error.kind() != ErrorKind::NotFound => Err(error),
+			_ => Ok(()),
+		}
+	}
+}
+
+// Seals and Opens Private Data. A Random Vault Key Does the Actual
+// Encryption so Each Record Is Fast to Read, and That Key Is Itself
+// Kept in a File Locked With the User's Passphrase or Identity. It Is
+// Only Unlocked the First Time Something Needs It
+pub struct Vault {
+	key_file: PathBuf,
+	identity: Option<PathBuf>,
+	session: Option<SessionCache>,
+	key: OnceLock<x25519::Identity>,
+}
+
+impl Vault {
+	// The Configured Vault, or None When Encryption Is Turned Off
+	pub fn from_config(config: &DaveConfig) -> Option<Self> {
+		if !config.encryption.enabled {
+			return None
+		}
+		// Older Versions Could Leave an Unlocked Key on Disk Here
+		let _ = fs::remove_file(SessionCache::legacy_path(config));
+		Some(Vault {
+			key_file: config.vault_key_path(),
+			identity: config.encryption.identity.clone(),
+			session: SessionCache::for_config(config),
+			key: OnceLock::new(),
+		})
+	}
+
+	// A Vault With a Brand New Key That Hasn't Been Written Anywhere Yet
+	pub fn generate<P: Into<PathBuf>>(key_file: P) -> Self {
+		Self::with_key(key_file, x25519::Identity::generate())
+	}
+
+	pub fn with_key<P: Into<PathBuf>>(key_file: P, key: x25519::Identity) -> Self {
+		let vault = Vault { key_file: key_file.into(), identity: None, session: None, key: OnceLock::new() };
+		let _ = vault.key.set(key);
+		vault
+	}
+
+	// Unlock if Needed and Return a Second Handle on the Same Key
+	pub fn try_clone(&self) -> io::Result<Self> {
+		Ok(Self::with_key(&self.key_file, self.key()?.clone()))
+	}
+
+	fn key(&self) -> io::Result<&x25519::Identity> {
+		if let Some(key) = self.key.get() {
+			return Ok(key)
+		}
+		let key = self.unlock()?;
+		Ok(self.key.get_or_init(|| key))
+	}
+
+	fn unlock(&self) -> io::Result<x25519::Identity> {
+		let now = Utc::now();
+		if let Some(key) = self.session.as_ref().and_then(|session| session.load(&self.key_file, now)) {
+			return Ok(key)
+		}
+		let locked = fs::read(&self.key_file).map_err(|error| {
+			Error::new(error.kind(), format!("Vault Key {}: {}", self.key_file.display(), error))
+		})?;
+		println!("##==> Unlocking Encrypted Data ...");
+		let (_, unlock) = vault_lock(self.identity.as_deref(), false)?;
+		let mut text = Vec::new();
+		decrypt_stream(&unlock, &locked[..], &mut text)?;
+		let key: x25519::Identity = String::from_utf8_lossy(&text)
+			.trim()
+			.parse()
+			.map_err(|error: &str| Error::new(ErrorKind::InvalidData, format!("Vault Key Is Damaged: {}", error)))?;
+		if let Some(session) = &self.session {
+			session.store(&self.key_file, &key, now)?;
+		}
+		Ok(key)
+	}
+
+	// Write the Key File, Locked so Only the Given Passphrase or Identity Opens It
+	pub fn write_key_file(&self, lock: &EncryptWith) -> io::Result<()> {
+		let key = self.key()?;
+		let mut locked = Vec::new();
+		encrypt_stream(lock, &mut key.to_string().expose_secret().as_bytes(), &mut locked, true)?;
+		write_atomic_with(&self.key_file, |file| file.write_all(&locked))
+	}
+
+	// Remember This Vault's Key for the Session
+	pub fn remember(&self, session: &SessionCache) -> io::Result<()> {
+		session.store(&self.key_file, self.key()?, Utc::now())
+	}
+
+	pub fn seal(&self, bytes: &[u8]) -> io::Result<Vec<u8>> {
+		let key = EncryptWith::Recipients(vec![self.key()?.to_public()]);
+		let mut sealed = Vec::new();
+		encrypt_stream(&key, &mut &bytes[..], &mut sealed, false)?;
+		Ok(sealed)
+	}
+
+	pub fn open(&self, sealed: &[u8]) -> io::Result<Vec<u8>> {
+		let key = DecryptWith::Identities(vec![self.key()?.clone()]);
+		let mut bytes = Vec::new();
+		decrypt_stream(&key, sealed, &mut bytes)?;
+		Ok(bytes)
+	}
+}
+
+// Open Data Read From Disk. Sealed Data Needs a Vault, Plain Data Is
+// Passed Through so Files From Before Encryption Still Load
+pub fn open_bytes(vault: Option<&Vault>, bytes: Vec<u8>) -> io::Result<Vec<u8>> {
+	match (is_sealed(&bytes), vault) {
+		(false, _) => Ok(bytes),
+		(true, Some(vault)) => vault.open(&bytes),
+		(true, None) => Err(Error::new(
+			ErrorKind::PermissionDenied,
+			"Data Is Encrypted but Encryption Isn't Enabled in the Config",
+		)),
+	}
+}
+
+pub fn seal_bytes(vault: Option<&Vault>, bytes: Vec<u8>) -> io::Result<Vec<u8>> {
+	match vault {
+		Some(vault) => vault.seal(&bytes),
+		None => Ok(bytes),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn can_seal_and_cache_keys() {
+		let dir = tempfile::tempdir().unwrap();
+		let key_file = dir.path().join("vault.key");
+		let vault = Vault::generate(&key_file);
+		let sealed = vault.seal(b"rent 1200").unwrap();
+		assert!(is_sealed(&sealed));
+		assert_eq!(b"rent 1200".to_vec(), open_bytes(Some(&vault), sealed.clone()).unwrap());
+		assert_eq!(b"plain".to_vec(), open_bytes(Some(&vault), b"plain".to_vec()).unwrap());
+		assert_eq!(ErrorKind::PermissionDenied, open_bytes(None, sealed.clone()).unwrap_err().kind());
+		assert!(Vault::generate(&key_file).open(&sealed).is_err());
+
+		// The Key File Only Opens With the Passphrase It Was Locked With
+		vault.write_key_file(&EncryptWith::Passphrase("hunter2".to_string())).unwrap();
+		let mut text = Vec::new();
+		let unlock = DecryptWith::Passphrase("hunter2".to_string());
+		decrypt_stream(&unlock, &fs::read(&key_file).unwrap()[..], &mut text).unwrap();
+		let key: x25519::Identity = String::from_utf8(text).unwrap().parse().unwrap();
+		assert_eq!(b"rent 1200".to_vec(), Vault::with_key(&key_file, key).open(&sealed).unwrap());
+
+		let session = SessionCache::new(dir.path().join(SESSION_FILE), 15);
+		let now = Utc::now();
+		session.store(&key_file, vault.key().unwrap(), now).unwrap();
+		assert!(session.load(&key_file, now).is_some());
+		assert!(session.load(&dir.path().join("other.key"), now).is_none());
+		session.store(&key_file, vault.key().unwrap(), now).unwrap();
+		assert!(session.load(&key_file, now + Duration::minutes(16)).is_none());
+		session.store(&key_file, vault.key().unwrap(), now).unwrap();
+		session.clear_expired(now + Duration::minutes(14));
+		assert!(session.path().exists());
+		session.clear_expired(now + Duration::minutes(16));
+		assert!(!session.path().exists());
+		// Expired Sessions Are Removed
+		assert!(!dir.path().join(SESSION_FILE).exists());
+	}
+}
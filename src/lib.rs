@@ -2,6 +2,7 @@
 extern crate serde_derive;
 
 pub mod config;
+pub mod dave_archive;
 pub mod dave_breakout;
 pub mod dave_budget;
 pub mod dave_calcs;
@@ -10,9 +11,12 @@ pub mod dave_chip8;
 pub mod dave_conversions;
 pub mod dave_currency;
 pub mod dave_db;
+pub mod dave_dupes;
 pub mod dave_ecs;
 pub mod dave_ed;
 pub mod dave_encrypt;
+pub mod dave_expr;
+pub mod dave_finance;
 pub mod dave_game;
 pub mod dave_genetic_algo;
 pub mod dave_graphics;
@@ -20,6 +24,7 @@ pub mod dave_grep;
 pub mod dave_guess;
 pub mod dave_gui;
 pub mod dave_hash;
+pub mod dave_integrity;
 pub mod dave_land;
 pub mod dave_land_lib;
 pub mod dave_machine;
@@ -31,11 +36,17 @@ pub mod dave_parse;
 pub mod dave_perceptron;
 pub mod dave_port_scan;
 pub mod dave_quiz;
+pub mod dave_rate_provider;
 pub mod dave_rep_max;
 pub mod dave_scrape;
+pub mod dave_search;
+pub mod dave_setup;
 pub mod dave_skybox;
 pub mod dave_snake;
+pub mod dave_statement;
+pub mod dave_store;
 pub mod dave_stress_tests;
 pub mod dave_tic_tac_toe;
+pub mod dave_vault;
 pub mod release;
 pub mod utils;
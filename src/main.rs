@@ -1,3 +1,4 @@
+use bytesize::ByteSize;
 use clap::{
     Command,
     Arg,
@@ -19,28 +20,22 @@ use crossterm::{
         LeaveAlternateScreen,
     },
 };
-use rand::{
-    thread_rng,
-    Rng,
-};
-use rand::distributions::Alphanumeric;
-use sha2::Digest;
+use iso_currency::Currency;
+use rust_decimal::Decimal;
 use tui::{
     backend::CrosstermBackend,
     Terminal,
 };
-use std::borrow::BorrowMut;
 use std::env;
 use std::fs::{
-    self,
     File,
     OpenOptions,
 };
 use std::io::{
     self,
+    IsTerminal,
     Read,
     stdout,
-    Write,
 };
 use std::path::{
     Path,
@@ -51,14 +46,20 @@ use std::str::FromStr;
 use std::time::Instant;
 use davelib::config::*;
 use davelib::dave_breakout::dave_breakout_main;
-use davelib::dave_budget::DaveBudget;
+use davelib::dave_budget::*;
 use davelib::dave_calcs::*;
 use davelib::dave_cars::dave_cars_main;
 use davelib::dave_chip8::*;
 use davelib::dave_conversions::*;
-use davelib::dave_currency::dave_currency_conv;
-use davelib::dave_db::DaveDatabase;
+use davelib::dave_currency::*;
+use davelib::dave_db::{
+    DaveDatabase,
+    DaveTree,
+};
+use davelib::dave_dupes::*;
 use davelib::dave_ecs::dave_ecs_main;
+use davelib::dave_expr::{format_number, Calculator};
+use davelib::dave_finance::{Loan, Period, Savings, FREQUENCY_NAMES};
 use davelib::dave_ed::{dave_ed_main, dave_ed_load_file};
 use davelib::dave_encrypt::*;
 use davelib::dave_game::davegame_main;
@@ -76,10 +77,13 @@ use davelib::dave_graphics::{
 use davelib::dave_grep::{
     self,
     Config,
+    OutputMode,
+    ReplaceMode,
 };
 use davelib::dave_guess::guess_number;
 use davelib::dave_gui::dave_gui;
 use davelib::dave_hash::*;
+use davelib::dave_integrity::*;
 use davelib::dave_land::dave_game_loop;
 use davelib::dave_machine::*;
 use davelib::dave_notes::*;
@@ -87,24 +91,82 @@ use davelib::dave_parse::parse_handle_file;
 use davelib::dave_perceptron::daves_perceptron;
 use davelib::dave_port_scan::port_scan_main;
 use davelib::dave_quiz::*;
+use davelib::dave_rate_provider::{
+    RateCache,
+    RateRefresh,
+};
 use davelib::dave_rep_max::dave_rep_max_calc;
 use davelib::dave_scrape::*;
+use davelib::dave_search::SearchOptions;
+use davelib::dave_setup::*;
 use davelib::dave_skybox::daves_skybox_main;
 use davelib::dave_snake::Game;
+use davelib::dave_statement::{
+    parse_statement,
+    Categorizer,
+    CategoryRule,
+    StatementFormat,
+};
 use davelib::dave_stress_tests::{
     davemark_main,
     st_too_many_buttons_main,
     st_too_many_lights_main,
 };
 use davelib::dave_tic_tac_toe::tic_tac_toe_main;
+use davelib::dave_vault::{vault_lock, SessionCache, Vault};
 use davelib::utils::*;
 use davelib::release;
 use davelib::release::*;
 
+// Arguments Shared by the Subcommands That Search Through Files
+fn search_args(command: Command) -> Command {
+    command
+        .arg(Arg::new("hidden")
+            .long("hidden")
+            .action(ArgAction::SetTrue)
+            .help("Also search hidden files and directories"))
+        .arg(Arg::new("no-ignore")
+            .long("no-ignore")
+            .action(ArgAction::SetTrue)
+            .help("Don't skip paths listed in .gitignore, .ignore or .daveignore files"))
+        .arg(Arg::new("binary")
+            .long("binary")
+            .action(ArgAction::SetTrue)
+            .help("Search binary files as if they were text"))
+        .arg(Arg::new("type")
+            .long("type")
+            .short('t')
+            .value_name("type")
+            .action(ArgAction::Append)
+            .help("Only search files of a type, such as 'rust', 'py' or 'md'. Can be given more than once"))
+        .arg(Arg::new("glob")
+            .long("glob")
+            .short('g')
+            .value_name("glob")
+            .action(ArgAction::Append)
+            .help("Only search files matching a glob, or skip them if it starts with '!'. Can be given more than once"))
+}
+
+fn search_options(matches: &ArgMatches) -> SearchOptions {
+    SearchOptions {
+        hidden: matches.get_flag("hidden"),
+        no_ignore: matches.get_flag("no-ignore"),
+        binary: matches.get_flag("binary"),
+        types: matches.get_many::<String>("type").into_iter().flatten().cloned().collect(),
+        globs: matches.get_many::<String>("glob").into_iter().flatten().cloned().collect(),
+    }
+}
+
 fn argument_parser() -> ArgMatches {
     Command::new(release::DISPLAY_NAME)
         .version(release::VERSION_STR)
         .about(release::DISPLAY_DESCRIPTION)
+        .arg(Arg::new("override")
+            .long("override")
+            .value_name("key=value")
+            .action(ArgAction::Append)
+            .global(true)
+            .help("Override a setting for this run only, like --override hash_type=blake3. Can be repeated"))
         .subcommand(Command::new("config")
             .about("Save or set default settings for the program's config file, along with the path to it")
             .arg(Arg::new("defaults")
@@ -124,7 +186,123 @@ fn argument_parser() -> ArgMatches {
             .arg(Arg::new("save")
                 .long("save")
                 .action(ArgAction::SetTrue)
-                .help("Write this configuration to its default location or the path specified by config --path")))
+                .help("Write this configuration to its default location or the path specified by config --path"))
+            .arg(Arg::new("encrypt")
+                .long("encrypt")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["rekey", "lock"])
+                .help("Encrypt the budget and notes at rest. You'll be asked for a passphrase unless --identity is given"))
+            .arg(Arg::new("rekey")
+                .long("rekey")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("lock")
+                .help("Re-encrypt the budget and notes under a new key, locked with a new passphrase or --identity"))
+            .arg(Arg::new("identity")
+                .long("identity")
+                .short('i')
+                .value_parser(value_parser!(PathBuf))
+                .value_name("path")
+                .num_args(1)
+                .help("Unlock encrypted data with an age identity file instead of a passphrase. Used with --encrypt or --rekey"))
+            .arg(Arg::new("lock")
+                .long("lock")
+                .action(ArgAction::SetTrue)
+                .help("Forget the unlocked key so the next command asks for it again"))
+            .arg(Arg::new("hash-type")
+                .long("hash-type")
+                .value_parser(HASH_TYPE_NAMES)
+                .value_name("algorithm")
+                .num_args(1)
+                .help("Sets the default hashing algorithm. Combine with --save to keep it"))
+            .arg(Arg::new("explain")
+                .long("explain")
+                .action(ArgAction::SetTrue)
+                .help("Show every setting and which layer it came from: default, config file, project .dave.conf, DAVE_* environment variable or --override"))
+            .args_conflicts_with_subcommands(true)
+            .subcommand(Command::new("list")
+                .about("Show every setting and its current value"))
+            .subcommand(Command::new("get")
+                .about("Show the value of one setting, like hash_type or import.csv.delimiter")
+                .arg(Arg::new("key")
+                    .value_name("key")
+                    .required(true)
+                    .num_args(1)))
+            .subcommand(Command::new("set")
+                .about("Change a setting and save it. Values are checked against the setting's type")
+                .arg(Arg::new("key")
+                    .value_name("key")
+                    .required(true)
+                    .num_args(1))
+                .arg(Arg::new("value")
+                    .value_name("value")
+                    .required(true)
+                    .allow_hyphen_values(true)
+                    .num_args(1)))
+            .subcommand(Command::new("unset")
+                .about("Put a setting back to its default and save it")
+                .arg(Arg::new("key")
+                    .value_name("key")
+                    .required(true)
+                    .num_args(1)))
+            .subcommand(Command::new("validate")
+                .about("Check the config file for unknown settings, wrong types and missing paths")
+                .arg(Arg::new("file")
+                    .long("file")
+                    .short('f')
+                    .value_parser(value_parser!(PathBuf))
+                    .value_name("path")
+                    .num_args(1)
+                    .help("Check this file instead of the current config file"))))
+        .subcommand(Command::new("setup")
+            .about("Create Dave's data and config directories, copy in its data files and write a default dave.conf")
+            .arg(Arg::new("prefix")
+                .long("prefix")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("path")
+                .num_args(1)
+                .help("Install under this directory. Defaults to $XDG_DATA_HOME/dave"))
+            .arg(Arg::new("from")
+                .long("from")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("path")
+                .num_args(1)
+                .help("Copy quizzes, ROMs, assets and rates from this dave_conf directory. Defaults to ./dave_conf when there is one"))
+            .arg(Arg::new("config")
+                .long("config")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("path")
+                .num_args(1)
+                .help("Write dave.conf here. Defaults to $XDG_CONFIG_HOME/dave/dave.conf"))
+            .arg(Arg::new("yes")
+                .long("yes")
+                .short('y')
+                .action(ArgAction::SetTrue)
+                .help("Don't ask before making changes"))
+            .arg(Arg::new("dry-run")
+                .long("dry-run")
+                .action(ArgAction::SetTrue)
+                .help("Only show what setup would do")))
+        .subcommand(Command::new("uninstall")
+            .about("Remove what 'dave setup' installed, after listing it")
+            .arg(Arg::new("prefix")
+                .long("prefix")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("path")
+                .num_args(1)
+                .help("Remove the installation under this directory. Defaults to the configured root_path"))
+            .arg(Arg::new("purge")
+                .long("purge")
+                .action(ArgAction::SetTrue)
+                .help("Also remove var/, which holds the budget, notes, database and vault key. It's kept otherwise"))
+            .arg(Arg::new("yes")
+                .long("yes")
+                .short('y')
+                .action(ArgAction::SetTrue)
+                .help("Don't ask before removing anything"))
+            .arg(Arg::new("dry-run")
+                .long("dry-run")
+                .action(ArgAction::SetTrue)
+                .help("Only list what would be removed")))
         .subcommand(Command::new("dave-ed")
             .about("Dave's text editor")
             .arg(Arg::new("load")
@@ -138,7 +316,7 @@ fn argument_parser() -> ArgMatches {
                 .value_parser(value_parser!(String))
                 .value_name("path")
                 .num_args(1)))
-        .subcommand(Command::new("find")
+        .subcommand(search_args(Command::new("find")
             .about("Find a pattern within files of a given directory")
             .arg(Arg::new("verbose")
                 .long("verbose")
@@ -155,9 +333,15 @@ fn argument_parser() -> ArgMatches {
             .arg(Arg::new("filename")
                 .value_parser(value_parser!(String))
                 .value_name("filename")
-                .num_args(1)))
+                .num_args(1))))
         .subcommand(Command::new("calc")
-            .about("Use the program's calculator")
+            .about("Use the program's calculator. Evaluates an expression, or starts an interactive calculator when given none")
+            .arg(Arg::new("expression")
+                .value_name("expression")
+                .num_args(1..)
+                .allow_hyphen_values(true)
+                .conflicts_with_all(["simple", "income", "interest", "compound", "loan", "goal"])
+                .help("An expression like \"2 * (3 + 4)^2\" or \"x = 3; sqrt(x^2 + 16)\""))
             .arg(Arg::new("simple")
                 .long("simple")
                 .short('s')
@@ -172,7 +356,84 @@ fn argument_parser() -> ArgMatches {
                 .long("interest")
                 .short('n')
                 .action(ArgAction::SetTrue)
-                .help("Simple Interest Calculator by Dave")))
+                .help("Interest Calculator by Dave. Asks for each value"))
+            .arg(Arg::new("compound")
+                .long("compound")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["loan", "goal"])
+                .help("Project savings with compound interest and regular contributions"))
+            .arg(Arg::new("loan")
+                .long("loan")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("goal")
+                .help("Show a loan's amortisation schedule and payoff date"))
+            .arg(Arg::new("goal")
+                .long("goal")
+                .value_parser(value_parser!(f64))
+                .value_name("amount")
+                .num_args(1)
+                .help("Find the contribution needed to save this much"))
+            .arg(Arg::new("principal")
+                .long("principal")
+                .short('p')
+                .value_parser(value_parser!(f64))
+                .value_name("amount")
+                .num_args(1)
+                .help("Starting balance, or the amount borrowed with --loan. Defaults to 0 with --goal"))
+            .arg(Arg::new("rate")
+                .long("rate")
+                .short('r')
+                .value_parser(value_parser!(f64))
+                .value_name("percent")
+                .num_args(1)
+                .help("Yearly interest rate, like 5 for 5%"))
+            .arg(Arg::new("years")
+                .long("years")
+                .short('y')
+                .value_parser(value_parser!(f64))
+                .value_name("years")
+                .num_args(1)
+                .help("How long to save for, or the loan term"))
+            .arg(Arg::new("compounding")
+                .long("compounding")
+                .value_parser(FREQUENCY_NAMES)
+                .default_value("monthly")
+                .value_name("frequency")
+                .num_args(1)
+                .help("How often interest compounds on savings"))
+            .arg(Arg::new("contribution")
+                .long("contribution")
+                .value_parser(value_parser!(f64))
+                .default_value("0")
+                .value_name("amount")
+                .num_args(1)
+                .help("Added to savings every period set by --every"))
+            .arg(Arg::new("every")
+                .long("every")
+                .value_parser(FREQUENCY_NAMES)
+                .default_value("monthly")
+                .value_name("frequency")
+                .num_args(1)
+                .help("How often contributions or loan payments are made"))
+            .arg(Arg::new("extra")
+                .long("extra")
+                .value_parser(value_parser!(f64))
+                .default_value("0")
+                .value_name("amount")
+                .num_args(1)
+                .help("Paid on top of every loan payment"))
+            .arg(Arg::new("start")
+                .long("start")
+                .value_parser(value_parser!(chrono::NaiveDate))
+                .value_name("YYYY-MM-DD")
+                .num_args(1)
+                .help("Date the loan starts. Defaults to today"))
+            .arg(Arg::new("csv")
+                .long("csv")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("path")
+                .num_args(1)
+                .help("Also write the table as CSV. Use - to print only the CSV")))
         .subcommand(Command::new("chip8")
             .about("Use Dave's very own rudimentary Chip8 emulator")
             .arg(Arg::new("pixel")
@@ -185,7 +446,8 @@ fn argument_parser() -> ArgMatches {
             .arg(Arg::new("filename")
                 .value_parser(value_parser!(String))
                 .value_name("path")
-                .num_args(1)))
+                .num_args(1)
+                .help("A ROM file, or the name of one in the configured roms_path")))
         .subcommand(Command::new("quiz")
             .about("Take David's quizzes")
             .arg(Arg::new("animals")
@@ -272,18 +534,85 @@ fn argument_parser() -> ArgMatches {
                 .value_name("KPH")
                 .help("Convert Kilometers per Hour to Miles per Hour")))
         .subcommand(Command::new("hash")
-            .about("Hash a file using a preferred hashing algorithm")
+            .about("Hash files and directories using a preferred hashing algorithm, printed in sha256sum format")
             .arg(Arg::new("filename")
-                .value_parser(value_parser!(String))
+                .value_parser(value_parser!(PathBuf))
                 .value_name("path")
-                .num_args(1))
+                .num_args(1..)
+                .help("Files to hash. Directories are hashed recursively"))
+            .arg(Arg::new("check")
+                .long("check")
+                .short('c')
+                .num_args(1)
+                .value_name("SUMS")
+                .value_parser(value_parser!(PathBuf))
+                .conflicts_with("filename")
+                .help("Verify the files listed in a sha256sum style manifest, reporting OK, FAILED or MISSING for each"))
+            .arg(Arg::new("hash-type")
+                .long("hash-type")
+                .value_name("algorithm")
+                .value_parser(HASH_TYPE_NAMES)
+                .num_args(1)
+                .help("Chooses which hashing algorithm the program will use. Defaults to the configured hash_type"))
+            .arg(Arg::new("hmac-key")
+                .long("hmac-key")
+                .value_name("KEY")
+                .num_args(1)
+                .value_parser(value_parser!(String))
+                .help("Compute an HMAC with this secret instead of a plain digest, e.g. to verify signed webhook payloads. BLAKE3 uses its keyed mode with a 32 byte key")))
+        .subcommand(Command::new("dupes")
+            .about("Find files with identical contents and report the space they waste")
+            .arg(Arg::new("directory")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("DIR")
+                .num_args(1..)
+                .required(true)
+                .help("Directories to search. Subdirectories are searched too"))
+            .arg(Arg::new("hardlink")
+                .long("hardlink")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("delete")
+                .help("Replace every copy but the first with a hard link to it"))
+            .arg(Arg::new("delete")
+                .long("delete")
+                .action(ArgAction::SetTrue)
+                .help("Delete every copy but the first"))
+            .arg(Arg::new("apply")
+                .long("apply")
+                .action(ArgAction::SetTrue)
+                .help("Actually link or delete files. Without this '--hardlink' and '--delete' only show what would happen"))
             .arg(Arg::new("hash-type")
                 .long("hash-type")
                 .value_name("algorithm")
-                .default_value("sha-256")
-                .value_parser(["md5", "sha-256", "sha-384", "sha-512"])
+                .value_parser(HASH_TYPE_NAMES)
                 .num_args(1)
-                .help("Chooses which hashing algorithm the program will use")))
+                .help("Chooses which hashing algorithm compares file contents. Defaults to the configured hash_type")))
+        .subcommand(Command::new("integrity")
+            .about("Record a baseline of a directory and detect files that have since been added, removed or changed")
+            .subcommand_required(true)
+            .subcommand(Command::new("init")
+                .about("Record the path, size, modification time, permissions and digest of every file under a directory")
+                .arg(Arg::new("directory")
+                    .value_parser(value_parser!(PathBuf))
+                    .value_name("DIR")
+                    .num_args(1)
+                    .required(true))
+                .arg(Arg::new("hash-type")
+                    .long("hash-type")
+                    .value_name("algorithm")
+                    .value_parser(HASH_TYPE_NAMES)
+                    .num_args(1)
+                    .help("Chooses which hashing algorithm the baseline uses. Defaults to the configured hash_type")))
+            .subcommand(Command::new("check")
+                .about("Compare a directory against its baseline. Checks every recorded baseline when no directory is given")
+                .arg(Arg::new("directory")
+                    .value_parser(value_parser!(PathBuf))
+                    .value_name("DIR")
+                    .num_args(1))
+                .arg(Arg::new("json")
+                    .long("json")
+                    .action(ArgAction::SetTrue)
+                    .help("Print the report as JSON instead of a colored summary"))))
         .subcommand(Command::new("guess")
             .about("Guess a number from 0 - 10 for funsies")
             .arg(Arg::new("number")
@@ -302,7 +631,7 @@ fn argument_parser() -> ArgMatches {
                 .short('d')
                 .action(ArgAction::SetTrue)
                 .help("Run a standard NumPy compatible DBScan algorithm")))
-        .subcommand(Command::new("dgrep")
+        .subcommand(search_args(Command::new("dgrep")
             .about("Behold Dave's glorious implementation of GREP in Rust.")
             .arg(Arg::new("option")
                 .long("option")
@@ -310,16 +639,85 @@ fn argument_parser() -> ArgMatches {
                 .num_args(1)
                 .value_parser(value_parser!(String))
                 .help("Pass '-o i' or '-o I' for case insensitivity. Pass '-o r' or '-o R' for REGEX pattern matching"))
+            .arg(Arg::new("ignore-case")
+                .long("ignore-case")
+                .short('i')
+                .action(ArgAction::SetTrue)
+                .help("Match regardless of case"))
+            .arg(Arg::new("regex")
+                .long("regex")
+                .short('E')
+                .action(ArgAction::SetTrue)
+                .help("Treat the pattern as a REGEX"))
+            .arg(Arg::new("word")
+                .long("word-regexp")
+                .short('w')
+                .action(ArgAction::SetTrue)
+                .help("Only match the pattern as a whole word"))
+            .arg(Arg::new("invert")
+                .long("invert-match")
+                .short('v')
+                .action(ArgAction::SetTrue)
+                .help("Print the lines that don't match"))
+            .arg(Arg::new("count")
+                .long("count")
+                .short('c')
+                .action(ArgAction::SetTrue)
+                .conflicts_with("files-with-matches")
+                .help("Print how many lines matched in each file"))
+            .arg(Arg::new("files-with-matches")
+                .long("files-with-matches")
+                .short('l')
+                .action(ArgAction::SetTrue)
+                .help("Only print the names of files with a match"))
+            .arg(Arg::new("after")
+                .long("after-context")
+                .short('A')
+                .value_parser(value_parser!(usize))
+                .value_name("#")
+                .num_args(1)
+                .help("Print # lines after each match"))
+            .arg(Arg::new("before")
+                .long("before-context")
+                .short('B')
+                .value_parser(value_parser!(usize))
+                .value_name("#")
+                .num_args(1)
+                .help("Print # lines before each match"))
+            .arg(Arg::new("context")
+                .long("context")
+                .short('C')
+                .value_parser(value_parser!(usize))
+                .value_name("#")
+                .num_args(1)
+                .help("Print # lines before and after each match"))
+            .arg(Arg::new("replace")
+                .long("replace")
+                .value_name("template")
+                .num_args(1)
+                .conflicts_with_all(["invert", "count", "files-with-matches"])
+                .help("Replace each match with the template and show the changes as a diff. With --regex, '$1' or '${name}' insert captures"))
+            .arg(Arg::new("write")
+                .long("write")
+                .action(ArgAction::SetTrue)
+                .requires("replace")
+                .help("Write the replacements to the files instead of only showing them"))
+            .arg(Arg::new("interactive")
+                .long("interactive")
+                .action(ArgAction::SetTrue)
+                .requires("replace")
+                .conflicts_with("write")
+                .help("Ask before writing each change"))
             .arg(Arg::new("pattern")
                 .value_parser(value_parser!(String))
                 .value_name("pattern")
                 .num_args(1)
                 .help("The pattern for DGREP to match against"))
-            .arg(Arg::new("filename")
-                .value_parser(value_parser!(String))
-                .value_name("filename")
-                .num_args(1)
-                .help("The file or directory passed to DGREP for it to search through for the given pattern")))
+            .arg(Arg::new("paths")
+                .value_parser(value_parser!(PathBuf))
+                .value_name("path")
+                .num_args(1..)
+                .help("The files or directories for DGREP to search through. Directories are searched recursively"))))
         .subcommand(Command::new("hpower")
             .about("Convert Horsepower to engine or to wheel.")
             .arg(Arg::new("value")
@@ -341,25 +739,75 @@ fn argument_parser() -> ArgMatches {
                 .num_args(1)
                 .help("Choose whether to convert from Horsepower (HP) or from Wheel Horsepower (WHP)")))
         .subcommand(Command::new("crypt")
-            .about("File Encryption and Decryption using a Passphrase")
-            .arg(Arg::new("option")
-                .long("option")
+            .about("File Encryption and Decryption with age, using a Passphrase or Public Keys. Files can be any kind of file")
+            .args_conflicts_with_subcommands(true)
+            .subcommand(Command::new("keygen")
+                .about("Generate an age identity (secret key). Its public key is printed for sharing with whoever encrypts to you")
+                .arg(Arg::new("output")
+                    .long("output")
+                    .short('o')
+                    .value_parser(value_parser!(PathBuf))
+                    .value_name("path")
+                    .num_args(1)
+                    .help("Write the identity to a file readable only by you instead of printing it")))
+            .arg(Arg::new("encrypt")
+                .long("encrypt")
+                .short('e')
+                .action(ArgAction::SetTrue)
+                .conflicts_with("decrypt")
+                .help("Encrypt the file. This is the default unless the file ends in '.age'"))
+            .arg(Arg::new("decrypt")
+                .long("decrypt")
+                .short('d')
+                .action(ArgAction::SetTrue)
+                .help("Decrypt the file. This is the default when the file ends in '.age'"))
+            .arg(Arg::new("output")
+                .long("output")
                 .short('o')
-                .value_parser(value_parser!(String))
+                .value_parser(value_parser!(PathBuf))
+                .value_name("path")
                 .num_args(1)
-                .help("Pass '-o e' to encrypt a file or pass '-o d' to decrypt a file"))
-            .arg(Arg::new("password")
-                .long("password")
-                .short('p')
+                .help("Where to write the result. Defaults to adding '.age' when encrypting, or '.davearc.age' for a directory, and removing it when decrypting"))
+            .arg(Arg::new("recipient")
+                .long("recipient")
+                .short('r')
                 .value_parser(value_parser!(String))
-                .num_args(1)
-                .value_name("passphrase")
-                .help("Provide a secure Passphrase for the hashing algoritm to use"))
+                .value_name("age1...")
+                .action(ArgAction::Append)
+                .conflicts_with("decrypt")
+                .help("Encrypt to a public key. Can be given more than once"))
+            .arg(Arg::new("recipients-file")
+                .long("recipients-file")
+                .short('R')
+                .value_parser(value_parser!(PathBuf))
+                .value_name("path")
+                .action(ArgAction::Append)
+                .conflicts_with("decrypt")
+                .help("Encrypt to every public key listed in a file, one per line"))
+            .arg(Arg::new("identity")
+                .long("identity")
+                .short('i')
+                .value_parser(value_parser!(PathBuf))
+                .value_name("path")
+                .action(ArgAction::Append)
+                .conflicts_with("encrypt")
+                .help("Decrypt with the secret keys in an identity file, like one made by 'dave crypt keygen'"))
+            .arg(Arg::new("archive")
+                .long("archive")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("encrypt")
+                .help("Restore the decrypted file as a directory. Only needed when an encrypted directory was renamed from '*.davearc.age'"))
+            .arg(Arg::new("armor")
+                .long("armor")
+                .short('a')
+                .action(ArgAction::SetTrue)
+                .conflicts_with("decrypt")
+                .help("Write PEM style text instead of binary, for pasting into chat or email. Decryption detects it automatically"))
             .arg(Arg::new("filename")
                 .value_parser(value_parser!(String))
                 .value_name("filename")
                 .num_args(1)
-                .help("The file or directory passed to DCRYPT for it to do all its crypty business with")))
+                .help("The file or directory passed to DCRYPT for it to do all its crypty business with. Directories are encrypted into a single archive. Without recipients or identities you'll be asked for a passphrase")))
         .subcommand(Command::new("scrape")
             .about("This program will scrape the internet for valuable and tasty information")
             .arg(Arg::new("weather")
@@ -527,11 +975,77 @@ fn argument_parser() -> ArgMatches {
                 .value_name("NOTE NAME")
                 .num_args(1)
                 .help("Add a new note"))
+            .arg(Arg::new("edit")
+                .long("edit")
+                .short('e')
+                .value_parser(value_parser!(u64))
+                .value_name("ID #")
+                .num_args(1)
+                .help("Edit an existing note. Combine with '--title', '--tag', '--priority' or '--due'"))
+            .arg(Arg::new("title")
+                .long("title")
+                .value_parser(value_parser!(String))
+                .value_name("NOTE NAME")
+                .num_args(1)
+                .requires("edit")
+                .help("The new title for a note being edited"))
+            .arg(Arg::new("tag")
+                .long("tag")
+                .short('t')
+                .value_parser(value_parser!(String))
+                .value_name("TAG")
+                .action(ArgAction::Append)
+                .help("Tag a note being added or edited. When listing, only show notes with this tag"))
+            .arg(Arg::new("priority")
+                .long("priority")
+                .short('p')
+                .value_parser(["low", "normal", "high"])
+                .value_name("PRIORITY")
+                .num_args(1)
+                .help("Set the priority of a note being added or edited"))
+            .arg(Arg::new("due")
+                .long("due")
+                .short('d')
+                .value_parser(value_parser!(String))
+                .value_name("YYYY-MM-DD")
+                .num_args(1)
+                .help("Set the due date of a note being added or edited"))
+            .arg(Arg::new("no-due")
+                .long("no-due")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("due")
+                .help("Remove the due date from a note being edited"))
+            .arg(Arg::new("delete")
+                .long("delete")
+                .value_parser(value_parser!(u64))
+                .value_name("ID #")
+                .num_args(1)
+                .help("Delete an existing note by passing the Note ID#"))
             .arg(Arg::new("list")
                 .long("list")
                 .short('l')
                 .action(ArgAction::SetTrue)
-                .help("List existing notes"))
+                .help("List existing notes, open notes first and sorted by due date and priority"))
+            .arg(Arg::new("overdue")
+                .long("overdue")
+                .action(ArgAction::SetTrue)
+                .help("When listing, only show open notes past their due date"))
+            .arg(Arg::new("done")
+                .long("done")
+                .action(ArgAction::SetTrue)
+                .help("When listing, only show completed notes"))
+            .arg(Arg::new("export")
+                .long("export")
+                .num_args(2)
+                .value_names(["FORMAT", "FILE"])
+                .value_parser(value_parser!(String))
+                .help("Export every note to a file. FORMAT is one of 'json', 'csv' or 'md' for a Markdown task list"))
+            .arg(Arg::new("import")
+                .long("import")
+                .num_args(2)
+                .value_names(["FORMAT", "FILE"])
+                .value_parser(value_parser!(String))
+                .help("Import notes from a 'json', 'csv' or 'md' file. Notes that already exist are not duplicated"))
             .arg(Arg::new("overwrite")
                 .long("overwrite")
                 .short('o')
@@ -543,7 +1057,14 @@ fn argument_parser() -> ArgMatches {
                 .value_parser(value_parser!(u64))
                 .value_name("ID #")
                 .num_args(1)
-                .help("Complete an existing note by passing the Note ID#, which can be found when running note with '--list'")))
+                .help("Complete an existing note by passing the Note ID#, which can be found when running note with '--list'"))
+            .arg(Arg::new("uncomplete")
+                .long("uncomplete")
+                .short('u')
+                .value_parser(value_parser!(u64))
+                .value_name("ID #")
+                .num_args(1)
+                .help("Mark a completed note as open again by passing the Note ID#")))
         .subcommand(Command::new("budget")
             .about("Budget your income and become WEALTHY. Thanks to Dave")
             .arg(Arg::new("new")
@@ -567,13 +1088,95 @@ fn argument_parser() -> ArgMatches {
                 .value_names(["expense", "amount"])
                 .value_parser(value_parser!(String))
                 .help("Subtract an expense from your budget. Input an expense and an amount"))
+            .arg(Arg::new("category")
+                .long("category")
+                .short('c')
+                .num_args(1)
+                .value_name("CATEGORY")
+                .value_parser(value_parser!(String))
+                .help("The category income added with '--income' is filed under. Defaults to 'income'"))
+            .arg(Arg::new("memo")
+                .long("memo")
+                .short('m')
+                .num_args(1)
+                .value_name("TEXT")
+                .value_parser(value_parser!(String))
+                .help("A note to attach to the new transaction or recurring rule"))
+            .arg(Arg::new("account")
+                .long("account")
+                .num_args(1)
+                .value_name("ACCOUNT")
+                .value_parser(value_parser!(String))
+                .help("The account the new transaction or recurring rule belongs to"))
+            .arg(Arg::new("date")
+                .long("date")
+                .short('d')
+                .num_args(1)
+                .value_name("YYYY-MM-DD")
+                .value_parser(value_parser!(String))
+                .help("The date of the new transaction, or the first date of a recurring rule. Defaults to today"))
+            .arg(Arg::new("limit")
+                .long("limit")
+                .num_args(2)
+                .value_names(["CATEGORY", "AMOUNT"])
+                .value_parser(value_parser!(String))
+                .help("Set a monthly spending limit for a category"))
+            .arg(Arg::new("recurring")
+                .long("recurring")
+                .num_args(3)
+                .value_names(["income/expense", "CATEGORY", "AMOUNT"])
+                .value_parser(value_parser!(String))
+                .help("Add income or an expense that repeats, starting on '--date'"))
+            .arg(Arg::new("every")
+                .long("every")
+                .num_args(1)
+                .default_value("monthly")
+                .value_parser(["weekly", "monthly", "yearly"])
+                .help("How often a recurring rule repeats"))
+            .arg(Arg::new("until")
+                .long("until")
+                .num_args(1)
+                .value_name("YYYY-MM-DD")
+                .value_parser(value_parser!(String))
+                .help("The last date a recurring rule applies to"))
+            .arg(Arg::new("import")
+                .long("import")
+                .num_args(1)
+                .value_name("FILE")
+                .value_parser(value_parser!(PathBuf))
+                .help("Import transactions from a CSV or OFX bank statement. Lines already in the budget are skipped"))
+            .arg(Arg::new("format")
+                .long("format")
+                .num_args(1)
+                .value_parser(["csv", "ofx"])
+                .help("The format of the statement passed to '--import'. Guessed from the file extension by default"))
+            .arg(Arg::new("map")
+                .long("map")
+                .num_args(1)
+                .value_name("FIELD=COLUMN")
+                .action(ArgAction::Append)
+                .value_parser(value_parser!(String))
+                .help("Override the CSV column used for date, payee, amount, debit, credit or memo, or set date_format or delimiter, for this import"))
+            .arg(Arg::new("rule")
+                .long("rule")
+                .num_args(2)
+                .value_names(["PATTERN", "CATEGORY"])
+                .value_parser(value_parser!(String))
+                .help("Save a rule to the config filing imported payees matching a regex under a category"))
+            .arg(Arg::new("report")
+                .long("report")
+                .short('r')
+                .num_args(1)
+                .value_name("YYYY-MM")
+                .value_parser(value_parser!(String))
+                .help("Show totals per category, variance against limits and the running balance for a month"))
             .arg(Arg::new("summary")
                 .long("summary")
                 .short('s')
                 .action(ArgAction::SetTrue)
                 .help("Prints a summary of the current budget to the screen")))
         .subcommand(Command::new("currency")
-            .about("Dave's implementation of a currency converter. Values as of 3-9-2024")
+            .about("Dave's implementation of a currency converter. Rates are read from the table in dave_conf/etc")
             .arg(Arg::new("option")
                 .long("option")
                 .short('o')
@@ -582,21 +1185,61 @@ fn argument_parser() -> ArgMatches {
                 .help("Pass '-o a' or '-o all' to see each nation that uses the currency specified"))
             .arg(Arg::new("amount")
                 .num_args(1)
-                .value_parser(value_parser!(f32))
+                .value_parser(parse_amount)
                 .value_name("VALUE")
                 .help("Pass an amount to convert into another world currency"))
             .arg(Arg::new("currency")
                 .num_args(1)
                 .value_name("CODE")
-                .value_parser(value_parser!(String))
-                .value_parser(["USD", "EUR", "GBP", "JPY", "CAD", "CNY", "AUD", "CHF", "SEK", "INR", "KRW", "NOK", "NZD", "RUB", "BRL", "SAR", "ILS", "DKK", "PLN", "MXN"])
+                .value_parser(|code: &str| parse_currency_code(code).map_err(|error| error.to_string()))
                 .help("Pass a three letter ISO 4217 currency code to indicate the starting currency"))
             .arg(Arg::new("convert")
                 .num_args(1)
                 .value_name("CODE")
-                .value_parser(value_parser!(String))
-                .value_parser(["USD", "EUR", "GBP", "JPY", "CAD", "CNY", "AUD", "CHF", "SEK", "INR", "KRW", "NOK", "NZD", "RUB", "BRL", "SAR", "ILS", "DKK", "PLN", "MXN"])
-                .help("Enter the three letter ISO 4217 currency code you wish to convert your intial amount to")))
+                .value_parser(|code: &str| parse_currency_code(code).map_err(|error| error.to_string()))
+                .help("Enter the three letter ISO 4217 currency code you wish to convert your intial amount to"))
+            .arg(Arg::new("rates")
+                .long("rates")
+                .action(ArgAction::SetTrue)
+                .help("Show every rate in the table and the date the rates are from"))
+            .arg(Arg::new("refresh")
+                .long("refresh")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("offline")
+                .help("Fetch rates from the configured provider now, even if the cached rates haven't expired"))
+            .arg(Arg::new("offline")
+                .long("offline")
+                .action(ArgAction::SetTrue)
+                .help("Use the rate table in dave_conf/etc without contacting the configured provider"))
+            .arg(Arg::new("date")
+                .long("date")
+                .short('d')
+                .num_args(1)
+                .value_name("YYYY-MM-DD")
+                .value_parser(value_parser!(chrono::NaiveDate))
+                .help("Convert using the rates stored in the history for this date"))
+            .arg(Arg::new("record")
+                .long("record")
+                .action(ArgAction::SetTrue)
+                .help("Store the rates in use, fetched or offline, in the history under their as-of date"))
+            .arg(Arg::new("chart")
+                .long("chart")
+                .num_args(2)
+                .value_names(["FROM", "TO"])
+                .value_parser(|code: &str| parse_currency_code(code).map_err(|error| error.to_string()))
+                .help("Draw a sparkline of how the rate between two currencies moved across the stored history"))
+            .arg(Arg::new("since")
+                .long("from")
+                .num_args(1)
+                .value_name("YYYY-MM-DD")
+                .value_parser(value_parser!(chrono::NaiveDate))
+                .help("The first date shown by '--chart'"))
+            .arg(Arg::new("until")
+                .long("to")
+                .num_args(1)
+                .value_name("YYYY-MM-DD")
+                .value_parser(value_parser!(chrono::NaiveDate))
+                .help("The last date shown by '--chart'")))
         .subcommand(Command::new("drm")
             .about("Calculate your max possible repetitions or weight lifted")
             .arg(Arg::new("option")
@@ -620,13 +1263,12 @@ fn argument_parser() -> ArgMatches {
         .get_matches()
 }
 
-fn update_config<'a>(matches: &ArgMatches) {
+fn update_config(matches: &ArgMatches) {
     // Setup Config
-    let mut writer = CONFIG.write().unwrap();
+    let mut config = CONFIG.read().unwrap().clone();
     if matches.get_flag("defaults") {
-        *writer = DaveConfig::default();
+        config = DaveConfig::default();
     }
-    let config: &mut DaveConfig = writer.borrow_mut();
 
     // Deal With Arguments Related to Output Paths
     if let Some(config_path) = matches.get_one::<String>("path") {
@@ -638,95 +1280,549 @@ fn update_config<'a>(matches: &ArgMatches) {
         config.set_budget_path(budget_path_buf);
     }
 
-    // Deal With Config Arguments That are Flags or Bools
+    // Deal With Determining Hashing Algorithm to Use
+    if let Some(hash_choice) = matches.get_one::<String>("hash-type") {
+        match HashType::from_str(hash_choice) {
+            Ok(hash_type) => config.hash_type = hash_type,
+            Err(error) => {
+                eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
+                process::exit(1)
+            },
+        }
+    }
 
     // Deal With Saving Config to Proper File and Location
     if matches.get_flag("save") {
-        let config_path = config.config_path();
-
-        if let Err(error) = config.save() {
-            eprintln!(
-                "##==>>>> ERROR: Unable to save configuration: {}: {}",
-                config_path.display(),
-                error,
-            );
-            std::process::exit(1)
+        let mut keys = Vec::new();
+        if matches.contains_id("bpath") {
+            keys.push("budget_file");
         }
-        println!(
-            "##==> Successfully Wrote Configuration to: {}",
-            config_path.display(),
-        );
-        std::process::exit(0)
-    }
-
-    // Deal With Determining Hashing Algorithm to Use
-    if let Some(hash_choice) = matches.get_one::<String>("hash-type") {
-        let hash_choice_parsed = HashType::from_str(hash_choice);
-        config.hash_type = hash_choice_parsed.unwrap();
+        if matches.contains_id("hash-type") {
+            keys.push("hash_type");
+        }
+        save_config(&config, &keys, matches.get_flag("defaults"));
     }
+    *CONFIG.write().unwrap() = config;
 }
 
-fn print_startup_message() {
+// Write the Given Settings to the Config's config_path, Starting the
+// File Over When Asked, and Exit if That Fails. Only These Settings Are
+// Written, so Project, Environment and --override Values Stay Out
+fn save_config(config: &DaveConfig, keys: &[&str], fresh: bool) {
+    let config_path = config.config_path();
+    let saved = match fresh {
+        true => DaveConfig::reset_file(&config_path),
+        false => Ok(()),
+    }
+    .and_then(|_| config.save_settings(keys));
+    if let Err(error) = saved {
+        eprintln!(
+            "{}Unable to save configuration: {}: {}",
+            "##==>>>> ERROR: ".red(),
+            config_path.display(),
+            error,
+        );
+        process::exit(1)
+    }
     println!(
-        "##==> Dave Version: {}, Release: {}, Patchlevel: {} ({})",
-        VERSION[0],
-        VERSION[1],
-        VERSION[2],
-        BUILD_DATE,
+        "##==> Successfully Wrote Configuration to: {}",
+        config_path.display(),
     );
-    println!();
 }
 
-#[cfg(not(windows))]
-fn setup_terminal() -> std::io::Result<()> {
-    Ok(())
+fn show_setting(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
 }
 
-#[cfg(windows)]
-fn setup_terminal() -> std::io::Result<()> {
-    control::set_virtual_terminal(true).unwrap();
-    Ok(())
+// Pull key=value Pairs Out of Every --override
+fn config_overrides(matches: &ArgMatches) -> Vec<(String, String)> {
+    let mut overrides = Vec::new();
+    for setting in matches.get_many::<String>("override").into_iter().flatten() {
+        match setting.split_once('=') {
+            Some((key, value)) => overrides.push((key.trim().to_string(), value.to_string())),
+            None => {
+                eprintln!("{}Expected key=value, Found '{}'", "##==>>>> ERROR: ".red(), setting);
+                process::exit(1)
+            },
+        }
+    }
+    overrides
 }
 
-fn main() {
-    let start = Instant::now();
-    // Print Program Startup Message
-    print_startup_message();
-
-    // Check Current OS to Determine Colored Terminal Output
-    println!(
-        "##==> INFO! Found Operating System '{}'. Configuring Terminal Environment ...",
-        env::consts::OS,
-    );
-    if let Err(error) = setup_terminal() {
+// Deal With 'dave config list/get/set/unset/validate'
+fn config_command(subcommand: &str, matches: &ArgMatches, overrides: &[(String, String)]) {
+    let mut config = CONFIG.read().unwrap().clone();
+    let key = matches.try_get_one::<String>("key").ok().flatten().map(String::as_str).unwrap_or_default();
+    let config_path = config.config_path();
+    let result = match subcommand {
+        "list" => {
+            for (key, value) in config.entries() {
+                println!("{} = {}", key, show_setting(&value));
+            }
+            Ok(())
+        },
+        "get" => config.get(key).map(|value| println!("{}", show_setting(&value))),
+        // Only the Changed Setting Is Written, so Values From Other Layers Stay Where They Are
+        "set" => config
+            .set(key, matches.get_one::<String>("value").unwrap())
+            .and_then(|_| config.get(key))
+            .and_then(|value| DaveConfig::write_setting(&config_path, key, Some(value))),
+        "unset" => config
+            .get(key)
+            .and_then(|_| DaveConfig::write_setting(&config_path, key, None)),
+        "validate" => {
+            let path = matches.get_one::<PathBuf>("file").cloned().unwrap_or_else(|| config.config_path());
+            let problems = match DaveConfig::validate_file(&path) {
+                Ok((_, problems)) => problems,
+                Err(error) => vec![("config".to_string(), error.to_string())],
+            };
+            for (key, problem) in &problems {
+                eprintln!("{}{}: {}", "##==>>>> ERROR: ".red(), key, problem);
+            }
+            if !problems.is_empty() {
+                process::exit(1)
+            }
+            println!("##==>> {} Is Valid", path.display());
+            Ok(())
+        },
+        _ => unreachable!(),
+    };
+    if let Err(error) = result {
         eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
+        process::exit(1)
     }
-    println!();
 
-    // Setup Files Necessary for Output
-    let mut file_options = OpenOptions::new();
-    file_options.write(true);
-    file_options.create(true);
+    if subcommand == "set" || subcommand == "unset" {
+        println!("##==> Successfully Wrote Configuration to: {}", config_path.display());
+        *CONFIG.write().unwrap() = DaveConfig::resolve(overrides).config;
+    }
+}
 
-    // Create Files That Will Have Important Data Written to Them
-    let _output_file = match file_options.append(true).open(find_output_file()) {
-        Ok(output_file) => output_file,
-        Err(error) => {
-            eprintln!("##==>>>> ERROR: {}: {}", find_output_file().display(), error);
-            return
+// Show Each Setting Beside the Layer It Came From
+fn explain_config(overrides: &[(String, String)]) {
+    let resolved = DaveConfig::resolve(overrides);
+    for (key, value) in resolved.config.entries() {
+        println!("{} = {}  {}", key, show_setting(&value), format!("({})", resolved.source(&key)).dimmed());
+    }
+    for warning in &resolved.warnings {
+        println!("##==>> Warning! {}", warning);
+    }
+}
+
+// Deal With 'dave calc --compound/--loan/--goal'. Values Not Given as
+// Flags Are Asked For, so Scripts Pass Them All
+fn finance_command(matches: &ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let number = |name: &str, question: &str| -> io::Result<f64> {
+        match matches.get_one::<f64>(name) {
+            Some(value) => Ok(*value),
+            None => prompt_number(question, None),
         }
     };
+    let frequency = |name: &str| Period::from_str(matches.get_one::<String>(name).unwrap());
+    let csv_path = matches.get_one::<PathBuf>("csv").map(PathBuf::as_path);
 
-    // Parse CLI Args
-    let matches = argument_parser();
+    if matches.get_flag("loan") {
+        let loan = Loan {
+            principal: number("principal", "Enter the amount borrowed")?,
+            annual_rate: number("rate", "Enter the yearly interest rate (%)")?,
+            years: number("years", "Enter the loan term (years)")?,
+            frequency: frequency("every")?,
+            extra_payment: *matches.get_one::<f64>("extra").unwrap(),
+            start: matches.get_one::<chrono::NaiveDate>("start").copied().unwrap_or_else(|| chrono::Local::now().date_naive()),
+        };
+        return print_loan(&loan, csv_path)
+    }
 
-    // Get Important Data From Config
-    let reader = CONFIG.read().unwrap();
+    let principal = match matches.get_one::<f64>("goal") {
+        Some(_) => matches.get_one::<f64>("principal").copied().unwrap_or(0.0),
+        None => number("principal", "Enter the amount of money earning interest")?,
+    };
+    let savings = Savings {
+        principal,
+        annual_rate: number("rate", "Enter the yearly interest rate (%)")?,
+        years: number("years", "Enter the number of years")?,
+        compounding: frequency("compounding")?,
+        contribution: *matches.get_one::<f64>("contribution").unwrap(),
+        contribution_frequency: frequency("every")?,
+    };
+    match matches.get_one::<f64>("goal") {
+        Some(target) => print_goal(&savings, *target, csv_path),
+        None => print_compound(&savings, csv_path),
+    }
+}
 
-    // Deal With Passed Subcommands and Their Arguments
-    match matches.subcommand() {
+// Make a Path Absolute Against the Working Directory, so a Config
+// Written Now Still Points at the Right Place From Anywhere Else
+fn absolute_path(path: &Path) -> PathBuf {
+    std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+// Deal With 'dave setup'
+fn setup_command(matches: &ArgMatches) -> io::Result<()> {
+    let prefix = absolute_path(&matches.get_one::<PathBuf>("prefix").cloned().unwrap_or_else(find_install_root));
+    let config_path = matches
+        .get_one::<PathBuf>("config")
+        .cloned()
+        .or_else(user_config_path)
+        .unwrap_or_else(|| prefix.join("etc").join(CONF_FILE));
+    let mut config = DaveConfig::for_root(&prefix);
+    config.set_config_path(absolute_path(&config_path));
+    let source = matches
+        .get_one::<PathBuf>("from")
+        .cloned()
+        .or_else(|| Some(PathBuf::from(ROOT_PATH)).filter(|path| path.is_dir()));
+    if let Some(source) = source.as_ref().filter(|source| !source.is_dir()) {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("{} Is Not a Directory", source.display())))
+    }
+
+    let steps = plan_setup(&config, source.as_deref())?;
+    if steps.is_empty() {
+        println!("##==>> Dave Is Already Set Up in {}", prefix.display());
+    } else {
+        println!("##==> Setup Will:");
+        for step in &steps {
+            println!("    {}", step);
+        }
+        if matches.get_flag("dry-run") {
+            return Ok(())
+        }
+        if !matches.get_flag("yes") && !confirm("##==> Continue?")? {
+            println!("##==>> Setup Cancelled");
+            return Ok(())
+        }
+        apply_setup(&config, &steps)?;
+        println!("##==> Successfully Set Up Dave in {}", prefix.display());
+    }
+
+    // An Existing Config Is Never Replaced, but Say if It Points Elsewhere
+    if !steps.contains(&SetupStep::WriteConfig(config.config_path())) {
+        if let Ok((existing, _)) = DaveConfig::validate_file(&config.config_path()) {
+            if absolute_path(&existing.root_path()) != prefix {
+                println!(
+                    "##==>> Warning! {} Uses root_path {}. Run 'dave config set root_path {}' to Use This Installation",
+                    config.config_path().display(),
+                    existing.root_path().display(),
+                    prefix.display(),
+                );
+                // Paths Set in the File Don't Follow root_path
+                let pinned = DaveConfig::pinned_paths(&config.config_path()).unwrap_or_default();
+                if !pinned.is_empty() {
+                    println!(
+                        "##==>> Warning! It Also Sets {}. Change Them Too, or 'dave config unset' Them to Follow root_path",
+                        pinned.join(", "),
+                    );
+                }
+            }
+        }
+    }
+    let missing = verify_install(&config);
+    for path in &missing {
+        println!("##==>> Warning! Missing {}", path.display());
+    }
+    if !missing.is_empty() {
+        println!("##==>> Warning! {} Data Files Are Missing. Run Setup Again With --from Pointing at a dave_conf Directory", missing.len());
+    }
+    Ok(())
+}
+
+// Deal With 'dave uninstall'
+fn uninstall_command(reader: &DaveConfig, matches: &ArgMatches) -> io::Result<()> {
+    let config = match matches.get_one::<PathBuf>("prefix") {
+        Some(prefix) if absolute_path(prefix) != absolute_path(&reader.root_path()) => {
+            let mut config = DaveConfig::for_root(prefix);
+            config.set_config_path(prefix.join("etc").join(CONF_FILE));
+            config
+        },
+        _ => reader.clone(),
+    };
+    let steps = uninstall_steps(&config, matches.get_flag("purge"))?;
+    println!("##==> Uninstalling Will:");
+    for step in &steps {
+        match step {
+            UninstallStep::RemoveTree(path) => {
+                let size: u64 = walkdir::WalkDir::new(path)
+                    .into_iter()
+                    .filter_map(Result::ok)
+                    .filter_map(|entry| entry.metadata().ok())
+                    .filter(|metadata| metadata.is_file())
+                    .map(|metadata| metadata.len())
+                    .sum();
+                println!("    {} ({})", step, ByteSize(size));
+            },
+            _ => println!("    {}", step),
+        }
+    }
+    if matches.get_flag("dry-run") {
+        return Ok(())
+    }
+    if !matches.get_flag("yes") && !confirm("##==> Remove These?")? {
+        println!("##==>> Uninstall Cancelled");
+        return Ok(())
+    }
+    for kept in apply_uninstall(&steps)? {
+        println!("##==>> Kept {}, which has files setup didn't install", kept.display());
+    }
+    println!("##==> Successfully Uninstalled Dave From {}", config.root_path().display());
+    if !matches.get_flag("purge") {
+        println!("##==>> Kept {}. Run Again With --purge to Remove It", config.root_path().join("var").display());
+    }
+    Ok(())
+}
+
+// Move the Budget and Notes Under a Freshly Generated Vault Key. Turns
+// Encryption On the First Time, and Changes the Key Afterwards
+fn rekey_store(current: &DaveConfig, matches: &ArgMatches) {
+    let fail = |error: &dyn std::fmt::Display| -> ! {
+        eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
+        process::exit(1)
+    };
+    let rekey = matches.get_flag("rekey");
+    if rekey && !current.encryption.enabled {
+        fail(&"Encryption Isn't Turned On. Use 'dave config --encrypt' First");
+    }
+    if !rekey && current.encryption.enabled {
+        fail(&"Already Encrypted. Use 'dave config --rekey' to Change the Key");
+    }
+
+    // Read Everything With the Current Key Before Anything Changes
+    let current_vault = Vault::from_config(current);
+    let clone_vault = || match current_vault.as_ref().map(Vault::try_clone).transpose() {
+        Ok(vault) => vault,
+        Err(error) => fail(&error),
+    };
+    let budget_vault = clone_vault();
+    // Kept to Put the Budget Back if Resealing Fails
+    let old_vault = clone_vault();
+    let budget = if current.budget_path().exists() {
+        match BudgetStore::new(current.budget_path()).with_vault(budget_vault).load() {
+            Ok(budget) => Some(budget),
+            Err(error) => fail(&error),
+        }
+    } else {
+        None
+    };
+    let mut db = match DaveDatabase::open_at(current.database_path()) {
+        Ok(db) => db.with_vault(current_vault),
+        Err(error) => fail(&error),
+    };
+
+    let mut config = current.clone();
+    config.encryption.enabled = true;
+    config.encryption.identity = matches.get_one::<PathBuf>("identity").cloned();
+    if config.encryption.identity.is_none() {
+        println!("##==> Choose a Passphrase for Encrypted Data");
+    }
+    let (lock, _) = match vault_lock(config.encryption.identity.as_deref(), true) {
+        Ok(lock) => lock,
+        Err(error) => fail(&error),
+    };
+
+    // Forget Any Unlocked Key First so Nothing Holds the Old One
+    if let Some(session) = SessionCache::for_config(current) {
+        if let Err(error) = session.clear() {
+            fail(&error);
+        }
+    }
+    if let Err(error) = SessionCache::new(SessionCache::legacy_path(current), 0).clear() {
+        fail(&error);
+    }
+
+    // The Old Key Is Backed Up, Then the New Key and Config Are Saved
+    // Before Any Data Is Resealed. If Resealing Fails It's All Put Back
+    let key = age::x25519::Identity::generate();
+    let key_path = config.vault_key_path();
+    let old_key = match std::fs::read(&key_path) {
+        Ok(bytes) => Some(bytes),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => None,
+        Err(error) => fail(&error),
+    };
+    // Only the Encryption Settings Are Written to the User's Config, and
+    // Rolling Back Puts Back Just What the File Said Before
+    let encryption_keys = ["encryption.enabled", "encryption.identity"];
+    let config_path = current.config_path();
+    let mut previous = Vec::new();
+    for key in encryption_keys {
+        match DaveConfig::read_setting(&config_path, key) {
+            Ok(value) => previous.push((key, value)),
+            Err(error) => fail(&error),
+        }
+    }
+    let backup = old_key.as_ref().map(|bytes| {
+        let mut backup_name = key_path.as_os_str().to_owned();
+        backup_name.push(format!(".{}.old", chrono::Local::now().format("%Y%m%d%H%M%S")));
+        let backup = PathBuf::from(backup_name);
+        let written = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&backup)
+            .and_then(|mut file| io::Write::write_all(&mut file, bytes).and_then(|_| file.sync_all()));
+        if let Err(error) = written {
+            fail(&format!("{}: {}", backup.display(), error));
+        }
+        backup
+    });
+    let roll_back = |error: &dyn std::fmt::Display, budget_saved: bool| -> ! {
+        let mut restored = match &old_key {
+            Some(bytes) => davelib::dave_store::write_atomic(&key_path, bytes),
+            None => std::fs::remove_file(&key_path),
+        }
+        .map_err(|error| error.to_string())
+        .and_then(|_| DaveConfig::write_settings(&config_path, &previous).map_err(|error| error.to_string()));
+        if let (true, Some(budget)) = (budget_saved, &budget) {
+            restored = restored.and_then(|_| {
+                let vault = old_vault.as_ref().map(Vault::try_clone).transpose().map_err(|error| error.to_string())?;
+                BudgetStore::new(current.budget_path()).with_vault(vault).save(budget).map_err(|error| error.to_string())
+            });
+        }
+        match (restored, &backup) {
+            (Ok(()), _) => fail(&format!("{}. Everything Was Put Back Under the Old Key", error)),
+            (Err(restore_error), Some(backup)) => fail(&format!(
+                "{}. Putting Things Back Also Failed ({}). The Old Key Is Kept in {}",
+                error,
+                restore_error,
+                backup.display(),
+            )),
+            (Err(restore_error), None) => fail(&format!("{}. Putting Things Back Also Failed ({})", error, restore_error)),
+        }
+    };
+    if let Err(error) = Vault::with_key(&key_path, key.clone()).write_key_file(&lock) {
+        roll_back(&error, false);
+    }
+    if let Err(error) = config.save_settings(&encryption_keys) {
+        roll_back(&error, false);
+    }
+    if let Some(budget) = &budget {
+        let store = BudgetStore::new(config.budget_path()).with_vault(Some(Vault::with_key(&key_path, key.clone())));
+        if let Err(error) = store.save(budget) {
+            roll_back(&error, false);
+        }
+    }
+    let resealed = match db.reseal(Some(Vault::with_key(&key_path, key.clone()))) {
+        Ok(resealed) => resealed,
+        Err(error) => roll_back(&error, true),
+    };
+    if let Some(backup) = &backup {
+        if let Err(error) = std::fs::remove_file(backup) {
+            println!("##==>> Warning! Old Key Backup {} Could Not be Removed: {}", backup.display(), error);
+        }
+    }
+    let vault = Vault::with_key(&key_path, key);
+    match SessionCache::for_config(&config) {
+        Some(session) => {
+            if let Err(error) = vault.remember(&session) {
+                println!("##==>> Warning! Unlocked Key Could Not be Remembered: {}", error);
+            }
+        },
+        None => println!("##==>> No Runtime Directory (XDG_RUNTIME_DIR), so You'll be Asked to Unlock Each Time"),
+    }
+    println!(
+        "##==>> Encrypted the Budget and {} Database Record(s). Key Kept in {}",
+        resealed,
+        key_path.display(),
+    );
+}
+
+// Startup and Timing Chatter Goes to stderr so stdout Only Carries What
+// Was Asked For, Such as 'dave calc --csv -'
+fn print_startup_message() {
+    eprintln!(
+        "##==> Dave Version: {}, Release: {}, Patchlevel: {} ({})",
+        VERSION[0],
+        VERSION[1],
+        VERSION[2],
+        BUILD_DATE,
+    );
+    eprintln!();
+}
+
+#[cfg(not(windows))]
+fn setup_terminal() -> std::io::Result<()> {
+    Ok(())
+}
+
+#[cfg(windows)]
+fn setup_terminal() -> std::io::Result<()> {
+    control::set_virtual_terminal(true).unwrap();
+    Ok(())
+}
+
+fn main() {
+    let start = Instant::now();
+    // Print Program Startup Message
+    print_startup_message();
+
+    // Check Current OS to Determine Colored Terminal Output
+    eprintln!(
+        "##==> INFO! Found Operating System '{}'. Configuring Terminal Environment ...",
+        env::consts::OS,
+    );
+    if let Err(error) = setup_terminal() {
+        eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
+    }
+    eprintln!();
+
+    // Parse CLI Args
+    let matches = argument_parser();
+
+    // Layer the Config, Then Apply Any --override Settings
+    let overrides = config_overrides(&matches);
+    *CONFIG.write().unwrap() = DaveConfig::load_with(&overrides);
+
+    // Get Important Data From Config
+    // A Copy, Not a Guard, so Commands Can Still Update the Config
+    let reader = CONFIG.read().unwrap().clone();
+
+    // Setup Files Necessary for Output
+    let mut file_options = OpenOptions::new();
+    file_options.write(true);
+    file_options.create(true);
+
+    // Create Files That Will Have Important Data Written to Them
+    // Before 'dave setup' Has Run There's Nowhere to Put It Yet
+    let _output_file = match file_options.append(true).open(reader.output_file()) {
+        Ok(output_file) => Some(output_file),
+        Err(error) => {
+            eprintln!("##==>> Warning! {}: {}. Run 'dave setup' to Create It", reader.output_file().display(), error);
+            None
+        }
+    };
+
+    // Deal With Passed Subcommands and Their Arguments
+    match matches.subcommand() {
         Some(("config", matches)) => {
-            update_config(&matches);
+            if let Some((subcommand, matches)) = matches.subcommand() {
+                config_command(subcommand, matches, &overrides);
+            } else if matches.get_flag("explain") {
+                explain_config(&overrides);
+            } else if matches.get_flag("encrypt") || matches.get_flag("rekey") {
+                rekey_store(&reader, matches);
+            } else if matches.get_flag("lock") {
+                let legacy = SessionCache::new(SessionCache::legacy_path(&reader), 0);
+                let cleared = SessionCache::for_config(&reader)
+                    .map_or(Ok(()), |session| session.clear())
+                    .and_then(|_| legacy.clear());
+                match cleared {
+                    Ok(()) => println!("##==>> Encrypted Data Locked"),
+                    Err(error) => eprintln!("{}{}", "##==>>>> ERROR: ".red(), error),
+                }
+            } else {
+                update_config(&matches);
+            }
+        },
+        Some(("setup", matches)) => {
+            if let Err(error) = setup_command(matches) {
+                eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
+                process::exit(1)
+            }
+        },
+        Some(("uninstall", matches)) => {
+            if let Err(error) = uninstall_command(&reader, matches) {
+                eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
+                process::exit(1)
+            }
         },
         Some(("dave-ed", matches)) => {
             if let Some(gotten_file) = matches.get_one::<String>("load") {
@@ -747,9 +1843,18 @@ fn main() {
         },
         Some(("snake", _matches)) => {
             print!("{esc}c", esc = 27 as char);
-            if let Err(error) = Game::new(stdout(), 15, 10).unwrap().run() {
+            let mut game = Game::new(stdout(), 15, 10).unwrap();
+            if let Err(error) = game.run() {
                 eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
             }
+            // Keep Track of the Best Snake Score
+            let record = DaveDatabase::open()
+                .and_then(|db| db.record_score(DaveTree::HighScores, "snake", game.score() as u64));
+            match record {
+                Ok(record) if record.is_new_best() => println!("{}", "!!! New High Score !!!".yellow()),
+                Ok(record) => println!("##==>> High Score: {}", record.best),
+                Err(error) => eprintln!("{}{}", "##==>>>> ERROR: ".red(), error),
+            }
         },
         Some(("breakout", _matches)) => {
             if let Err(error) = dave_breakout_main() {
@@ -816,6 +1921,7 @@ fn main() {
                         gotten_pattern.to_string(),
                         Path::new(gotten_file),
                         verbose,
+                        &search_options(matches),
                     ) {
                         eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
                     }
@@ -978,7 +2084,16 @@ fn main() {
             }
         },
         Some(("calc", matches)) => {
-            if matches.get_flag("simple") {
+            if let Some(expression) = matches.get_many::<String>("expression") {
+                let line = expression.cloned().collect::<Vec<_>>().join(" ");
+                match Calculator::new().evaluate(&line) {
+                    Ok(value) => println!("{}", format_number(value)),
+                    Err(error) => {
+                        print_calc_error(&line, &error);
+                        process::exit(1)
+                    },
+                }
+            } else if matches.get_flag("simple") {
                 if let Err(error) = dave_simple_calc_loop() {
                     eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
                 }
@@ -990,6 +2105,13 @@ fn main() {
                 if let Err(error) = dave_interest_calc_loop() {
                     eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
                 }
+            } else if matches.get_flag("compound") || matches.get_flag("loan") || matches.contains_id("goal") {
+                if let Err(error) = finance_command(matches) {
+                    eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
+                    process::exit(1)
+                }
+            } else if let Err(error) = dave_calc_repl() {
+                eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
             }
         },
         Some(("hpower", matches)) => {
@@ -1128,7 +2250,12 @@ fn main() {
 
             // Get File and File Contents From User
             if let Some(passed_rom) = matches.get_one::<String>("filename") {
-                let path = Path::new(passed_rom);
+                // Bare ROM Names Are Looked Up in the Configured ROMs Directory
+                let mut path = PathBuf::from(passed_rom);
+                if !path.exists() && reader.roms_path().join(passed_rom).exists() {
+                    path = reader.roms_path().join(passed_rom);
+                }
+                let path = path.as_path();
                 if !path.exists() {
                     let bad_path_string = format!("##==>>>> ERROR: Invalid Path - '{}'", path.display());
                     eprintln!("{}", bad_path_string.red());
@@ -1195,145 +2322,222 @@ fn main() {
                     },
                 };
             }
-            if matches.get_flag("animals") {
+            let quiz_choice = if matches.get_flag("animals") {
                 println!("{}", "^^^ David's Animal Quiz ^^^\n".green());
-                let quiz_choice = "animals".to_string();
-                if let Err(error) = dave_quiz(quiz_choice, total_questions, chosen_difficulty) {
-                    eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
-                }
+                "animals"
             } else if matches.get_flag("strek") {
                 println!("{}", "*** David's Star Trek Quiz ***\n".yellow());
-                let quiz_choice = "strek".to_string();
-                if let Err(error) = dave_quiz(quiz_choice, total_questions, chosen_difficulty) {
-                    eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
-                }
+                "strek"
             } else if matches.get_flag("swars") {
                 println!("{}", "### David's Star Wars Quiz ###\n".yellow());
-                let quiz_choice = "swars".to_string();
-                if let Err(error) = dave_quiz(quiz_choice, total_questions, chosen_difficulty) {
-                    eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
-                }
+                "swars"
             } else if matches.get_flag("mil-av") {
                 println!("{}", "<<< David's Military Aviation Quiz >>>\n".yellow());
-                let quiz_choice = "mil-av".to_string();
-                if let Err(error) = dave_quiz(quiz_choice, total_questions, chosen_difficulty) {
-                    eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
-                }
+                "mil-av"
             } else if matches.get_flag("gen-av") {
                 println!("{}", ">>> David's General Aviation Quiz <<<\n".yellow());
-                let quiz_choice = "gen-av".to_string();
-                if let Err(error) = dave_quiz(quiz_choice, total_questions, chosen_difficulty) {
-                    eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
-                }
+                "gen-av"
             } else {
                 println!(
                     "##==> A valid quiz must be chosen. Try running 'dave quiz --help' for more information",
                 );
+                return
+            };
+            match dave_quiz(quiz_choice.to_string(), total_questions, chosen_difficulty) {
+                Ok(score) => {
+                    // Keep Track of the Best Score for Each Quiz
+                    let record = DaveDatabase::open()
+                        .and_then(|db| db.record_score(DaveTree::QuizScores, quiz_choice, score as u64));
+                    match record {
+                        Ok(record) => println!("##==>> Best Score: {} ({} Attempts)", record.best, record.attempts),
+                        Err(error) => eprintln!("{}{}", "##==>>>> ERROR: ".red(), error),
+                    }
+                },
+                Err(error) => eprintln!("{}{}", "##==>>>> ERROR: ".red(), error),
             }
         },
         Some(("budget", matches)) => {
-            let mut budget_file = match file_options.append(false).open(find_budget_file()) {
-                Ok(budget_file) => budget_file,
-                Err(error) => {
-                    eprintln!(
-                        "##==>>>> ERROR: {}: {}",
-                        find_budget_file().display(),
-                        error
-                    );
-                    std::process::exit(1)
-                }
-            };
-            let budget_path = reader.budget_path();
+            let store = BudgetStore::new(reader.budget_path()).with_vault(Vault::from_config(&reader));
+            let today = chrono::Local::now().date_naive();
 
             if matches.get_flag("new") {
-                // Remove Budget File and Create New One
-                // Until I Can Figure Out Extra Chars Problem
-                fs::remove_file(budget_path.clone()).unwrap();
-                let mut budget_file = match file_options.append(false).open(find_budget_file()) {
-                    Ok(budget_file) => budget_file,
-                    Err(error) => {
-                        eprintln!(
-                            "##==>>>> ERROR: {}: {}",
-                            find_budget_file().display(),
-                            error
-                        );
-                        std::process::exit(1)
-                    }
-                };
                 // Create New Budget Object and Write to Budget File
-                let budget = DaveBudget::new();
-                if let Err(error) = write!(budget_file, "{}", serde_json::to_string(&budget).unwrap()) {
+                if let Err(error) = store.save(&DaveBudget::new()) {
                     eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
                     std::process::exit(1)
                 }
                 println!("##==>> New Budget Created!");
             }
+
+            // Load the Budget, Migrating Older Budgets to the Ledger
+            let mut budget = match store.load() {
+                Ok(budget) => budget,
+                Err(error) => {
+                    eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
+                    std::process::exit(1)
+                },
+            };
+            let mut changed = false;
+
+            // Details Shared by Every New Entry
+            let date = match matches.get_one::<String>("date").map(|date| parse_budget_date(date)) {
+                Some(Ok(date)) => date,
+                Some(Err(error)) => {
+                    eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
+                    std::process::exit(1)
+                },
+                None => today,
+            };
+            let memo = matches.get_one::<String>("memo").map_or("", |memo| memo.as_str());
+            let account = matches.get_one::<String>("account").map_or(DEFAULT_ACCOUNT, |account| account.as_str());
+
             if let Some(income_amount) = matches.get_one::<f64>("income") {
-                // Get JSON String From Budget File
-                let budget_file_string: String = fs::read_to_string(budget_path.clone()).unwrap().parse().unwrap();
-                // Have Serde Deserialize It Into Budget Object
-                let mut budget:DaveBudget = match serde_json::from_str(&budget_file_string) {
-                    Ok(budget) => budget,
+                let category = matches.get_one::<String>("category").map_or("income", |category| category.as_str());
+                budget.add_income(date, *income_amount, category, memo, account);
+                changed = true;
+            }
+            if let Some(mut values) = matches.get_many::<String>("expense") {
+                let tag = values.next().unwrap();
+                let amount = match values.next().unwrap().parse::<f64>() {
+                    Ok(amount) => amount,
                     Err(error) => {
                         eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
                         std::process::exit(1)
                     },
                 };
-                // Add Income and Update by Writing to Budget File
-                budget.add_income(*income_amount);
-                if let Err(error) = write!(budget_file, "{}", serde_json::to_string(&budget).unwrap()) {
-                    eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
-                    std::process::exit(1)
+                println!("Expense: {}, Amount: {}", tag, amount);
+                budget.add_expense(date, amount, tag, memo, account);
+                changed = true;
+            }
+            if let Some(mut values) = matches.get_many::<String>("limit") {
+                let category = values.next().unwrap();
+                match values.next().unwrap().parse::<f64>() {
+                    Ok(amount) => {
+                        budget.set_limit(category, amount);
+                        println!("##==>> Monthly Limit for {} Set to ${:.2}", category, amount);
+                        changed = true;
+                    },
+                    Err(error) => eprintln!("{}{}", "##==>>>> ERROR: ".red(), error),
                 }
-                println!("##==>> Budget Updated!");
             }
-            if let Some(mut values) = matches.get_many::<String>("expense") {
-                // Get JSON String From Budget File
-                let budget_file_string: String = fs::read_to_string(budget_path.clone()).unwrap().parse().unwrap();
-                // Have Serde Deserialize It Into Budget Object
-                let mut budget:DaveBudget = match serde_json::from_str(&budget_file_string) {
-                    Ok(budget) => budget,
+            if let Some(mut values) = matches.get_many::<String>("recurring") {
+                let kind = TransactionKind::from_str(values.next().unwrap());
+                let category = values.next().unwrap();
+                let amount = values.next().unwrap().parse::<f64>().map_err(|error| error.to_string());
+                let frequency = Frequency::from_str(matches.get_one::<String>("every").unwrap());
+                let end = matches.get_one::<String>("until").map(|until| parse_budget_date(until)).transpose();
+                match (kind, amount, frequency, end) {
+                    (Ok(kind), Ok(amount), Ok(frequency), Ok(end)) => {
+                        budget.add_recurring(RecurringRule {
+                            kind,
+                            amount: amount.abs(),
+                            category: category.to_string(),
+                            memo: memo.to_string(),
+                            account: account.to_string(),
+                            frequency,
+                            start: date,
+                            end,
+                            posted: 0,
+                        });
+                        println!("##==>> Recurring {} for {} Added", kind, category);
+                        changed = true;
+                    },
+                    (Err(error), _, _, _) | (_, Err(error), _, _) | (_, _, Err(error), _) | (_, _, _, Err(error)) => {
+                        eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
+                    },
+                }
+            }
+
+            let mut rules = reader.import.rules.clone();
+            if let Some(mut values) = matches.get_many::<String>("rule") {
+                let pattern = values.next().unwrap();
+                let category = values.next().unwrap();
+                let rule = CategoryRule {
+                    pattern: pattern.to_string(),
+                    category: category.to_string(),
+                };
+                rules.push(rule.clone());
+                // The Rule Joins Those in the User's Own File, Not Ones
+                // From a Project File or the Environment
+                let config_path = reader.config_path();
+                let saved = Categorizer::new(&rules)
+                    .map_err(|error| error.to_string())
+                    .and_then(|_| DaveConfig::read_setting(&config_path, "import.rules").map_err(|error| error.to_string()))
+                    .and_then(|own| {
+                        let mut own: Vec<CategoryRule> = match own {
+                            Some(own) => serde_json::from_value(own).map_err(|error| error.to_string())?,
+                            None => Vec::new(),
+                        };
+                        own.push(rule);
+                        let own = serde_json::to_value(own).map_err(|error| error.to_string())?;
+                        DaveConfig::write_setting(&config_path, "import.rules", Some(own)).map_err(|error| error.to_string())
+                    });
+                match saved {
+                    Ok(()) => println!("##==>> Payees Matching '{}' Will be Filed Under {}", pattern, category),
                     Err(error) => {
                         eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
                         std::process::exit(1)
                     },
+                }
+            }
+            if let Some(statement_path) = matches.get_one::<PathBuf>("import") {
+                // Read and Categorise the Statement Before Touching the Budget
+                let mut columns = reader.import.csv.clone();
+                let format = match matches.get_one::<String>("format") {
+                    Some(format) => StatementFormat::from_str(format),
+                    None => StatementFormat::from_path(statement_path),
                 };
-                let tag = match values.next() {
-                    Some(tag) => tag,
-                    None => { std::process::exit(1) },
-                };
-                let amount = match values.next() {
-                    Some(amount) => amount,
-                    None => { std::process::exit(1) },
-                };
-                println!("Expense: {}, Amount: {}", tag, amount);
-                budget.add_expense(String::from(tag), amount.parse::<f64>().unwrap());
-                if let Err(error) = write!(budget_file, "{}", serde_json::to_string(&budget).unwrap()) {
+                let parsed = format
+                    .map_err(|error| error.into())
+                    .and_then(|format| {
+                        for mapping in matches.get_many::<String>("map").into_iter().flatten() {
+                            columns.set(mapping)?;
+                        }
+                        let categorizer = Categorizer::new(&rules)?;
+                        let entries = parse_statement(format, &columns, File::open(statement_path)?)?;
+                        Ok::<_, Box<dyn std::error::Error>>((entries, categorizer))
+                    });
+                match parsed {
+                    Ok((entries, categorizer)) => {
+                        let imported = budget.import_statement(entries, &categorizer, account);
+                        println!(
+                            "##==>> Imported {} Transaction(s), Skipped {} Duplicate(s)",
+                            imported.added,
+                            imported.duplicates,
+                        );
+                        changed |= imported.added > 0;
+                    },
+                    Err(error) => {
+                        eprintln!("{}{}: {}", "##==>>>> ERROR: ".red(), statement_path.display(), error);
+                        std::process::exit(1)
+                    },
+                }
+            }
+
+            // Post Any Recurring Entries That Have Come Due
+            let posted = budget.apply_recurring(today);
+            if posted > 0 {
+                println!("##==>> Posted {} Recurring Transaction(s)", posted);
+                changed = true;
+            }
+
+            if changed {
+                if let Err(error) = store.save(&budget) {
                     eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
                     std::process::exit(1)
                 }
                 println!("##==>> Budget Updated!");
             }
+            if let Some(month) = matches.get_one::<String>("report") {
+                match parse_month(month) {
+                    Ok((year, month)) => print_report(&budget.report(year, month)),
+                    Err(error) => eprintln!("{}{}", "##==>>>> ERROR: ".red(), error),
+                }
+            }
             if matches.get_flag("summary") {
-                // Get JSON String From Budget File
-                let budget_file_string: String = fs::read_to_string(budget_path).unwrap().parse().unwrap();
-                // Have Serde Deserialize It Into Budget Object
-                let budget:DaveBudget = match serde_json::from_str(&budget_file_string) {
-                    Ok(budget) => budget,
-                    Err(error) => {
-                        eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
-                        std::process::exit(1)
-                    },
-                };
                 // Print Budget Information to Screen From Gotten
                 // Budget Object
-                println!("##==>> Amount of Income: ${}", budget.income);
-                println!("##==>> Current Budget: ${}", budget.get_balance());
-                if budget.expenses.len() > 0 {
-                    for (expense, amount) in budget.expenses {
-                        println!("##==>> Expense: {} - ${}", expense, amount);
-                    }
-                }
+                print_summary(&budget);
             }
         },
         Some(("dave-land", _matches)) => {
@@ -1342,13 +2546,87 @@ fn main() {
             }
         },
         Some(("currency", matches)) => {
-            if let Some(passed_amount) = matches.get_one::<f32>("amount") {
-                if let Some(passed_currency) = matches.get_one::<String>("currency") {
-                    if let Some(passed_conversion) = matches.get_one::<String>("convert") {
+            // Use Rates From the Configured Provider When There Is One,
+            // Otherwise the Rate Table in dave_conf/etc. The Network Is
+            // Only Used When Today's Rates Are Shown, Converted With or Recorded
+            let dated = matches.contains_id("date");
+            let wants_rates = matches.get_flag("refresh")
+                || matches.get_flag("record")
+                || (!dated && !matches.contains_id("chart") && (matches.get_flag("rates") || matches.contains_id("amount")));
+            let provider = if matches.get_flag("offline") || !wants_rates {
+                None
+            } else {
+                reader.rate_provider.provider()
+            };
+            let fetched = provider.and_then(|provider| {
+                let cache = RateCache::new(reader.rate_cache_path(), reader.rate_provider.ttl());
+                match cache.refresh(provider.as_ref(), chrono::Utc::now(), matches.get_flag("refresh")) {
+                    Ok(RateRefresh::Stale(table, fetched, error)) => {
+                        println!("##==>> Warning! {}", error);
+                        println!("##==>> Warning! Using Rates Cached {}\n", fetched.format("%Y-%m-%d %H:%M UTC"));
+                        Some(table)
+                    },
+                    Ok(RateRefresh::Fetched(table)) => {
+                        println!("##==>> Fetched Rates as of {} From {}", table.as_of, provider.name());
+                        Some(table)
+                    },
+                    Ok(refresh) => Some(refresh.table().clone()),
+                    Err(error) => {
+                        println!("##==>> Warning! {}", error);
+                        println!("##==>> Warning! Using the Offline Rate Table\n");
+                        None
+                    },
+                }
+            });
+            let table = match fetched.map_or_else(|| RateTable::load(&reader.rates_path()), Ok) {
+                Ok(table) => table,
+                Err(error) => {
+                    eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
+                    std::process::exit(1)
+                },
+            };
+            let history_path = reader.rate_history_path();
+            let mut history = match RateHistory::load(&history_path) {
+                Ok(history) => history,
+                Err(error) => {
+                    eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
+                    std::process::exit(1)
+                },
+            };
+            if matches.get_flag("record") {
+                history.record(table.clone());
+                match history.save(&history_path) {
+                    Ok(()) => println!("##==>> Stored Rates as of {} ({} Snapshots)", table.as_of, history.len()),
+                    Err(error) => eprintln!("{}{}", "##==>>>> ERROR: ".red(), error),
+                }
+            }
+            // Older Rates Come From the History
+            let table = match matches.get_one::<chrono::NaiveDate>("date") {
+                Some(date) => match history.on(*date) {
+                    Ok(table) => table,
+                    Err(error) => {
+                        eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
+                        std::process::exit(1)
+                    },
+                },
+                None => &table,
+            };
+            if let Some(mut codes) = matches.get_many::<Currency>("chart") {
+                let from = *codes.next().unwrap();
+                let to = *codes.next().unwrap();
+                let since = matches.get_one::<chrono::NaiveDate>("since").copied();
+                let until = matches.get_one::<chrono::NaiveDate>("until").copied();
+                print_rate_chart(&history.series(from, to, since, until), from, to);
+            } else if matches.get_flag("rates") {
+                print_rate_table(table);
+            } else if let Some(passed_amount) = matches.get_one::<Decimal>("amount") {
+                if let Some(passed_currency) = matches.get_one::<Currency>("currency") {
+                    if let Some(passed_conversion) = matches.get_one::<Currency>("convert") {
                         if let Err(error) = dave_currency_conv(
+                            table,
                             *passed_amount,
-                            passed_currency,
-                            passed_conversion,
+                            *passed_currency,
+                            *passed_conversion,
                         ) {
                             eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
                         }
@@ -1358,7 +2636,7 @@ fn main() {
                 } else {
                     println!("##==> A type of currency for your amount must be passed to the program. Try running 'dave currency --help' for more information");
                 }
-            } else {
+            } else if !matches.get_flag("record") {
                 println!("##==> An amount must be passed to the program. Try running 'dave currency --help' for more information");
             }
         },
@@ -1403,45 +2681,111 @@ fn main() {
             }
         },
         Some(("crypt", matches)) => {
-            if let Some(passed_file) = matches.get_one::<String>("filename") {
+            if let Some(("keygen", matches)) = matches.subcommand() {
+                match matches.get_one::<PathBuf>("output") {
+                    Some(path) => match generate_identity(path) {
+                        Ok(recipient) => {
+                            println!("##==>> Identity Written to {}", path.display());
+                            println!("##==>> Public Key: {}", recipient.to_string().bold());
+                        },
+                        Err(error) => {
+                            eprintln!("{}{}: {}", "##==>>>> ERROR: ".red(), path.display(), error);
+                            process::exit(1)
+                        },
+                    },
+                    None => print!("{}", format_identity(&age::x25519::Identity::generate())),
+                }
+            } else if let Some(passed_file) = matches.get_one::<String>("filename") {
                 let path = Path::new(passed_file);
-                let mut passphrase: String = thread_rng()
-                    .sample_iter(&Alphanumeric)
-                    .take(30)
-                    .map(char::from)
-                    .collect();
+                if !path.exists() {
+                    eprintln!("{}'{}'", "##==>>>> ERROR: File Not Found: ".red(), path.display());
+                    process::exit(1)
+                }
+                let output = matches.get_one::<PathBuf>("output").map(PathBuf::as_path);
+                let fail = |error: io::Error| -> ! {
+                    eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
+                    process::exit(1)
+                };
 
-                if path.exists() {
-                    let mut option: String = "".to_string();
-                    if let Some(passed_option) = matches.get_one::<String>("option") {
-                        if passed_option == "e" || passed_option == "encrypt" {
-                            option = "e".to_string();
-                        } else if passed_option == "d" || passed_option == "decrypt" {
-                            option = "d".to_string();
-                        }
+                // Decide Direction From the Flags, Falling Back to the Extension
+                let decrypt = matches.get_flag("decrypt")
+                    || matches.contains_id("identity")
+                    || (!matches.get_flag("encrypt")
+                        && path.is_file()
+                        && path.extension().is_some_and(|extension| extension == AGE_EXTENSION));
+
+                if decrypt {
+                    if path.is_dir() {
+                        fail(io::Error::new(io::ErrorKind::InvalidInput, "Only Files Can be Decrypted. Pass the Encrypted Archive, Not a Directory"))
                     }
-                    if let Some(passed_phrase) = matches.get_one::<String>("password") {
-                        passphrase = passed_phrase.to_string();
+                    let key = match matches.get_many::<PathBuf>("identity") {
+                        Some(paths) => {
+                            let mut identities = Vec::new();
+                            for identity_path in paths {
+                                identities.extend(read_identity_file(identity_path).unwrap_or_else(|error| fail(error)));
+                            }
+                            DecryptWith::Identities(identities)
+                        },
+                        None => DecryptWith::Passphrase(prompt_passphrase(false).unwrap_or_else(|error| fail(error))),
+                    };
+                    println!("##==> Decrypting {} ...", path.display());
+                    let archive = matches.get_flag("archive") || is_archive_path(path);
+                    match dave_decrypt(&key, path, output, archive) {
+                        Ok(Decrypted::File(written)) => println!("##==>> Decrypted to {}", written.display()),
+                        Ok(Decrypted::Directory(written, summary)) => println!(
+                            "##==>> Restored {} File(s) and {} Director(ies) to {}",
+                            summary.files,
+                            summary.directories,
+                            written.display(),
+                        ),
+                        Err(error) => fail(error),
                     }
-                    if option == "e" {
-                        println!("##==> Encrypting {} ...", path.display());
-                        match dave_encrypt(&passphrase, path) {
-                            Ok(encrypted_result) => {
-                                println!("##==>> Encrypted Result: {:?}", encrypted_result);
-                            },
-                            Err(error) => eprintln!("{}{}", "##==>>>> ERROR: ".red(), error),
+                } else {
+                    let mut recipients = Vec::new();
+                    for recipient in matches.get_many::<String>("recipient").unwrap_or_default() {
+                        recipients.push(parse_recipient(recipient).unwrap_or_else(|error| fail(error)));
+                    }
+                    for recipients_path in matches.get_many::<PathBuf>("recipients-file").unwrap_or_default() {
+                        recipients.extend(read_recipients_file(recipients_path).unwrap_or_else(|error| fail(error)));
+                    }
+                    let key = if recipients.is_empty() {
+                        if matches.contains_id("recipients-file") {
+                            fail(io::Error::new(io::ErrorKind::InvalidInput, "The Recipients File Has No Keys in It"))
                         }
-                    } else if option == "d" {
-                        println!("##==> Decrypting {} ...", path.display());
-                        match dave_decrypt(&passphrase, path) {
-                            Ok(decrypted_result) => {
-                                println!("##==>> Decrypted Result: {}", String::from_utf8_lossy(&decrypted_result));
+                        EncryptWith::Passphrase(prompt_passphrase(true).unwrap_or_else(|error| fail(error)))
+                    } else {
+                        EncryptWith::Recipients(recipients)
+                    };
+                    println!("##==> Encrypting {} ...", path.display());
+                    if path.is_dir() {
+                        match dave_encrypt_directory(&key, path, output, matches.get_flag("armor")) {
+                            Ok((written, summary)) => {
+                                if !is_archive_path(&written) {
+                                    println!(
+                                        "##==>> Warning! {} doesn't end in '.{}.{}', so pass --archive when decrypting it",
+                                        written.display(),
+                                        ARCHIVE_EXTENSION,
+                                        AGE_EXTENSION,
+                                    );
+                                }
+                                for skipped in &summary.skipped {
+                                    println!("##==>> Warning! Skipped {}, only files and directories are archived", skipped.display());
+                                }
+                                println!(
+                                    "##==>> Archived {} File(s) ({}) to {}",
+                                    summary.files,
+                                    ByteSize::b(summary.bytes),
+                                    written.display(),
+                                );
                             },
-                            Err(error) => eprintln!("{}{}", "##==>>>> ERROR: ".red(), error),
+                            Err(error) => fail(error),
+                        }
+                    } else {
+                        match dave_encrypt(&key, path, output, matches.get_flag("armor")) {
+                            Ok(written) => println!("##==>> Encrypted to {}", written.display()),
+                            Err(error) => fail(error),
                         }
                     }
-                } else {
-                    eprintln!("{}'{}'", "##==>>>> ERROR: File Not Found: ".red(), path.display());
                 }
             } else {
                 println!("##==> A file or path must be passed to the program. Try running 'dave crypt --help' for more information");
@@ -1449,7 +2793,7 @@ fn main() {
         },
         Some(("note", matches)) => {
             // Create or Get Database
-            let mut db: sled::Db = match sled::open("./dave_conf/var/daves_notes") {
+            let db = match DaveDatabase::open() {
                 Ok(db) => db,
                 Err(error) => {
                     eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
@@ -1457,9 +2801,19 @@ fn main() {
                 },
             };
 
+            // Bring Notes Written by Older Versions Up to Date
+            match db.migrate_notes() {
+                Ok(0) => {},
+                Ok(migrated) => println!("##==> Migrated {} Note(s) to the New Notes Format", migrated),
+                Err(error) => {
+                    eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
+                    std::process::exit(1)
+                },
+            }
+
             // Clear Database and Return
             if matches.get_flag("overwrite") {
-                match db.clear() {
+                match db.clear_notes() {
                     Ok(_) => {
                         println!("##==> Database Overwritten Successfully");
                         return
@@ -1470,135 +2824,455 @@ fn main() {
                     },
                 }
             }
-            if matches.get_flag("list") {
-                println!("##==> Current Notes:");
-                // Iterate Over Database 
-                // FIXME: so ID #'s are in Order
-                // Use sort with predicate
-                let iter_db = db.iter().values().rev();
-                for member in iter_db {
-                    if let Ok(ref value) = member {
-                        let dave_note: DaveNote = bincode::deserialize(&value).unwrap();
-                        let status = if dave_note.completed { "[X]" } else { "[ ]" };
-                        println!("{} {} - {}", status, dave_note.id, dave_note.title);
-                    }
-                }
-            }
+
+            // Gather Note Fields Shared by Add and Edit
+            let tags: Option<Vec<String>> = matches
+                .get_many::<String>("tag")
+                .map(|tags| tags.cloned().collect());
+            let priority = matches
+                .get_one::<String>("priority")
+                .map(|priority| NotePriority::from_str(priority).unwrap_or_default());
+            let due = match matches.get_one::<String>("due").map(|due| parse_due_date(due)) {
+                Some(Ok(due)) => Some(due),
+                Some(Err(error)) => {
+                    eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
+                    std::process::exit(1)
+                },
+                None => None,
+            };
+
             if let Some(note_label) = matches.get_one::<String>("add") {
                 println!("##==> Adding Note ...");
                 // Create New Note
                 let mut dave_note = DaveNote::new();
                 // Set Known Values for New Note
                 dave_note.title = note_label.to_string();
-                dave_note.completed = false;
-
-                // Determine ID number by counting members of database
-                // and adding 1
-                let count = db.len();
-                dave_note.id = count as u64 + 1;
+                if let Some(tags) = tags.clone() {
+                    dave_note.set_tags(tags);
+                }
+                dave_note.priority = priority.unwrap_or_default();
+                dave_note.due = due;
 
-                // Create Key Value by Hashing Label String
-                let mut hasher = sha2::Sha256::new();
-                hasher.update(&note_label);
-                let hash_value = hasher.finalize().to_vec();
+                // Store Note Under a Newly Generated ID
+                match db.add_note(dave_note) {
+                    Ok(dave_note) => println!("##==> Note #{} Added Successfully", dave_note.id),
+                    Err(error) => eprintln!("{}{}", "##==>>>> ERROR: ".red(), error),
+                }
+            }
+            if let Some(note_id) = matches.get_one::<u64>("edit") {
+                match db.get_note(*note_id) {
+                    Ok(Some(mut dave_note)) => {
+                        if let Some(title) = matches.get_one::<String>("title") {
+                            dave_note.title = title.to_string();
+                        }
+                        if let Some(tags) = tags.clone() {
+                            dave_note.set_tags(tags);
+                        }
+                        if let Some(priority) = priority {
+                            dave_note.priority = priority;
+                        }
+                        if due.is_some() {
+                            dave_note.due = due;
+                        } else if matches.get_flag("no-due") {
+                            dave_note.due = None;
+                        }
+                        dave_note.touch();
 
-                // Update the Database
-                if let Err(error) = DaveDatabase::update(&mut db, dave_note, &hash_value) {
-                    eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
+                        match db.update_note(&dave_note) {
+                            Ok(_) => println!("##==> Note Updated Successfully\n{}", dave_note),
+                            Err(error) => eprintln!("{}{}", "##==>>>> ERROR: ".red(), error),
+                        }
+                    },
+                    Ok(None) => println!("##==> No Note Found with ID #{}", note_id),
+                    Err(error) => eprintln!("{}{}", "##==>>>> ERROR: ".red(), error),
                 }
-                println!("##==> Note Added Successfully");
-            }
-            if let Some(note_id) = matches.get_one::<u64>("complete") {
-                // Iterate Over Members of Database and Find Matching ID Number
-                let iter_db = db.iter().values();
-                for member in iter_db {
-                    if let Ok(ref value) = member {
-                        // Deserialize Member Into DaveNote and Check ID #
-                        let mut dave_note: DaveNote = bincode::deserialize(&value).unwrap();
-                        // If ID #'s Equal, Correct Note Found
-                        if dave_note.id == *note_id {
+            }
+            for (arg, completed) in [("complete", true), ("uncomplete", false)] {
+                if let Some(note_id) = matches.get_one::<u64>(arg) {
+                    match db.get_note(*note_id) {
+                        Ok(Some(mut dave_note)) => {
                             println!("##==> Found Note with ID #{} - {}!", note_id, dave_note.title);
-                            if dave_note.completed {
+                            if dave_note.completed == completed {
                                 println!("##==> Warning! You already did that. You're senile");
-                                std::process::exit(0)
+                                continue
                             }
-                            // Set Note Completion to True
-                            dave_note.completed = true;
-
-                            // Create New Key for Updated Note by Hashing Label String
-                            let mut hasher = sha2::Sha256::new();
-                            hasher.update(&dave_note.title);
-                            let hash_value = hasher.finalize().to_vec();
-
-                            // Update Database with Modified Entry
-                            if let Err(error) = DaveDatabase::update(&mut db, dave_note, &hash_value) {
-                                eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
+                            // Set Note Completion and Update Database
+                            dave_note.completed = completed;
+                            dave_note.touch();
+                            match db.update_note(&dave_note) {
+                                Ok(_) => println!("##==> Note Updated Successfully"),
+                                Err(error) => eprintln!("{}{}", "##==>>>> ERROR: ".red(), error),
                             }
-                            println!("##==> Note Updated Successfully");
-                        }
+                        },
+                        Ok(None) => println!("##==> No Note Found with ID #{}", note_id),
+                        Err(error) => eprintln!("{}{}", "##==>>>> ERROR: ".red(), error),
                     }
                 }
             }
-        },
-        Some(("hash", matches)) => {
-            if let Some(passed_path) = matches.get_one::<String>("filename") {
-                let path = Path::new(passed_path);
-                if path.exists() {
-                    println!("{}", "##==> Path Exists! Continuing ...".green());
-                    // Deal With Determining Hashing Algorithm to Use
-                    let mut hash_type = HashType::Sha256;
-                    if let Some(hash_choice) = matches.get_one::<String>("hash-type") {
-                        let hash_choice_parsed: Result<HashType, DaveError> = HashType::from_str(hash_choice);
-                        match hash_choice_parsed {
-                            Ok(ht) => {
-                                hash_type = ht;
-                            },
-                            Err(error) => {
-                                eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
+            if let Some(note_id) = matches.get_one::<u64>("delete") {
+                match db.delete_note(*note_id) {
+                    Ok(Some(dave_note)) => println!("##==> Deleted Note #{} - {}", dave_note.id, dave_note.title),
+                    Ok(None) => println!("##==> No Note Found with ID #{}", note_id),
+                    Err(error) => eprintln!("{}{}", "##==>>>> ERROR: ".red(), error),
+                }
+            }
+            if let Some(mut values) = matches.get_many::<String>("import") {
+                let format = values.next().map(|format| NoteFormat::from_str(format));
+                let path = PathBuf::from(values.next().unwrap());
+                match format {
+                    Some(Ok(format)) => {
+                        let imported = File::open(&path)
+                            .map_err(|error| error.into())
+                            .and_then(|file| parse_notes(format, file));
+                        match imported {
+                            Ok(notes) => match db.import_notes(notes, format) {
+                                Ok(summary) => println!(
+                                    "##==> Imported Notes From {}: {} Added, {} Updated, {} Already Present",
+                                    path.display(),
+                                    summary.added,
+                                    summary.updated,
+                                    summary.unchanged,
+                                ),
+                                Err(error) => eprintln!("{}{}", "##==>>>> ERROR: ".red(), error),
                             },
+                            Err(error) => eprintln!("{}{}: {}", "##==>>>> ERROR: ".red(), path.display(), error),
                         }
+                    },
+                    Some(Err(error)) => eprintln!("{}{}", "##==>>>> ERROR: ".red(), error),
+                    None => {},
+                }
+            }
+            if let Some(mut values) = matches.get_many::<String>("export") {
+                let format = values.next().map(|format| NoteFormat::from_str(format));
+                let path = PathBuf::from(values.next().unwrap());
+                match (format, db.all_notes()) {
+                    (Some(Ok(format)), Ok(mut notes)) => {
+                        notes.sort_by(compare_notes);
+                        let exported = File::create(&path)
+                            .map_err(|error| error.into())
+                            .and_then(|file| export_notes(&notes, format, file));
+                        match exported {
+                            Ok(_) => println!("##==> Exported {} Note(s) to {}", notes.len(), path.display()),
+                            Err(error) => eprintln!("{}{}: {}", "##==>>>> ERROR: ".red(), path.display(), error),
+                        }
+                    },
+                    (Some(Err(error)), _) => eprintln!("{}{}", "##==>>>> ERROR: ".red(), error),
+                    (_, Err(error)) => eprintln!("{}{}", "##==>>>> ERROR: ".red(), error),
+                    (None, _) => {},
+                }
+            }
+            if matches.get_flag("list") {
+                let filter = NoteFilter {
+                    // Tags Given While Adding or Editing Aren't Filters
+                    tag: if matches.contains_id("add") || matches.contains_id("edit") {
+                        None
+                    } else {
+                        matches.get_one::<String>("tag").cloned()
+                    },
+                    overdue: matches.get_flag("overdue"),
+                    done: matches.get_flag("done"),
+                };
+                match db.all_notes() {
+                    Ok(notes) => {
+                        println!("##==> Current Notes:");
+                        let today = chrono::Local::now().date_naive();
+                        print_notes(&select_notes(notes, &filter, today));
+                    },
+                    Err(error) => eprintln!("{}{}", "##==>>>> ERROR: ".red(), error),
+                }
+            }
+        },
+        Some(("hash", matches)) => {
+            // Deal With Determining Hashing Algorithm to Use
+            let mut hash_type = reader.hash_type();
+            if let Some(hash_choice) = matches.get_one::<String>("hash-type") {
+                let hash_choice_parsed: Result<HashType, String> = HashType::from_str(hash_choice);
+                match hash_choice_parsed {
+                    Ok(ht) => {
+                        hash_type = ht;
+                    },
+                    Err(error) => {
+                        eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
+                    },
+                }
+            }
+            let hasher: Box<dyn Hasher> = match matches.get_one::<String>("hmac-key") {
+                Some(key) => match hash_type.keyed(key.as_bytes()) {
+                    Ok(hasher) => hasher,
+                    Err(error) => {
+                        eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
+                        process::exit(1)
+                    },
+                },
+                None => hash_type.into(),
+            };
+
+            if let Some(sums_path) = matches.get_one::<PathBuf>("check") {
+                let sums = match std::fs::read_to_string(sums_path) {
+                    Ok(sums) => sums,
+                    Err(error) => {
+                        eprintln!("{}{}: {}", "##==>>>> ERROR: ".red(), sums_path.display(), error);
+                        process::exit(1)
+                    },
+                };
+                let lines: Vec<&str> = sums.lines().filter(|line| !line.trim().is_empty()).collect();
+                let entries: Vec<SumEntry> = lines.iter().filter_map(|line| parse_sum_line(line)).collect();
+                let malformed = lines.len() - entries.len();
+
+                let statuses = check_sums(hasher.as_ref(), &entries);
+                let mut failed = 0;
+                let mut missing = 0;
+                for (entry, status) in entries.iter().zip(&statuses) {
+                    println!("{}: {}", entry.path.display(), status);
+                    match status {
+                        CheckStatus::Ok => {},
+                        CheckStatus::Missing => missing += 1,
+                        CheckStatus::Failed => failed += 1,
+                        CheckStatus::Unreadable(error) => {
+                            eprintln!("{}{}: {}", "##==>>>> ERROR: ".red(), entry.path.display(), error);
+                            failed += 1;
+                        },
+                    }
+                }
+                if malformed > 0 {
+                    eprintln!("##==>> Warning! {} Line(s) Are Improperly Formatted", malformed);
+                }
+                if failed > 0 {
+                    eprintln!("##==>> Warning! {} Computed Checksum(s) Did NOT Match", failed);
+                }
+                if missing > 0 {
+                    eprintln!("##==>> Warning! {} Listed File(s) Could Not be Found", missing);
+                }
+                if failed > 0 || missing > 0 || entries.is_empty() {
+                    process::exit(1)
+                }
+            } else if let Some(passed_paths) = matches.get_many::<PathBuf>("filename") {
+                // Hash Every File Found, Reporting Problems Without Stopping
+                let passed_paths: Vec<PathBuf> = passed_paths.cloned().collect();
+                let mut had_error = false;
+                let mut files = Vec::new();
+                for file in collect_files(&passed_paths) {
+                    match file {
+                        Ok(file) => files.push(file),
+                        Err((path, error)) => {
+                            eprintln!("{}{}: {}", "##==>>>> ERROR: ".red(), path.display(), error);
+                            had_error = true;
+                        },
                     }
-                    match hash_file(hash_type, passed_path.into()) {
-                        Ok(hash_result) => {
-                            let encoded_string = hex::encode(hash_result);
-                            println!("#==>> {} Checksum: {}", hash_type, encoded_string);
+                }
+                for (file, hash_result) in files.iter().zip(hash_files(hasher.as_ref(), &files)) {
+                    match hash_result {
+                        Ok(digest) => println!("{}", format_sum_line(&digest, file)),
+                        Err(error) => {
+                            eprintln!("{}{}: {}", "##==>>>> ERROR: ".red(), file.display(), error);
+                            had_error = true;
                         },
-                        Err(error) => eprintln!("{}{}", "##==>>>> ERROR: ".red(), error),
-                    };
-                } else {
-                    eprintln!("{}'{}'", "##==>>>> ERROR: File Not Found: ".red(), passed_path);
+                    }
+                }
+                if had_error {
+                    process::exit(1)
                 }
             } else {
                 println!("##==> A file or path must be passed to the program. Try running 'dave hash --help' for more information");
             }
         },
-        Some(("dgrep", matches)) => {
-            let mut option = String::new();
-            if let Some(gotten_option) = matches.get_one::<String>("option") {
-                option = gotten_option.to_string();
+        Some(("dupes", matches)) => {
+            let hash_type = match matches.get_one::<String>("hash-type").map(|choice| HashType::from_str(choice)) {
+                Some(Ok(hash_type)) => hash_type,
+                Some(Err(error)) => {
+                    eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
+                    process::exit(1)
+                },
+                None => reader.hash_type(),
+            };
+            let roots: Vec<PathBuf> = matches.get_many::<PathBuf>("directory").unwrap().cloned().collect();
+            let report = find_duplicates(&roots, hash_type);
+            for (path, error) in &report.errors {
+                eprintln!("{}{}: {}", "##==>>>> ERROR: ".red(), path.display(), error);
             }
+            print_dupes_report(&report);
 
-            if let Some(gotten_pattern) = matches.get_one::<String>("pattern") {
-                let pattern = gotten_pattern.to_string();
-                if let Some(gotten_filename) = matches.get_one::<String>("filename") {
-                    let filename = gotten_filename.to_string();
-                    if Path::new(&filename).exists() {
-                        let config = Config::new(
-                            option.to_string(),
-                            pattern.to_string(),
-                            filename.to_string(),
-                        ).unwrap_or_else(|error| {
-                            eprintln!("{}{}", "##==>>>> ERROR: ".red(), error.red());
+            let action = if matches.get_flag("hardlink") {
+                DupeAction::Hardlink
+            } else if matches.get_flag("delete") {
+                DupeAction::Delete
+            } else {
+                DupeAction::Report
+            };
+            if action != DupeAction::Report {
+                // Dry Run Unless Told Otherwise
+                let apply = matches.get_flag("apply");
+                let (verb, done) = match action {
+                    DupeAction::Hardlink => ("Link", "Linked"),
+                    _ => ("Delete", "Deleted"),
+                };
+                let mut failed = false;
+                for set in &report.sets {
+                    let results = resolve_set(set, if apply { action } else { DupeAction::Report }, hash_type);
+                    for (duplicate, result) in results {
+                        match (apply, result) {
+                            (false, _) => println!("##==> Would {}: {}", verb, duplicate.display()),
+                            (true, Ok(())) => println!("##==> {}: {}", done, duplicate.display()),
+                            (true, Err(error)) => {
+                                eprintln!("{}{}: {}", "##==>>>> ERROR: ".red(), duplicate.display(), error);
+                                failed = true;
+                            },
+                        }
+                    }
+                }
+                if !apply {
+                    println!("##==>> Dry Run Only. Pass '--apply' to Make These Changes");
+                }
+                if failed {
+                    process::exit(1)
+                }
+            }
+        },
+        Some(("integrity", matches)) => {
+            let db = match DaveDatabase::open() {
+                Ok(db) => db,
+                Err(error) => {
+                    eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
+                    process::exit(1)
+                },
+            };
+            match matches.subcommand() {
+                Some(("init", matches)) => {
+                    let directory = matches.get_one::<PathBuf>("directory").unwrap();
+                    let hash_type = match matches.get_one::<String>("hash-type").map(|choice| HashType::from_str(choice)) {
+                        Some(Ok(hash_type)) => hash_type,
+                        Some(Err(error)) => {
+                            eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
                             process::exit(1)
-                        });
-                        if let Err(error) = dave_grep::run(config) {
+                        },
+                        None => reader.hash_type(),
+                    };
+                    let (baseline, errors) = match scan_directory(directory, hash_type) {
+                        Ok(scan) => scan,
+                        Err(error) => {
+                            eprintln!("{}{}: {}", "##==>>>> ERROR: ".red(), directory.display(), error);
+                            process::exit(1)
+                        },
+                    };
+                    for (path, error) in &errors {
+                        eprintln!("{}{}: {}", "##==>>>> ERROR: ".red(), path.display(), error);
+                    }
+                    if let Err(error) = db.save_baseline(&baseline) {
+                        eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
+                        process::exit(1)
+                    }
+                    println!(
+                        "##==>> Recorded {} File(s) Under {} Using {}",
+                        baseline.files.len(),
+                        baseline.root.display(),
+                        baseline.hash_type,
+                    );
+                },
+                Some(("check", matches)) => {
+                    // Check One Directory, or Every Baseline on Record
+                    let baselines = match matches.get_one::<PathBuf>("directory") {
+                        Some(directory) => {
+                            let root = directory.canonicalize().unwrap_or_else(|_| directory.clone());
+                            match db.baseline(&root) {
+                                Ok(Some(baseline)) => Ok(vec![baseline]),
+                                Ok(None) => {
+                                    eprintln!(
+                                        "{}No Baseline for {}. Run 'dave integrity init' First",
+                                        "##==>>>> ERROR: ".red(),
+                                        root.display(),
+                                    );
+                                    process::exit(1)
+                                },
+                                Err(error) => Err(error),
+                            }
+                        },
+                        None => db.all_baselines(),
+                    };
+                    let baselines = match baselines {
+                        Ok(baselines) => baselines,
+                        Err(error) => {
                             eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
+                            process::exit(1)
+                        },
+                    };
+
+                    let mut reports = Vec::new();
+                    for baseline in &baselines {
+                        match check_baseline(baseline) {
+                            Ok(report) => reports.push(report),
+                            Err(error) => {
+                                eprintln!("{}{}: {}", "##==>>>> ERROR: ".red(), baseline.root.display(), error);
+                                process::exit(1)
+                            },
+                        }
+                    }
+                    if matches.get_flag("json") {
+                        match serde_json::to_string_pretty(&reports) {
+                            Ok(json) => println!("{}", json),
+                            Err(error) => eprintln!("{}{}", "##==>>>> ERROR: ".red(), error),
                         }
+                    } else if reports.is_empty() {
+                        println!("##==>> No Baselines Recorded. Run 'dave integrity init <dir>' First");
                     } else {
-                        eprintln!("{}'{}'", "##==>>>> ERROR: File Not Found: ".red(), filename);
+                        for report in &reports {
+                            print_integrity_report(report);
+                        }
                     }
-                } else {
-                    println!("##==> A file or path must be passed to DGREP. Try running 'dave dgrep --help' for more information");
+                    if reports.iter().any(|report| !report.is_clean()) {
+                        process::exit(1)
+                    }
+                },
+                _ => unreachable!("clap requires an integrity subcommand"),
+            }
+        },
+        Some(("dgrep", matches)) => {
+            let mut option = String::new();
+            if let Some(gotten_option) = matches.get_one::<String>("option") {
+                option = gotten_option.to_string();
+            }
+
+            if let Some(gotten_pattern) = matches.get_one::<String>("pattern") {
+                let paths: Vec<PathBuf> = matches.get_many::<PathBuf>("paths").unwrap_or_default().cloned().collect();
+                for missing in paths.iter().filter(|path| !path.exists()) {
+                    eprintln!("{}'{}'", "##==>>>> ERROR: File Not Found: ".red(), missing.display());
+                }
+                let mut config = Config::new(
+                    option,
+                    gotten_pattern.to_string(),
+                    paths.into_iter().filter(|path| path.exists()).collect(),
+                ).unwrap_or_else(|error| {
+                    eprintln!("{}{}", "##==>>>> ERROR: ".red(), error.red());
+                    process::exit(1)
+                });
+                config.case_sensitive &= !matches.get_flag("ignore-case");
+                config.regex |= matches.get_flag("regex");
+                config.whole_word = matches.get_flag("word");
+                config.invert = matches.get_flag("invert");
+                let context = matches.get_one::<usize>("context").copied().unwrap_or(0);
+                config.before_context = matches.get_one::<usize>("before").copied().unwrap_or(context);
+                config.after_context = matches.get_one::<usize>("after").copied().unwrap_or(context);
+                if matches.get_flag("count") {
+                    config.mode = OutputMode::Count;
+                } else if matches.get_flag("files-with-matches") {
+                    config.mode = OutputMode::FilesWithMatches;
+                }
+                config.color = io::stdout().is_terminal();
+                config.search = search_options(matches);
+                let result = match matches.get_one::<String>("replace") {
+                    Some(template) => {
+                        let mode = if matches.get_flag("interactive") {
+                            ReplaceMode::Interactive
+                        } else if matches.get_flag("write") {
+                            ReplaceMode::Write
+                        } else {
+                            ReplaceMode::Preview
+                        };
+                        dave_grep::replace(config, template, mode)
+                    },
+                    None => dave_grep::run(config),
+                };
+                if let Err(error) = result {
+                    eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
                 }
             } else {
                 println!("##==> A match pattern must be passed to DGREP. Try running 'dave dgrep --help' for more information");
@@ -1608,7 +3282,7 @@ fn main() {
     }
 
     let time = start.elapsed();
-    println!(
+    eprintln!(
         "\n##==> Dave Ran For {}.{}s",
         time.as_secs(),
         time.subsec_millis(),
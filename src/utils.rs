@@ -1,15 +1,15 @@
 use std::io::{
     self,
-    BufReader,
     prelude::*,
 };
 use std::f32::consts::*;
 use std::fmt;
-use std::fs::{
+use std::fs;
+use std::path::Path;
+use crate::dave_search::{
     self,
-    File,
+    SearchOptions,
 };
-use std::path::Path;
 use bevy::{
     input::mouse::MouseMotion,
     prelude::*,
@@ -44,6 +44,15 @@ pub fn generate_random_number(min_value: u16, max_value: u16) -> u16 {
     random_value
 }
 
+// Ask a Yes or No Question. Anything But Yes, Even No Answer at All, Is No
+pub fn confirm(question: &str) -> io::Result<bool> {
+    print!("{} [y/N]: ", question);
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
 pub fn get_file_size(path: &Path) -> io::Result<()> {
     if path.exists() {
         let file_metadata = fs::metadata(path)?;
@@ -105,46 +114,45 @@ pub fn dave_ls_main(dir: String) -> io::Result<()> {
     Ok(())
 }
 
-pub fn dave_find_main(pattern: String, dir: &Path, verbose: u32) -> io::Result<()> {
-    for entry in WalkDir::new(dir).follow_links(true).into_iter().filter_map(|e| e.ok()) {
-        if entry.metadata()?.is_file() && !entry.metadata()?.permissions().readonly() {
-            let file = File::open(entry.path())?;
-            let reader = BufReader::new(file);
-            let mut found = false;
-            let mut line_number = 0;
-
-            for mut line in reader.lines() {
-                line_number += 1;
-                let line_text = match line.as_mut() {
-                    Ok(data) => data,
-                    Err(error) => {
-                        if verbose == 3 {
-                            eprintln!("{}{}", "##==>>>> ERROR: ".red(), error);
-                        }
-                        break
-                    },
-                };
-                if line_text.contains(&pattern) {
-                    println!(
-                        "##==>> Pattern '{}' WAS found on line #{} in '{}'",
-                        pattern,
-                        line_number,
-                        entry.path().display(),
-                    );
-                    if verbose >= 1 {
-                        println!("##==> Line #{}: '{}'", line_number, line_text);
-                    }
-                    found = true;
-                }
+pub fn dave_find_main(pattern: String, dir: &Path, verbose: u32, options: &SearchOptions) -> io::Result<()> {
+    let results = dave_search::search(&[dir.to_path_buf()], options, |_, contents| {
+        contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| line.contains(&pattern))
+            .map(|(index, line)| (index + 1, line.to_string()))
+            .collect::<Vec<_>>()
+    })
+    .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error.to_string()))?;
+
+    for (path, found) in &results.files {
+        for (line_number, line_text) in found {
+            println!(
+                "##==>> Pattern '{}' WAS found on line #{} in '{}'",
+                pattern,
+                line_number,
+                path.display(),
+            );
+            if verbose >= 1 {
+                println!("##==> Line #{}: '{}'", line_number, line_text);
             }
+        }
 
-            if found == false && verbose >= 2 {
-                println!(
-                    "##==>> Pattern '{}' was NOT found in '{}'",
-                    pattern,
-                    entry.path().display(),
-                );
-            }
+        if found.is_empty() && verbose >= 2 {
+            println!(
+                "##==>> Pattern '{}' was NOT found in '{}'",
+                pattern,
+                path.display(),
+            );
+        }
+    }
+
+    if verbose == 3 {
+        for path in &results.binaries {
+            println!("##==> Skipped Binary File '{}'", path.display());
+        }
+        for (path, error) in &results.errors {
+            eprintln!("{}{}: {}", "##==>>>> ERROR: ".red(), path.display(), error);
         }
     }
     Ok(())